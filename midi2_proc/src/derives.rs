@@ -54,6 +54,32 @@ pub fn packets(item: TokenStream1) -> TokenStream1 {
     .into()
 }
 
+pub fn remappable(item: TokenStream1) -> TokenStream1 {
+    let input = parse_macro_input!(item as ItemEnum);
+    let ident = &input.ident;
+    let mut match_arms = TokenStream::new();
+    for variant in &input.variants {
+        let variant_ident = &variant.ident;
+        match_arms.extend(quote! {
+            #variant_ident(m) => m.remap(remap),
+        });
+    }
+    quote! {
+        impl<B: crate::buffer::Ump> crate::traits::Remappable<B> for #ident<B> {
+            fn remap(&mut self, remap: &crate::router::Remap)
+            where
+                B: crate::buffer::BufferMut
+            {
+                use #ident::*;
+                match self {
+                    #match_arms
+                }
+            }
+        }
+    }
+    .into()
+}
+
 pub fn from_bytes(item: TokenStream1) -> TokenStream1 {
     let input = parse_macro_input!(item as ItemEnum);
     let ident = &input.ident;
@@ -407,6 +407,16 @@ fn deref_sysex7_impl(root_ident: &syn::Ident) -> TokenStream {
     }
 }
 
+fn into_sysex7_impl(root_ident: &syn::Ident) -> TokenStream {
+    quote! {
+        impl<const VERSION: u8, B: crate::buffer::Bytes> core::convert::From<#root_ident<VERSION, B>> for crate::sysex7::Sysex7<B> {
+            fn from(other: #root_ident<VERSION, B>) -> Self {
+                other.0
+            }
+        }
+    }
+}
+
 fn message_impl(root_ident: &syn::Ident, properties: &Vec<Property>) -> TokenStream {
     let mut methods = TokenStream::new();
     for property in properties.iter().filter(|p| !p.constant) {
@@ -450,7 +460,7 @@ fn ci_impl(root_ident: &syn::Ident) -> TokenStream {
         &make_property(
             syn::Ident::new("source", proc_macro2::Span::call_site()),
             syn::parse_quote! { crate::ci::common_properties::SourceProperty },
-            syn::parse_quote! { ux::u28 },
+            syn::parse_quote! { crate::ci::muid::Muid },
         ),
         false,
     ));
@@ -458,7 +468,7 @@ fn ci_impl(root_ident: &syn::Ident) -> TokenStream {
         &make_property(
             syn::Ident::new("destination", proc_macro2::Span::call_site()),
             syn::parse_quote! { crate::ci::common_properties::DestinationProperty },
-            syn::parse_quote! { ux::u28 },
+            syn::parse_quote! { crate::ci::muid::Muid },
         ),
         false,
     ));
@@ -552,6 +562,7 @@ pub fn generate_ci(attrs: TokenStream1, item: TokenStream1) -> TokenStream1 {
     let try_new_impl = try_new_impl(root_ident, &properties);
     let ci_version_impls = ci_version_impls(root_ident, &args);
     let deref_sysex7_impl = deref_sysex7_impl(root_ident);
+    let into_sysex7_impl = into_sysex7_impl(root_ident);
     let message_impl = message_impl(root_ident, &properties);
     let ci_impl = ci_impl(root_ident);
     let try_from_slice_impl = try_from_slice_impl(root_ident, &properties);
@@ -568,6 +579,7 @@ pub fn generate_ci(attrs: TokenStream1, item: TokenStream1) -> TokenStream1 {
         #new_impl
         #try_new_impl
         #deref_sysex7_impl
+        #into_sysex7_impl
         #message_impl
         #ci_impl
         #try_from_slice_impl
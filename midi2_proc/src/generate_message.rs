@@ -93,6 +93,9 @@ impl Property {
     pub fn is_channel(&self) -> bool {
         self.ident == "channel"
     }
+    pub fn is_optional_channel(&self) -> bool {
+        self.ident == "optional_channel"
+    }
     pub fn is_sysex_payload(&self) -> bool {
         self.ident == "sysex_payload"
     }
@@ -563,6 +566,42 @@ fn channeled_impl(
     }
 }
 
+fn remappable_impl(root_ident: &syn::Ident, properties: &[Property]) -> TokenStream {
+    let mut remap_properties = TokenStream::new();
+    if let Some(property) = properties.iter().find(|p| p.is_group()) {
+        let meta_type = &property.meta_type;
+        remap_properties.extend(quote! {
+            let group = <#meta_type as crate::detail::property::ReadProperty<B>>::read(self.buffer_access());
+            <#meta_type as crate::detail::property::WriteProperty<B>>::write(self.buffer_access_mut(), remap.group(group));
+        });
+    }
+    if let Some(property) = properties.iter().find(|p| p.is_channel()) {
+        let meta_type = &property.meta_type;
+        remap_properties.extend(quote! {
+            let channel = <#meta_type as crate::detail::property::ReadProperty<B>>::read(self.buffer_access());
+            <#meta_type as crate::detail::property::WriteProperty<B>>::write(self.buffer_access_mut(), remap.channel(channel));
+        });
+    }
+    if let Some(property) = properties.iter().find(|p| p.is_optional_channel()) {
+        let meta_type = &property.meta_type;
+        remap_properties.extend(quote! {
+            let channel = <#meta_type as crate::detail::property::ReadProperty<B>>::read(self.buffer_access());
+            <#meta_type as crate::detail::property::WriteProperty<B>>::write(self.buffer_access_mut(), channel.map(|c| remap.channel(c)));
+        });
+    }
+    quote! {
+        impl<B: crate::buffer::Ump> crate::traits::Remappable<B> for #root_ident<B> {
+            #[allow(unused_variables)]
+            fn remap(&mut self, remap: &crate::router::Remap)
+            where
+                B: crate::buffer::BufferMut
+            {
+                #remap_properties
+            }
+        }
+    }
+}
+
 fn from_bytes_impl(root_ident: &syn::Ident, properties: &Vec<Property>) -> TokenStream {
     let convert_properties = convert_properties(properties, &quote! { B });
     quote! {
@@ -784,6 +823,7 @@ pub fn generate_message(attrs: TokenStream1, item: TokenStream1) -> TokenStream1
         Representation::Ump | Representation::UmpOrBytes
     ) {
         tokens.extend(packets_impl(root_ident));
+        tokens.extend(remappable_impl(root_ident, &properties));
     }
     if let Some(via_type) = args.via.as_ref() {
         match args.representation() {
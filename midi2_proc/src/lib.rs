@@ -35,6 +35,11 @@ pub fn derive_channeled(item: TokenStream1) -> TokenStream1 {
     derives::channeled(item)
 }
 
+#[proc_macro_derive(Remappable)]
+pub fn derive_remappable(item: TokenStream1) -> TokenStream1 {
+    derives::remappable(item)
+}
+
 #[proc_macro_derive(Debug)]
 pub fn derive_ump_debug(item: TokenStream1) -> TokenStream1 {
     derives::debug(item)
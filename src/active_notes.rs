@@ -0,0 +1,472 @@
+//! Tracking of sounding notes, to release them when a source goes away.
+//!
+//! ```rust
+//! use midi2::{prelude::*, active_notes::ActiveNotes};
+//!
+//! let mut notes = ActiveNotes::<1>::new();
+//!
+//! let note_on = channel_voice2::ChannelVoice2::try_from(&[0x4293_3C00, 0xFFFF_0000][..]).unwrap();
+//! notes.receive_channel_voice2(&note_on);
+//! assert!(notes.is_active(u4::new(0x2), u4::new(0x3), u7::new(0x3C)));
+//!
+//! let note_offs = notes.note_offs_midi2().collect::<Vec<_>>();
+//! assert_eq!(note_offs.len(), 1);
+//! assert_eq!(note_offs[0].data(), &[0x4283_3C00, 0x0000_0000]);
+//! ```
+
+use crate::ux::{u4, u7};
+
+const NUMBER_OF_CHANNELS: usize = 16 * 16;
+const ALL_SOUND_OFF: u8 = 120;
+const ALL_NOTES_OFF: u8 = 123;
+
+/// The notes currently held on every group and channel.
+///
+/// Each note keeps a count of `COUNT_BITS` bits, so that stacked note ons
+/// on the same note number need as many note offs before the note is released.
+/// With a single bit the tracker doesn't count, and any note off releases the note.
+/// Counts saturate at `2^COUNT_BITS - 1`.
+///
+/// The state is a fixed size bitset of `COUNT_BITS * 4` KiB.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ActiveNotes<const COUNT_BITS: usize = 1> {
+    planes: [[u128; COUNT_BITS]; NUMBER_OF_CHANNELS],
+}
+
+impl<const COUNT_BITS: usize> core::default::Default for ActiveNotes<COUNT_BITS> {
+    fn default() -> Self {
+        let () = Self::VALID;
+        ActiveNotes {
+            planes: [[0; COUNT_BITS]; NUMBER_OF_CHANNELS],
+        }
+    }
+}
+
+impl<const COUNT_BITS: usize> ActiveNotes<COUNT_BITS> {
+    const VALID: () = assert!(0 < COUNT_BITS && COUNT_BITS <= 8);
+    const MAX_COUNT: u8 = ((1_u16 << COUNT_BITS) - 1) as u8;
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_active(&self, group: u4, channel: u4, note: u7) -> bool {
+        self.count(group, channel, note) != 0
+    }
+
+    /// The number of note ons which haven't been matched by a note off.
+    pub fn count(&self, group: u4, channel: u4, note: u7) -> u8 {
+        let planes = &self.planes[address(group, channel)];
+        let bit = 1_u128 << u8::from(note);
+        planes
+            .iter()
+            .enumerate()
+            .filter(|(_, plane)| *plane & bit != 0)
+            .fold(0, |count, (i, _)| count | (1 << i))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        (0..NUMBER_OF_CHANNELS).all(|address| self.active_bits(address) == 0)
+    }
+
+    pub fn note_on(&mut self, group: u4, channel: u4, note: u7) {
+        let count = self.count(group, channel, note);
+        if count < Self::MAX_COUNT {
+            self.set_count(group, channel, note, count + 1);
+        }
+    }
+
+    pub fn note_off(&mut self, group: u4, channel: u4, note: u7) {
+        let count = self.count(group, channel, note);
+        if count > 0 {
+            self.set_count(group, channel, note, count - 1);
+        }
+    }
+
+    /// Release every note on the channel.
+    pub fn clear_channel(&mut self, group: u4, channel: u4) {
+        self.planes[address(group, channel)] = [0; COUNT_BITS];
+    }
+
+    /// Release every note.
+    pub fn clear(&mut self) {
+        self.planes = [[0; COUNT_BITS]; NUMBER_OF_CHANNELS];
+    }
+
+    /// Every active note as `(group, channel, note)`,
+    /// ordered by group, then channel, then note number.
+    pub fn active_notes(&self) -> ActiveNotesIterator<'_, COUNT_BITS> {
+        ActiveNotesIterator {
+            notes: self,
+            address: 0,
+            note: 0,
+        }
+    }
+
+    /// Every group and channel with at least one active note as `(group, channel)`.
+    pub fn active_channels(&self) -> impl Iterator<Item = (u4, u4)> + '_ {
+        (0..NUMBER_OF_CHANNELS)
+            .filter(|address| self.active_bits(*address) != 0)
+            .map(from_address)
+    }
+
+    /// Follow the note ons and note offs of a MIDI 1.0 message.
+    ///
+    /// Note ons with zero velocity are note offs,
+    /// and All Sound Off and All Notes Off release the whole channel.
+    #[cfg(feature = "channel-voice1")]
+    pub fn receive_channel_voice1<B: crate::buffer::Ump>(
+        &mut self,
+        message: &crate::channel_voice1::ChannelVoice1<B>,
+    ) {
+        use crate::{
+            channel_voice1::ChannelVoice1::*,
+            traits::{Channeled, Grouped},
+        };
+
+        let group = message.group();
+        let channel = message.channel();
+        match message {
+            NoteOn(m) if m.velocity() == u7::new(0) => {
+                self.note_off(group, channel, m.note_number())
+            }
+            NoteOn(m) => self.note_on(group, channel, m.note_number()),
+            NoteOff(m) => self.note_off(group, channel, m.note_number()),
+            ControlChange(m) if releases_channel(m.control()) => self.clear_channel(group, channel),
+            _ => {}
+        }
+    }
+
+    /// Follow the note ons and note offs of a MIDI 2.0 message.
+    ///
+    /// All Sound Off and All Notes Off release the whole channel.
+    #[cfg(feature = "channel-voice2")]
+    pub fn receive_channel_voice2<B: crate::buffer::Ump>(
+        &mut self,
+        message: &crate::channel_voice2::ChannelVoice2<B>,
+    ) {
+        use crate::{
+            channel_voice2::ChannelVoice2::*,
+            traits::{Channeled, Grouped},
+        };
+
+        let group = message.group();
+        let channel = message.channel();
+        match message {
+            NoteOn(m) => self.note_on(group, channel, m.note_number()),
+            NoteOff(m) => self.note_off(group, channel, m.note_number()),
+            ControlChange(m) if releases_channel(m.control()) => self.clear_channel(group, channel),
+            _ => {}
+        }
+    }
+
+    /// A MIDI 1.0 note off for every active note.
+    #[cfg(feature = "channel-voice1")]
+    pub fn note_offs_midi1(
+        &self,
+    ) -> impl Iterator<Item = crate::channel_voice1::NoteOff<[u32; 4]>> + '_ {
+        use crate::traits::{Channeled, Grouped};
+
+        self.active_notes().map(|(group, channel, note)| {
+            let mut message = crate::channel_voice1::NoteOff::<[u32; 4]>::new();
+            message.set_group(group);
+            message.set_channel(channel);
+            message.set_note_number(note);
+            message
+        })
+    }
+
+    /// A MIDI 2.0 note off for every active note.
+    #[cfg(feature = "channel-voice2")]
+    pub fn note_offs_midi2(
+        &self,
+    ) -> impl Iterator<Item = crate::channel_voice2::NoteOff<[u32; 4]>> + '_ {
+        use crate::traits::{Channeled, Grouped};
+
+        self.active_notes().map(|(group, channel, note)| {
+            let mut message = crate::channel_voice2::NoteOff::<[u32; 4]>::new();
+            message.set_group(group);
+            message.set_channel(channel);
+            message.set_note_number(note);
+            message
+        })
+    }
+
+    /// A MIDI 1.0 All Notes Off control change for every channel with active notes.
+    #[cfg(feature = "channel-voice1")]
+    pub fn all_notes_off_midi1(
+        &self,
+    ) -> impl Iterator<Item = crate::channel_voice1::ControlChange<[u32; 4]>> + '_ {
+        self.active_channels()
+            .map(|(group, channel)| control_change_midi1(group, channel, ALL_NOTES_OFF))
+    }
+
+    /// A MIDI 1.0 All Sound Off control change for every channel with active notes.
+    #[cfg(feature = "channel-voice1")]
+    pub fn all_sound_off_midi1(
+        &self,
+    ) -> impl Iterator<Item = crate::channel_voice1::ControlChange<[u32; 4]>> + '_ {
+        self.active_channels()
+            .map(|(group, channel)| control_change_midi1(group, channel, ALL_SOUND_OFF))
+    }
+
+    /// A MIDI 2.0 All Notes Off control change for every channel with active notes.
+    #[cfg(feature = "channel-voice2")]
+    pub fn all_notes_off_midi2(
+        &self,
+    ) -> impl Iterator<Item = crate::channel_voice2::ControlChange<[u32; 4]>> + '_ {
+        self.active_channels()
+            .map(|(group, channel)| control_change_midi2(group, channel, ALL_NOTES_OFF))
+    }
+
+    /// A MIDI 2.0 All Sound Off control change for every channel with active notes.
+    #[cfg(feature = "channel-voice2")]
+    pub fn all_sound_off_midi2(
+        &self,
+    ) -> impl Iterator<Item = crate::channel_voice2::ControlChange<[u32; 4]>> + '_ {
+        self.active_channels()
+            .map(|(group, channel)| control_change_midi2(group, channel, ALL_SOUND_OFF))
+    }
+
+    fn set_count(&mut self, group: u4, channel: u4, note: u7, count: u8) {
+        let planes = &mut self.planes[address(group, channel)];
+        let bit = 1_u128 << u8::from(note);
+        for (i, plane) in planes.iter_mut().enumerate() {
+            if count & (1 << i) != 0 {
+                *plane |= bit;
+            } else {
+                *plane &= !bit;
+            }
+        }
+    }
+
+    fn active_bits(&self, address: usize) -> u128 {
+        self.planes[address]
+            .iter()
+            .fold(0, |bits, plane| bits | plane)
+    }
+}
+
+/// Iterator over the notes of [ActiveNotes].
+#[derive(Debug, Clone)]
+pub struct ActiveNotesIterator<'a, const COUNT_BITS: usize> {
+    notes: &'a ActiveNotes<COUNT_BITS>,
+    address: usize,
+    note: u32,
+}
+
+impl<const COUNT_BITS: usize> core::iter::Iterator for ActiveNotesIterator<'_, COUNT_BITS> {
+    type Item = (u4, u4, u7);
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.address < NUMBER_OF_CHANNELS {
+            let remaining = self
+                .notes
+                .active_bits(self.address)
+                .checked_shr(self.note)
+                .unwrap_or(0);
+            if remaining != 0 {
+                let note = self.note + remaining.trailing_zeros();
+                self.note = note + 1;
+                let (group, channel) = from_address(self.address);
+                return Some((group, channel, u7::new(note as u8)));
+            }
+            self.address += 1;
+            self.note = 0;
+        }
+        None
+    }
+}
+
+impl<const COUNT_BITS: usize> core::iter::FusedIterator for ActiveNotesIterator<'_, COUNT_BITS> {}
+
+fn address(group: u4, channel: u4) -> usize {
+    u8::from(group) as usize * 16 + u8::from(channel) as usize
+}
+
+fn from_address(address: usize) -> (u4, u4) {
+    (u4::new((address / 16) as u8), u4::new((address % 16) as u8))
+}
+
+#[allow(dead_code)]
+fn releases_channel(control: u7) -> bool {
+    matches!(u8::from(control), ALL_SOUND_OFF | ALL_NOTES_OFF)
+}
+
+#[cfg(feature = "channel-voice1")]
+fn control_change_midi1(
+    group: u4,
+    channel: u4,
+    control: u8,
+) -> crate::channel_voice1::ControlChange<[u32; 4]> {
+    use crate::traits::{Channeled, Grouped};
+
+    let mut message = crate::channel_voice1::ControlChange::<[u32; 4]>::new();
+    message.set_group(group);
+    message.set_channel(channel);
+    message.set_control(u7::new(control));
+    message
+}
+
+#[cfg(feature = "channel-voice2")]
+fn control_change_midi2(
+    group: u4,
+    channel: u4,
+    control: u8,
+) -> crate::channel_voice2::ControlChange<[u32; 4]> {
+    use crate::traits::{Channeled, Grouped};
+
+    let mut message = crate::channel_voice2::ControlChange::<[u32; 4]>::new();
+    message.set_group(group);
+    message.set_channel(channel);
+    message.set_control(u7::new(control));
+    message
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn note_on_and_off() {
+        let mut notes = ActiveNotes::<1>::new();
+        notes.note_on(u4::new(0x1), u4::new(0x2), u7::new(0x3C));
+        notes.note_on(u4::new(0x1), u4::new(0x2), u7::new(0x3C));
+        assert!(notes.is_active(u4::new(0x1), u4::new(0x2), u7::new(0x3C)));
+        assert!(!notes.is_active(u4::new(0x0), u4::new(0x2), u7::new(0x3C)));
+        notes.note_off(u4::new(0x1), u4::new(0x2), u7::new(0x3C));
+        assert!(notes.is_empty());
+    }
+
+    #[test]
+    fn stacked_note_ons() {
+        let mut notes = ActiveNotes::<2>::new();
+        let (group, channel, note) = (u4::new(0x0), u4::new(0x0), u7::new(0x7F));
+        for _ in 0..4 {
+            notes.note_on(group, channel, note);
+        }
+        assert_eq!(notes.count(group, channel, note), 3);
+        notes.note_off(group, channel, note);
+        notes.note_off(group, channel, note);
+        assert!(notes.is_active(group, channel, note));
+        notes.note_off(group, channel, note);
+        assert!(!notes.is_active(group, channel, note));
+        notes.note_off(group, channel, note);
+        assert_eq!(notes.count(group, channel, note), 0);
+    }
+
+    #[test]
+    fn active_notes() {
+        let mut notes = ActiveNotes::<1>::new();
+        notes.note_on(u4::new(0xF), u4::new(0xF), u7::new(0x7F));
+        notes.note_on(u4::new(0x0), u4::new(0x1), u7::new(0x00));
+        notes.note_on(u4::new(0x0), u4::new(0x1), u7::new(0x40));
+        assert_eq!(
+            notes.active_notes().collect::<std::vec::Vec<_>>(),
+            std::vec![
+                (u4::new(0x0), u4::new(0x1), u7::new(0x00)),
+                (u4::new(0x0), u4::new(0x1), u7::new(0x40)),
+                (u4::new(0xF), u4::new(0xF), u7::new(0x7F)),
+            ]
+        );
+        assert_eq!(
+            notes.active_channels().collect::<std::vec::Vec<_>>(),
+            std::vec![(u4::new(0x0), u4::new(0x1)), (u4::new(0xF), u4::new(0xF))]
+        );
+    }
+
+    #[test]
+    fn clear_channel() {
+        let mut notes = ActiveNotes::<1>::new();
+        notes.note_on(u4::new(0x0), u4::new(0x1), u7::new(0x40));
+        notes.note_on(u4::new(0x0), u4::new(0x2), u7::new(0x40));
+        notes.clear_channel(u4::new(0x0), u4::new(0x1));
+        assert_eq!(notes.active_notes().count(), 1);
+        notes.clear();
+        assert!(notes.is_empty());
+    }
+
+    #[cfg(feature = "channel-voice1")]
+    #[test]
+    fn channel_voice1() {
+        use crate::channel_voice1::ChannelVoice1;
+
+        let mut notes = ActiveNotes::<1>::new();
+        notes.receive_channel_voice1(&ChannelVoice1::try_from(&[0x2391_3C40_u32][..]).unwrap());
+        notes.receive_channel_voice1(&ChannelVoice1::try_from(&[0x2391_3E40_u32][..]).unwrap());
+        assert_eq!(notes.active_notes().count(), 2);
+        // note on with zero velocity
+        notes.receive_channel_voice1(&ChannelVoice1::try_from(&[0x2391_3C00_u32][..]).unwrap());
+        assert_eq!(notes.active_notes().count(), 1);
+        // all notes off
+        notes.receive_channel_voice1(&ChannelVoice1::try_from(&[0x23B1_7B00_u32][..]).unwrap());
+        assert!(notes.is_empty());
+    }
+
+    #[cfg(feature = "channel-voice1")]
+    #[test]
+    fn midi1_panic() {
+        use crate::traits::Data;
+
+        let mut notes = ActiveNotes::<1>::new();
+        notes.note_on(u4::new(0x3), u4::new(0x1), u7::new(0x3C));
+        notes.note_on(u4::new(0x3), u4::new(0x1), u7::new(0x3E));
+        assert_eq!(
+            notes
+                .note_offs_midi1()
+                .map(|m| m.data()[0])
+                .collect::<std::vec::Vec<_>>(),
+            std::vec![0x2381_3C00, 0x2381_3E00]
+        );
+        assert_eq!(
+            notes
+                .all_notes_off_midi1()
+                .map(|m| m.data()[0])
+                .collect::<std::vec::Vec<_>>(),
+            std::vec![0x23B1_7B00]
+        );
+        assert_eq!(
+            notes
+                .all_sound_off_midi1()
+                .map(|m| m.data()[0])
+                .collect::<std::vec::Vec<_>>(),
+            std::vec![0x23B1_7800]
+        );
+    }
+
+    #[cfg(feature = "channel-voice2")]
+    #[test]
+    fn channel_voice2() {
+        use crate::channel_voice2::ChannelVoice2;
+
+        let mut notes = ActiveNotes::<1>::new();
+        notes.receive_channel_voice2(
+            &ChannelVoice2::try_from(&[0x4091_3C00_u32, 0x8000_0000][..]).unwrap(),
+        );
+        assert_eq!(notes.active_notes().count(), 1);
+        notes.receive_channel_voice2(
+            &ChannelVoice2::try_from(&[0x4081_3C00_u32, 0x8000_0000][..]).unwrap(),
+        );
+        assert!(notes.is_empty());
+    }
+
+    #[cfg(feature = "channel-voice2")]
+    #[test]
+    fn midi2_panic() {
+        use crate::traits::Data;
+
+        let mut notes = ActiveNotes::<1>::new();
+        notes.note_on(u4::new(0x3), u4::new(0x1), u7::new(0x3C));
+        notes.note_on(u4::new(0x4), u4::new(0x2), u7::new(0x3E));
+        assert_eq!(
+            notes
+                .all_sound_off_midi2()
+                .map(|m| m.data().to_vec())
+                .collect::<std::vec::Vec<_>>(),
+            std::vec![
+                std::vec![0x43B1_7800, 0x0000_0000],
+                std::vec![0x44B2_7800, 0x0000_0000]
+            ]
+        );
+    }
+}
@@ -4,6 +4,8 @@ use crate::detail::BitOps;
 
 mod channel_pressure;
 mod control_change;
+mod control_function;
+mod controller_decoder;
 mod key_pressure;
 mod note_off;
 mod note_on;
@@ -12,6 +14,8 @@ mod program_change;
 
 pub use channel_pressure::*;
 pub use control_change::*;
+pub use control_function::ControlFunction;
+pub use controller_decoder::*;
 pub use key_pressure::*;
 pub use note_off::*;
 pub use note_on::*;
@@ -24,6 +28,7 @@ pub(crate) const UMP_MESSAGE_TYPE: u8 = 0x2;
     derive_more::From,
     midi2_proc::Data,
     midi2_proc::Packets,
+    midi2_proc::Remappable,
     midi2_proc::Channeled,
     midi2_proc::Grouped,
     midi2_proc::FromBytes,
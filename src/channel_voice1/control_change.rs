@@ -1,5 +1,5 @@
 use crate::{
-    channel_voice1::UMP_MESSAGE_TYPE,
+    channel_voice1::{control_function, UMP_MESSAGE_TYPE},
     detail::{common_properties, schema},
 };
 
@@ -35,6 +35,8 @@ struct ControlChange {
         schema::Ump<0x0000_007F, 0x0, 0x0, 0x0>,
     >)]
     control_data: crate::ux::u7,
+    #[property(control_function::ControlFunctionProperty)]
+    control_function: control_function::ControlFunction,
 }
 
 #[cfg(test)]
@@ -140,4 +142,38 @@ mod tests {
         assert_eq!(packets.next(), Some(&[0x2AB7_3637_u32][..]));
         assert_eq!(packets.next(), None);
     }
+
+    #[test]
+    fn control_function() {
+        assert_eq!(
+            ControlChange::try_from(&[0x2AB7_7B00_u32][..])
+                .unwrap()
+                .control_function(),
+            control_function::ControlFunction::AllNotesOff,
+        );
+    }
+
+    #[test]
+    fn control_function_bytes() {
+        assert_eq!(
+            ControlChange::try_from(&[0xB7_u8, 0x01, 0x40][..])
+                .unwrap()
+                .control_function(),
+            control_function::ControlFunction::ModulationWheel(u7::new(0x40)),
+        );
+    }
+
+    #[test]
+    fn set_control_function() {
+        let mut message = ControlChange::<[u32; 4]>::new();
+        message.set_control_function(control_function::ControlFunction::LocalControl(true));
+        assert_eq!(message, ControlChange([0x20B0_7A7F, 0x0, 0x0, 0x0]));
+    }
+
+    #[test]
+    fn set_control_function_bytes() {
+        let mut message = ControlChange::<[u8; 3]>::new();
+        message.set_control_function(control_function::ControlFunction::ResetAllControllers);
+        assert_eq!(message, ControlChange([0xB0, 0x79, 0x00]));
+    }
 }
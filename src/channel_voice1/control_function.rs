@@ -0,0 +1,308 @@
+use crate::{
+    buffer::{
+        Buffer, BufferMut, SpecialiseU32, SpecialiseU8, UnitPrivate, UNIT_ID_U32, UNIT_ID_U8,
+    },
+    detail::{property, BitOps},
+    ux::u7,
+};
+
+/// The standard assignments of the MIDI 1.0 control change numbers,
+/// including the channel mode messages.
+///
+/// Controllers which aren't covered are read as [Other](ControlFunction::Other),
+/// keeping the control number and data.
+///
+/// ```rust
+/// use midi2::{prelude::*, channel_voice1::{ControlChange, ControlFunction}};
+///
+/// let mut message = ControlChange::<[u8; 3]>::new();
+/// message.set_channel(u4::new(0x2));
+/// message.set_control_function(ControlFunction::MonoOn { channels: u7::new(4) });
+/// assert_eq!(message.data(), &[0xB2, 0x7E, 0x04]);
+///
+/// let message = ControlChange::try_from(&[0x20B0_4000_u32][..]).unwrap();
+/// assert_eq!(message.control_function(), ControlFunction::Sustain(u7::new(0x0)));
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ControlFunction {
+    BankSelect(u7),
+    ModulationWheel(u7),
+    BreathController(u7),
+    FootController(u7),
+    PortamentoTime(u7),
+    DataEntry(u7),
+    ChannelVolume(u7),
+    Balance(u7),
+    Pan(u7),
+    Expression(u7),
+    BankSelectLsb(u7),
+    ModulationWheelLsb(u7),
+    BreathControllerLsb(u7),
+    FootControllerLsb(u7),
+    PortamentoTimeLsb(u7),
+    DataEntryLsb(u7),
+    ChannelVolumeLsb(u7),
+    BalanceLsb(u7),
+    PanLsb(u7),
+    ExpressionLsb(u7),
+    /// Damper pedal. Values of 64 and above are on.
+    Sustain(u7),
+    /// Values of 64 and above are on.
+    Portamento(u7),
+    /// Values of 64 and above are on.
+    Sostenuto(u7),
+    /// Values of 64 and above are on.
+    SoftPedal(u7),
+    DataIncrement(u7),
+    DataDecrement(u7),
+    NonRegisteredParameterLsb(u7),
+    NonRegisteredParameterMsb(u7),
+    RegisteredParameterLsb(u7),
+    RegisteredParameterMsb(u7),
+    AllSoundOff,
+    ResetAllControllers,
+    LocalControl(bool),
+    AllNotesOff,
+    OmniOff,
+    OmniOn,
+    /// Zero channels means as many channels as the receiver has voices.
+    MonoOn {
+        channels: u7,
+    },
+    PolyOn,
+    Other {
+        control: u7,
+        data: u7,
+    },
+}
+
+const LSB_OFFSET: u8 = 32;
+const LOCAL_CONTROL_ON: u8 = 0x7F;
+
+pub fn from_control_and_data(control: u8, data: u8) -> ControlFunction {
+    use ControlFunction::*;
+
+    let value = u7::new(data);
+    match control {
+        0 => BankSelect(value),
+        1 => ModulationWheel(value),
+        2 => BreathController(value),
+        4 => FootController(value),
+        5 => PortamentoTime(value),
+        6 => DataEntry(value),
+        7 => ChannelVolume(value),
+        8 => Balance(value),
+        10 => Pan(value),
+        11 => Expression(value),
+        32 => BankSelectLsb(value),
+        33 => ModulationWheelLsb(value),
+        34 => BreathControllerLsb(value),
+        36 => FootControllerLsb(value),
+        37 => PortamentoTimeLsb(value),
+        38 => DataEntryLsb(value),
+        39 => ChannelVolumeLsb(value),
+        40 => BalanceLsb(value),
+        42 => PanLsb(value),
+        43 => ExpressionLsb(value),
+        64 => Sustain(value),
+        65 => Portamento(value),
+        66 => Sostenuto(value),
+        67 => SoftPedal(value),
+        96 => DataIncrement(value),
+        97 => DataDecrement(value),
+        98 => NonRegisteredParameterLsb(value),
+        99 => NonRegisteredParameterMsb(value),
+        100 => RegisteredParameterLsb(value),
+        101 => RegisteredParameterMsb(value),
+        120 => AllSoundOff,
+        121 => ResetAllControllers,
+        122 => LocalControl(data >= 0x40),
+        123 => AllNotesOff,
+        124 => OmniOff,
+        125 => OmniOn,
+        126 => MonoOn { channels: value },
+        127 => PolyOn,
+        _ => Other {
+            control: u7::new(control),
+            data: value,
+        },
+    }
+}
+
+pub fn to_control_and_data(function: ControlFunction) -> (u8, u8) {
+    use ControlFunction::*;
+
+    let (control, value) = match function {
+        BankSelect(v) => (0, v),
+        ModulationWheel(v) => (1, v),
+        BreathController(v) => (2, v),
+        FootController(v) => (4, v),
+        PortamentoTime(v) => (5, v),
+        DataEntry(v) => (6, v),
+        ChannelVolume(v) => (7, v),
+        Balance(v) => (8, v),
+        Pan(v) => (10, v),
+        Expression(v) => (11, v),
+        BankSelectLsb(v) => (LSB_OFFSET, v),
+        ModulationWheelLsb(v) => (LSB_OFFSET + 1, v),
+        BreathControllerLsb(v) => (LSB_OFFSET + 2, v),
+        FootControllerLsb(v) => (LSB_OFFSET + 4, v),
+        PortamentoTimeLsb(v) => (LSB_OFFSET + 5, v),
+        DataEntryLsb(v) => (LSB_OFFSET + 6, v),
+        ChannelVolumeLsb(v) => (LSB_OFFSET + 7, v),
+        BalanceLsb(v) => (LSB_OFFSET + 8, v),
+        PanLsb(v) => (LSB_OFFSET + 10, v),
+        ExpressionLsb(v) => (LSB_OFFSET + 11, v),
+        Sustain(v) => (64, v),
+        Portamento(v) => (65, v),
+        Sostenuto(v) => (66, v),
+        SoftPedal(v) => (67, v),
+        DataIncrement(v) => (96, v),
+        DataDecrement(v) => (97, v),
+        NonRegisteredParameterLsb(v) => (98, v),
+        NonRegisteredParameterMsb(v) => (99, v),
+        RegisteredParameterLsb(v) => (100, v),
+        RegisteredParameterMsb(v) => (101, v),
+        AllSoundOff => (120, u7::new(0)),
+        ResetAllControllers => (121, u7::new(0)),
+        LocalControl(on) => (122, u7::new(if on { LOCAL_CONTROL_ON } else { 0 })),
+        AllNotesOff => (123, u7::new(0)),
+        OmniOff => (124, u7::new(0)),
+        OmniOn => (125, u7::new(0)),
+        MonoOn { channels } => (126, channels),
+        PolyOn => (127, u7::new(0)),
+        Other { control, data } => (u8::from(control), data),
+    };
+    (control, u8::from(value))
+}
+
+impl ControlFunction {
+    /// Whether the control change is a channel mode message.
+    pub fn is_channel_mode(&self) -> bool {
+        to_control_and_data(*self).0 >= 120
+    }
+}
+
+impl core::default::Default for ControlFunction {
+    /// Default value is ControlFunction::BankSelect(0x0)
+    fn default() -> Self {
+        ControlFunction::BankSelect(u7::new(0x0))
+    }
+}
+
+pub struct ControlFunctionProperty;
+
+impl<B: Buffer> property::Property<B> for ControlFunctionProperty {
+    type Type = ControlFunction;
+}
+
+impl<'a, B: Buffer> property::ReadProperty<'a, B> for ControlFunctionProperty {
+    fn validate(_buffer: &B) -> Result<(), crate::error::InvalidData> {
+        Ok(())
+    }
+    fn read(buffer: &'a B) -> Self::Type {
+        let (control, data) = match <B::Unit as UnitPrivate>::UNIT_ID {
+            UNIT_ID_U32 => {
+                let b = buffer.buffer().specialise_u32()[0];
+                (b.octet(2), b.octet(3))
+            }
+            UNIT_ID_U8 => {
+                let b = buffer.buffer();
+                let b = b.specialise_u8();
+                (b[1], b[2])
+            }
+            _ => unreachable!(),
+        };
+        from_control_and_data(control & 0x7F, data & 0x7F)
+    }
+}
+
+impl<B: Buffer + BufferMut> property::WriteProperty<B> for ControlFunctionProperty {
+    fn validate(_v: &Self::Type) -> Result<(), crate::error::InvalidData> {
+        Ok(())
+    }
+    fn default() -> Self::Type {
+        Default::default()
+    }
+    fn write(buffer: &mut B, v: Self::Type) {
+        let (control, data) = to_control_and_data(v);
+        match <B::Unit as UnitPrivate>::UNIT_ID {
+            UNIT_ID_U32 => {
+                let mut b = buffer.buffer_mut();
+                let b = &mut b.specialise_u32_mut()[0];
+                b.set_octet(2, control);
+                b.set_octet(3, data);
+            }
+            UNIT_ID_U8 => {
+                let mut b = buffer.buffer_mut();
+                let b = b.specialise_u8_mut();
+                b[1] = control;
+                b[2] = data;
+            }
+            _ => unreachable!(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn every_control_round_trips() {
+        for control in 0..0x80 {
+            let data = match control {
+                120 | 121 | 123 | 124 | 125 | 127 => 0x0,
+                122 => LOCAL_CONTROL_ON,
+                _ => 0x55,
+            };
+            assert_eq!(
+                to_control_and_data(from_control_and_data(control, data)),
+                (control, data)
+            );
+        }
+    }
+
+    #[test]
+    fn lsb_counterparts() {
+        assert_eq!(
+            from_control_and_data(39, 0x12),
+            ControlFunction::ChannelVolumeLsb(u7::new(0x12))
+        );
+        assert_eq!(
+            to_control_and_data(ControlFunction::BankSelectLsb(u7::new(0x1))),
+            (32, 0x1)
+        );
+    }
+
+    #[test]
+    fn local_control() {
+        assert_eq!(
+            from_control_and_data(122, 0x0),
+            ControlFunction::LocalControl(false)
+        );
+        assert_eq!(
+            to_control_and_data(ControlFunction::LocalControl(true)),
+            (122, 0x7F)
+        );
+    }
+
+    #[test]
+    fn other() {
+        assert_eq!(
+            from_control_and_data(0x14, 0x22),
+            ControlFunction::Other {
+                control: u7::new(0x14),
+                data: u7::new(0x22)
+            }
+        );
+    }
+
+    #[test]
+    fn channel_mode() {
+        assert!(ControlFunction::AllNotesOff.is_channel_mode());
+        assert!(!ControlFunction::Sustain(u7::new(0x7F)).is_channel_mode());
+    }
+}
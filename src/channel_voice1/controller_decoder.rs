@@ -0,0 +1,504 @@
+use crate::{
+    channel_voice1::{ControlChange, ControlFunction},
+    ux::{u14, u7},
+};
+
+const LSB_OFFSET: u8 = 32;
+const NUMBER_OF_14_BIT_CONTROLLERS: u8 = 32;
+const NULL_PARAMETER: u16 = 0x3FFF;
+
+/// A control change sequence decoded by a [ControllerDecoder].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ControllerEvent {
+    /// A controller `0..=31` together with its LSB counterpart `32..=63`.
+    ///
+    /// Numbers from 32 have no LSB counterpart and encode to no control changes.
+    Controller14 {
+        number: u7,
+        value: u14,
+    },
+    /// Data entry for the registered parameter `MSB << 7 | LSB`.
+    Rpn {
+        parameter: u14,
+        value: u14,
+    },
+    /// Data entry for the non-registered parameter `MSB << 7 | LSB`.
+    Nrpn {
+        parameter: u14,
+        value: u14,
+    },
+    RpnIncrement {
+        parameter: u14,
+    },
+    RpnDecrement {
+        parameter: u14,
+    },
+    NrpnIncrement {
+        parameter: u14,
+    },
+    NrpnDecrement {
+        parameter: u14,
+    },
+    /// Any other control change, including the channel mode messages.
+    Other(ControlFunction),
+}
+
+/// Decodes the 14 bit controllers and (N)RPN sequences
+/// of the control changes of a single channel.
+///
+/// An MSB resets the LSB of its controller, so a controller sent as an MSB
+/// followed by an LSB is decoded as two events, the second carrying the full value.
+/// Data entry follows the same rule, and selecting a parameter resets it.
+/// Data entry, increment and decrement without a selected parameter,
+/// or after the RPN null parameter, are ignored.
+///
+/// ```rust
+/// use midi2::{prelude::*, channel_voice1::*};
+///
+/// let mut decoder = ControllerDecoder::new();
+///
+/// let mut events = [
+///     &[0xB0_u8, 0x65, 0x00][..],
+///     &[0xB0, 0x64, 0x00][..],
+///     &[0xB0, 0x06, 0x02][..],
+/// ]
+/// .into_iter()
+/// .filter_map(|bytes| decoder.decode(&ControlChange::try_from(bytes).unwrap()));
+///
+/// assert_eq!(
+///     events.last(),
+///     Some(ControllerEvent::Rpn { parameter: u14::new(0x0), value: u14::new(0x100) })
+/// );
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ControllerDecoder {
+    msb: [u8; NUMBER_OF_14_BIT_CONTROLLERS as usize],
+    parameter: Option<Parameter>,
+    data_entry_msb: u8,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+struct Parameter {
+    registered: bool,
+    number: u16,
+}
+
+impl ControllerDecoder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Forget the received controller values and the selected parameter.
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    /// Decode the next control change of the channel.
+    ///
+    /// Returns None for the parameter number control changes,
+    /// which only select the parameter for the following data entry.
+    pub fn decode<B: crate::buffer::Buffer>(
+        &mut self,
+        message: &ControlChange<B>,
+    ) -> Option<ControllerEvent> {
+        use ControlFunction::*;
+
+        match message.control_function() {
+            RegisteredParameterMsb(v) => self.select(true, Some(v), None),
+            RegisteredParameterLsb(v) => self.select(true, None, Some(v)),
+            NonRegisteredParameterMsb(v) => self.select(false, Some(v), None),
+            NonRegisteredParameterLsb(v) => self.select(false, None, Some(v)),
+            DataEntry(v) => {
+                self.data_entry_msb = u8::from(v);
+                self.parameter_value(u16::from(self.data_entry_msb) << 7)
+            }
+            DataEntryLsb(v) => {
+                self.parameter_value(u16::from(self.data_entry_msb) << 7 | u16::from(u8::from(v)))
+            }
+            DataIncrement(_) => self.active_parameter().map(|p| match p.registered {
+                true => ControllerEvent::RpnIncrement {
+                    parameter: u14::new(p.number),
+                },
+                false => ControllerEvent::NrpnIncrement {
+                    parameter: u14::new(p.number),
+                },
+            }),
+            DataDecrement(_) => self.active_parameter().map(|p| match p.registered {
+                true => ControllerEvent::RpnDecrement {
+                    parameter: u14::new(p.number),
+                },
+                false => ControllerEvent::NrpnDecrement {
+                    parameter: u14::new(p.number),
+                },
+            }),
+            function => {
+                let control = u8::from(message.control());
+                if control < NUMBER_OF_14_BIT_CONTROLLERS {
+                    let msb = u8::from(message.control_data());
+                    self.msb[control as usize] = msb;
+                    Some(ControllerEvent::Controller14 {
+                        number: u7::new(control),
+                        value: u14::new(u16::from(msb) << 7),
+                    })
+                } else if (LSB_OFFSET..LSB_OFFSET + NUMBER_OF_14_BIT_CONTROLLERS).contains(&control)
+                {
+                    let number = control - LSB_OFFSET;
+                    let msb = u16::from(self.msb[number as usize]);
+                    Some(ControllerEvent::Controller14 {
+                        number: u7::new(number),
+                        value: u14::new(msb << 7 | u16::from(u8::from(message.control_data()))),
+                    })
+                } else {
+                    Some(ControllerEvent::Other(function))
+                }
+            }
+        }
+    }
+
+    fn select(
+        &mut self,
+        registered: bool,
+        msb: Option<u7>,
+        lsb: Option<u7>,
+    ) -> Option<ControllerEvent> {
+        // data entry only applies to the parameter selected before it
+        self.data_entry_msb = 0;
+        let mut parameter = self
+            .parameter
+            .filter(|p| p.registered == registered)
+            .unwrap_or(Parameter {
+                registered,
+                number: 0,
+            });
+        if let Some(msb) = msb {
+            parameter.number = (parameter.number & 0x7F) | u16::from(u8::from(msb)) << 7;
+        }
+        if let Some(lsb) = lsb {
+            parameter.number = (parameter.number & 0x3F80) | u16::from(u8::from(lsb));
+        }
+        self.parameter = Some(parameter);
+        None
+    }
+
+    fn active_parameter(&self) -> Option<Parameter> {
+        self.parameter
+            .filter(|p| !(p.registered && p.number == NULL_PARAMETER))
+    }
+
+    fn parameter_value(&self, value: u16) -> Option<ControllerEvent> {
+        self.active_parameter().map(|p| {
+            let parameter = u14::new(p.number);
+            let value = u14::new(value);
+            match p.registered {
+                true => ControllerEvent::Rpn { parameter, value },
+                false => ControllerEvent::Nrpn { parameter, value },
+            }
+        })
+    }
+}
+
+impl ControllerEvent {
+    /// The control changes which encode the event.
+    ///
+    /// Parameter events select the parameter, send the data and
+    /// then select the RPN null parameter,
+    /// so that stray data entry messages can't alter the parameter.
+    pub fn control_functions(&self) -> ControlFunctions {
+        use ControlFunction::*;
+
+        let mut functions = ControlFunctions::default();
+        let msb = |v: u14| u7::new((u16::from(v) >> 7) as u8);
+        let lsb = |v: u14| u7::new((u16::from(v) & 0x7F) as u8);
+        match *self {
+            ControllerEvent::Controller14 { number, value } => {
+                let number = u8::from(number);
+                if number >= NUMBER_OF_14_BIT_CONTROLLERS {
+                    return functions;
+                }
+                functions.push(super::control_function::from_control_and_data(
+                    number,
+                    u8::from(msb(value)),
+                ));
+                functions.push(super::control_function::from_control_and_data(
+                    number + LSB_OFFSET,
+                    u8::from(lsb(value)),
+                ));
+                return functions;
+            }
+            ControllerEvent::Rpn { parameter, value } => {
+                functions.push(RegisteredParameterMsb(msb(parameter)));
+                functions.push(RegisteredParameterLsb(lsb(parameter)));
+                functions.push(DataEntry(msb(value)));
+                functions.push(DataEntryLsb(lsb(value)));
+            }
+            ControllerEvent::Nrpn { parameter, value } => {
+                functions.push(NonRegisteredParameterMsb(msb(parameter)));
+                functions.push(NonRegisteredParameterLsb(lsb(parameter)));
+                functions.push(DataEntry(msb(value)));
+                functions.push(DataEntryLsb(lsb(value)));
+            }
+            ControllerEvent::RpnIncrement { parameter } => {
+                functions.push(RegisteredParameterMsb(msb(parameter)));
+                functions.push(RegisteredParameterLsb(lsb(parameter)));
+                functions.push(DataIncrement(u7::new(0)));
+            }
+            ControllerEvent::RpnDecrement { parameter } => {
+                functions.push(RegisteredParameterMsb(msb(parameter)));
+                functions.push(RegisteredParameterLsb(lsb(parameter)));
+                functions.push(DataDecrement(u7::new(0)));
+            }
+            ControllerEvent::NrpnIncrement { parameter } => {
+                functions.push(NonRegisteredParameterMsb(msb(parameter)));
+                functions.push(NonRegisteredParameterLsb(lsb(parameter)));
+                functions.push(DataIncrement(u7::new(0)));
+            }
+            ControllerEvent::NrpnDecrement { parameter } => {
+                functions.push(NonRegisteredParameterMsb(msb(parameter)));
+                functions.push(NonRegisteredParameterLsb(lsb(parameter)));
+                functions.push(DataDecrement(u7::new(0)));
+            }
+            ControllerEvent::Other(function) => {
+                functions.push(function);
+                return functions;
+            }
+        }
+        functions.push(RegisteredParameterMsb(msb(u14::new(NULL_PARAMETER))));
+        functions.push(RegisteredParameterLsb(lsb(u14::new(NULL_PARAMETER))));
+        functions
+    }
+}
+
+/// The control changes produced by [ControllerEvent::control_functions].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ControlFunctions {
+    functions: [ControlFunction; 6],
+    len: usize,
+    index: usize,
+}
+
+impl ControlFunctions {
+    fn push(&mut self, function: ControlFunction) {
+        self.functions[self.len] = function;
+        self.len += 1;
+    }
+}
+
+impl core::iter::Iterator for ControlFunctions {
+    type Item = ControlFunction;
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.index == self.len {
+            return None;
+        }
+        self.index += 1;
+        Some(self.functions[self.index - 1])
+    }
+}
+
+impl core::iter::FusedIterator for ControlFunctions {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn decode(decoder: &mut ControllerDecoder, control: u8, data: u8) -> Option<ControllerEvent> {
+        decoder.decode(&ControlChange::try_from(&[0xB0_u8, control, data][..]).unwrap())
+    }
+
+    #[test]
+    fn controller14() {
+        let mut decoder = ControllerDecoder::new();
+        assert_eq!(
+            decode(&mut decoder, 0x07, 0x64),
+            Some(ControllerEvent::Controller14 {
+                number: u7::new(0x07),
+                value: u14::new(0x64 << 7)
+            })
+        );
+        assert_eq!(
+            decode(&mut decoder, 0x27, 0x01),
+            Some(ControllerEvent::Controller14 {
+                number: u7::new(0x07),
+                value: u14::new(0x64 << 7 | 0x01)
+            })
+        );
+    }
+
+    #[test]
+    fn rpn() {
+        let mut decoder = ControllerDecoder::new();
+        assert_eq!(decode(&mut decoder, 0x65, 0x00), None);
+        assert_eq!(decode(&mut decoder, 0x64, 0x02), None);
+        decode(&mut decoder, 0x06, 0x40);
+        assert_eq!(
+            decode(&mut decoder, 0x26, 0x10),
+            Some(ControllerEvent::Rpn {
+                parameter: u14::new(0x0002),
+                value: u14::new(0x40 << 7 | 0x10)
+            })
+        );
+    }
+
+    #[test]
+    fn nrpn() {
+        let mut decoder = ControllerDecoder::new();
+        decode(&mut decoder, 0x63, 0x01);
+        decode(&mut decoder, 0x62, 0x02);
+        assert_eq!(
+            decode(&mut decoder, 0x06, 0x7F),
+            Some(ControllerEvent::Nrpn {
+                parameter: u14::new(0x0082),
+                value: u14::new(0x7F << 7)
+            })
+        );
+    }
+
+    #[test]
+    fn increment_and_decrement() {
+        let mut decoder = ControllerDecoder::new();
+        decode(&mut decoder, 0x65, 0x00);
+        decode(&mut decoder, 0x64, 0x00);
+        assert_eq!(
+            decode(&mut decoder, 0x60, 0x00),
+            Some(ControllerEvent::RpnIncrement {
+                parameter: u14::new(0x0)
+            })
+        );
+        assert_eq!(
+            decode(&mut decoder, 0x61, 0x00),
+            Some(ControllerEvent::RpnDecrement {
+                parameter: u14::new(0x0)
+            })
+        );
+    }
+
+    #[test]
+    fn data_entry_after_null_is_ignored() {
+        let mut decoder = ControllerDecoder::new();
+        assert_eq!(decode(&mut decoder, 0x06, 0x40), None);
+        decode(&mut decoder, 0x65, 0x7F);
+        decode(&mut decoder, 0x64, 0x7F);
+        assert_eq!(decode(&mut decoder, 0x06, 0x40), None);
+    }
+
+    #[test]
+    fn data_entry_lsb_after_parameter_change() {
+        let mut decoder = ControllerDecoder::new();
+        decode(&mut decoder, 0x65, 0x00);
+        decode(&mut decoder, 0x64, 0x00);
+        decode(&mut decoder, 0x06, 0x40);
+        decode(&mut decoder, 0x64, 0x01);
+        assert_eq!(
+            decode(&mut decoder, 0x26, 0x10),
+            Some(ControllerEvent::Rpn {
+                parameter: u14::new(0x0001),
+                value: u14::new(0x10)
+            })
+        );
+    }
+
+    #[test]
+    fn lsb_selection_after_switching_parameter_kind() {
+        let mut decoder = ControllerDecoder::new();
+        decode(&mut decoder, 0x65, 0x7F);
+        decode(&mut decoder, 0x64, 0x7F);
+        decode(&mut decoder, 0x62, 0x05);
+        assert_eq!(
+            decode(&mut decoder, 0x06, 0x01),
+            Some(ControllerEvent::Nrpn {
+                parameter: u14::new(0x0005),
+                value: u14::new(0x01 << 7)
+            })
+        );
+    }
+
+    #[test]
+    fn other() {
+        let mut decoder = ControllerDecoder::new();
+        assert_eq!(
+            decode(&mut decoder, 0x7B, 0x00),
+            Some(ControllerEvent::Other(ControlFunction::AllNotesOff))
+        );
+    }
+
+    #[test]
+    fn encode_rpn() {
+        use ControlFunction::*;
+
+        assert_eq!(
+            ControllerEvent::Rpn {
+                parameter: u14::new(0x0001),
+                value: u14::new(0x2000),
+            }
+            .control_functions()
+            .collect::<std::vec::Vec<_>>(),
+            std::vec![
+                RegisteredParameterMsb(u7::new(0x00)),
+                RegisteredParameterLsb(u7::new(0x01)),
+                DataEntry(u7::new(0x40)),
+                DataEntryLsb(u7::new(0x00)),
+                RegisteredParameterMsb(u7::new(0x7F)),
+                RegisteredParameterLsb(u7::new(0x7F)),
+            ]
+        );
+    }
+
+    #[test]
+    fn encode_controller14() {
+        assert_eq!(
+            ControllerEvent::Controller14 {
+                number: u7::new(0x01),
+                value: u14::new(0x0081),
+            }
+            .control_functions()
+            .collect::<std::vec::Vec<_>>(),
+            std::vec![
+                ControlFunction::ModulationWheel(u7::new(0x01)),
+                ControlFunction::ModulationWheelLsb(u7::new(0x01)),
+            ]
+        );
+    }
+
+    #[test]
+    fn encode_controller14_without_lsb() {
+        assert_eq!(
+            ControllerEvent::Controller14 {
+                number: u7::new(0x40),
+                value: u14::new(0x2000),
+            }
+            .control_functions()
+            .count(),
+            0
+        );
+    }
+
+    #[test]
+    fn round_trip() {
+        let events = [
+            ControllerEvent::Nrpn {
+                parameter: u14::new(0x1234),
+                value: u14::new(0x0FED),
+            },
+            ControllerEvent::NrpnDecrement {
+                parameter: u14::new(0x0055),
+            },
+            ControllerEvent::Controller14 {
+                number: u7::new(0x10),
+                value: u14::new(0x3210),
+            },
+        ];
+        for event in events {
+            let mut decoder = ControllerDecoder::new();
+            let decoded = event
+                .control_functions()
+                .filter_map(|function| {
+                    let mut message = ControlChange::<[u8; 3]>::new();
+                    message.set_control_function(function);
+                    decoder.decode(&message)
+                })
+                .filter(|e| *e == event)
+                .count();
+            assert_eq!(decoded, 1);
+        }
+    }
+}
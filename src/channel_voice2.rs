@@ -5,6 +5,8 @@ mod assignable_per_note_controller;
 mod attribute;
 mod channel_pitch_bend;
 mod channel_pressure;
+#[cfg(feature = "std")]
+mod channel_state;
 mod control_change;
 mod controller;
 mod key_pressure;
@@ -12,8 +14,10 @@ mod note_off;
 mod note_on;
 mod per_note_management;
 mod per_note_pitch_bend;
+pub(crate) mod pitch;
 mod program_change;
 mod registered_controller;
+mod registered_parameter;
 mod registered_per_note_controller;
 mod relative_assignable_controller;
 mod relative_registered_controller;
@@ -23,6 +27,8 @@ pub use assignable_per_note_controller::*;
 pub use attribute::Attribute as NoteAttribute;
 pub use channel_pitch_bend::*;
 pub use channel_pressure::*;
+#[cfg(feature = "std")]
+pub use channel_state::*;
 pub use control_change::*;
 pub use controller::Controller;
 pub use key_pressure::*;
@@ -30,8 +36,10 @@ pub use note_off::*;
 pub use note_on::*;
 pub use per_note_management::*;
 pub use per_note_pitch_bend::*;
+pub use pitch::Pitch;
 pub use program_change::*;
 pub use registered_controller::*;
+pub use registered_parameter::RegisteredParameter;
 pub use registered_per_note_controller::*;
 pub use relative_assignable_controller::*;
 pub use relative_registered_controller::*;
@@ -42,6 +50,7 @@ pub(crate) const UMP_MESSAGE_TYPE: u8 = 0x4;
     derive_more::From,
     midi2_proc::Data,
     midi2_proc::Packets,
+    midi2_proc::Remappable,
     midi2_proc::Channeled,
     midi2_proc::Grouped,
     midi2_proc::RebufferFrom,
@@ -0,0 +1,834 @@
+use crate::{
+    channel_voice2::{controller, ChannelVoice2, Controller},
+    traits::{Channeled, Grouped},
+    ux::{u14, u4, u7},
+};
+use std::collections::BTreeMap;
+
+const PITCH_BEND_CENTER: u32 = 0x8000_0000;
+
+const BANK_SELECT_MSB: u8 = 0;
+const DATA_ENTRY_MSB: u8 = 6;
+const BANK_SELECT_LSB: u8 = 32;
+const DATA_ENTRY_LSB: u8 = 38;
+const DATA_INCREMENT: u8 = 96;
+const DATA_DECREMENT: u8 = 97;
+const NRPN_LSB: u8 = 98;
+const NRPN_MSB: u8 = 99;
+const RPN_LSB: u8 = 100;
+const RPN_MSB: u8 = 101;
+const CHANNEL_MODE_MESSAGES: u8 = 120;
+#[cfg(feature = "channel-voice1")]
+const NULL_PARAMETER: u8 = 0x7F;
+
+/// The current controller values of every group and channel,
+/// built from a stream of MIDI 2.0 channel voice messages,
+/// or MIDI 1.0 channel voice messages with the `channel-voice1` feature.
+///
+/// Values which haven't been received are reported as `None`.
+/// Relative controller messages are applied to the current absolute value
+/// (zero if none has been received), saturating at the bounds of the range.
+///
+/// ```rust
+/// use midi2::{prelude::*, channel_voice2::*};
+///
+/// let mut state = ChannelState::new();
+///
+/// let mut message = ControlChange::<[u32; 4]>::new();
+/// message.set_channel(u4::new(0x3));
+/// message.set_control(u7::new(0x07));
+/// message.set_control_change_data(0x8000_0000);
+/// state.receive(&message.into());
+///
+/// let channel = state.channel(u4::new(0x0), u4::new(0x3)).unwrap();
+/// assert_eq!(channel.control_change(u7::new(0x07)), Some(0x8000_0000));
+/// assert_eq!(channel.pitch_bend(), None);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ChannelState {
+    channels: BTreeMap<(u4, u4), Channel>,
+}
+
+/// The controller values of a single channel.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Channel {
+    control_changes: BTreeMap<u7, u32>,
+    registered_controllers: BTreeMap<(u7, u7), u32>,
+    assignable_controllers: BTreeMap<(u7, u7), u32>,
+    pitch_bend: Option<u32>,
+    channel_pressure: Option<u32>,
+    program: Option<u7>,
+    bank: Option<u14>,
+    notes: BTreeMap<u7, Note>,
+    #[cfg(feature = "channel-voice1")]
+    midi1: Midi1State,
+}
+
+/// The MIDI 1.0 control changes which only take effect in combination with others.
+#[cfg(feature = "channel-voice1")]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct Midi1State {
+    bank: Option<u14>,
+    decoder: crate::channel_voice1::ControllerDecoder,
+}
+
+/// The per-note controller values of a single note number.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Note {
+    registered_controllers: BTreeMap<u8, u32>,
+    assignable_controllers: BTreeMap<u8, u32>,
+    pitch_bend: Option<u32>,
+    key_pressure: Option<u32>,
+}
+
+impl ChannelState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The values of the channel, or None if no message for the channel has been received.
+    pub fn channel(&self, group: u4, channel: u4) -> Option<&Channel> {
+        self.channels.get(&(group, channel))
+    }
+
+    /// The group and channel of every channel with state, and their values.
+    pub fn channels(&self) -> impl Iterator<Item = ((u4, u4), &Channel)> {
+        self.channels
+            .iter()
+            .map(|(address, channel)| (*address, channel))
+    }
+
+    /// Forget every value.
+    pub fn clear(&mut self) {
+        self.channels.clear();
+    }
+
+    /// Update the state with the message.
+    pub fn receive<B: crate::buffer::Ump>(&mut self, message: &ChannelVoice2<B>) {
+        use ChannelVoice2::*;
+
+        let channel = self
+            .channels
+            .entry((message.group(), message.channel()))
+            .or_default();
+        match message {
+            ControlChange(m) => {
+                channel
+                    .control_changes
+                    .insert(m.control(), m.control_change_data());
+            }
+            RegisteredController(m) => {
+                channel
+                    .registered_controllers
+                    .insert((m.bank(), m.index()), m.controller_data());
+            }
+            AssignableController(m) => {
+                channel
+                    .assignable_controllers
+                    .insert((m.bank(), m.index()), m.controller_data());
+            }
+            RelativeRegisteredController(m) => {
+                let value = channel
+                    .registered_controllers
+                    .entry((m.bank(), m.index()))
+                    .or_default();
+                *value = apply_delta(*value, m.controller_data());
+            }
+            RelativeAssignableController(m) => {
+                let value = channel
+                    .assignable_controllers
+                    .entry((m.bank(), m.index()))
+                    .or_default();
+                *value = apply_delta(*value, m.controller_data());
+            }
+            ChannelPitchBend(m) => {
+                channel.pitch_bend = Some(m.pitch_bend_data());
+            }
+            ChannelPressure(m) => {
+                channel.channel_pressure = Some(m.channel_pressure_data());
+            }
+            ProgramChange(m) => {
+                channel.program = Some(m.program());
+                if let Some(bank) = m.bank() {
+                    channel.bank = Some(bank);
+                }
+            }
+            KeyPressure(m) => {
+                let note = channel.notes.entry(m.note_number()).or_default();
+                note.key_pressure = Some(m.key_pressure_data());
+            }
+            PerNotePitchBend(m) => {
+                let note = channel.notes.entry(m.note_number()).or_default();
+                note.pitch_bend = Some(m.pitch_bend_data());
+            }
+            RegisteredPerNoteController(m) => {
+                let (index, data) = controller::to_index_and_data(m.controller());
+                let note = channel.notes.entry(m.note_number()).or_default();
+                note.registered_controllers.insert(index, data);
+            }
+            AssignablePerNoteController(m) => {
+                let note = channel.notes.entry(m.note_number()).or_default();
+                note.assignable_controllers
+                    .insert(m.index(), m.controller_data());
+            }
+            PerNoteManagement(m) => {
+                if m.detach() {
+                    // values sent to the previous note no longer apply to the note number
+                    channel.notes.remove(&m.note_number());
+                }
+                if m.reset() {
+                    let note = channel.notes.entry(m.note_number()).or_default();
+                    *note = Note {
+                        pitch_bend: Some(PITCH_BEND_CENTER),
+                        ..Default::default()
+                    };
+                }
+            }
+            NoteOn(_) | NoteOff(_) => {}
+        }
+    }
+
+    /// Update the state with a MIDI 1.0 message.
+    ///
+    /// Bank select control changes are applied by the following program change,
+    /// and (N)RPN data entry, increment and decrement are applied to the registered
+    /// or assignable controller selected by the preceding parameter number control changes,
+    /// as decoded by a [ControllerDecoder](crate::channel_voice1::ControllerDecoder).
+    /// Values are scaled up with [translation::scale_up](crate::translation::scale_up).
+    #[cfg(feature = "channel-voice1")]
+    pub fn receive_channel_voice1<B: crate::buffer::Ump>(
+        &mut self,
+        message: &crate::channel_voice1::ChannelVoice1<B>,
+    ) {
+        use crate::{
+            channel_voice1::{ChannelVoice1, ControllerEvent},
+            translation,
+        };
+
+        let channel = self
+            .channels
+            .entry((message.group(), message.channel()))
+            .or_default();
+        match message {
+            ChannelVoice1::ControlChange(m) => match channel.midi1.decoder.decode(m) {
+                Some(ControllerEvent::Controller14 { number, value })
+                    if u8::from(number) == BANK_SELECT_MSB =>
+                {
+                    channel.midi1.bank = Some(value);
+                }
+                Some(ControllerEvent::Rpn { parameter, value }) => {
+                    let value = translation::scale_up(u16::from(value).into(), 14, 32);
+                    channel.set_parameter(true, parameter, value);
+                }
+                Some(ControllerEvent::Nrpn { parameter, value }) => {
+                    let value = translation::scale_up(u16::from(value).into(), 14, 32);
+                    channel.set_parameter(false, parameter, value);
+                }
+                Some(ControllerEvent::RpnIncrement { parameter }) => {
+                    channel.step_parameter(true, parameter, 1);
+                }
+                Some(ControllerEvent::RpnDecrement { parameter }) => {
+                    channel.step_parameter(true, parameter, -1);
+                }
+                Some(ControllerEvent::NrpnIncrement { parameter }) => {
+                    channel.step_parameter(false, parameter, 1);
+                }
+                Some(ControllerEvent::NrpnDecrement { parameter }) => {
+                    channel.step_parameter(false, parameter, -1);
+                }
+                Some(ControllerEvent::Controller14 { .. }) | Some(ControllerEvent::Other(_)) => {
+                    self.receive(&translation::to_midi2(message))
+                }
+                _ => {}
+            },
+            ChannelVoice1::ProgramChange(m) => {
+                channel.program = Some(m.program());
+                if let Some(bank) = channel.midi1.bank {
+                    channel.bank = Some(bank);
+                }
+            }
+            _ => self.receive(&translation::to_midi2(message)),
+        }
+    }
+
+    /// The MIDI 2.0 messages which bring a receiver to the current state.
+    ///
+    /// For each channel in order the program change comes first,
+    /// carrying the bank if one has been selected,
+    /// followed by the control changes, the channel pressure, the pitch bend
+    /// and the registered and assignable controllers.
+    /// Values which haven't been received are not sent.
+    ///
+    /// Control changes which are part of bank select or (N)RPN sequences,
+    /// and channel mode messages, are not sent as control changes.
+    /// Per-note values only apply to sounding notes and are not sent either.
+    pub fn chase_midi2(&self) -> std::vec::Vec<ChannelVoice2<[u32; 4]>> {
+        let mut messages = std::vec::Vec::new();
+        for ((group, channel), state) in self.channels.iter() {
+            let first = messages.len();
+            state.chase(&mut messages);
+            for message in messages[first..].iter_mut() {
+                message.set_group(*group);
+                message.set_channel(*channel);
+            }
+        }
+        messages
+    }
+
+    /// The MIDI 1.0 messages which bring a receiver to the current state.
+    ///
+    /// The messages of [chase_midi2](ChannelState::chase_midi2) translated with
+    /// [translation::to_midi1](crate::translation::to_midi1),
+    /// so each registered and assignable controller is sent with its full (N)RPN header.
+    /// The parameters of a channel are followed by the RPN null parameter
+    /// so that later data entry messages don't alter them.
+    #[cfg(feature = "channel-voice1")]
+    pub fn chase_midi1(&self) -> std::vec::Vec<crate::channel_voice1::ChannelVoice1<[u32; 4]>> {
+        use crate::{channel_voice1, translation};
+
+        let mut messages = std::vec::Vec::new();
+        for ((group, channel), state) in self.channels.iter() {
+            let mut chase = std::vec::Vec::new();
+            state.chase(&mut chase);
+            for message in chase.iter_mut() {
+                message.set_group(*group);
+                message.set_channel(*channel);
+                messages.extend(translation::to_midi1(message));
+            }
+            if state.has_parameters() {
+                for control in [RPN_MSB, RPN_LSB] {
+                    let mut null = channel_voice1::ControlChange::<[u32; 4]>::new();
+                    null.set_group(*group);
+                    null.set_channel(*channel);
+                    null.set_control(u7::new(control));
+                    null.set_control_data(u7::new(NULL_PARAMETER));
+                    messages.push(null.into());
+                }
+            }
+        }
+        messages
+    }
+}
+
+impl Channel {
+    pub fn control_change(&self, control: u7) -> Option<u32> {
+        self.control_changes.get(&control).copied()
+    }
+
+    pub fn registered_controller(&self, bank: u7, index: u7) -> Option<u32> {
+        self.registered_controllers.get(&(bank, index)).copied()
+    }
+
+    pub fn assignable_controller(&self, bank: u7, index: u7) -> Option<u32> {
+        self.assignable_controllers.get(&(bank, index)).copied()
+    }
+
+    pub fn pitch_bend(&self) -> Option<u32> {
+        self.pitch_bend
+    }
+
+    pub fn channel_pressure(&self) -> Option<u32> {
+        self.channel_pressure
+    }
+
+    pub fn program(&self) -> Option<u7> {
+        self.program
+    }
+
+    /// The bank of the most recent program change which selected one.
+    pub fn bank(&self) -> Option<u14> {
+        self.bank
+    }
+
+    /// The per-note values of the note number, or None if none have been received.
+    pub fn note(&self, note_number: u7) -> Option<&Note> {
+        self.notes.get(&note_number)
+    }
+}
+
+impl Channel {
+    fn chase(&self, messages: &mut std::vec::Vec<ChannelVoice2<[u32; 4]>>) {
+        use crate::channel_voice2::{
+            AssignableController, ChannelPitchBend, ChannelPressure, ControlChange, ProgramChange,
+            RegisteredController,
+        };
+
+        if let Some(program) = self.program {
+            let mut message = ProgramChange::<[u32; 4]>::new();
+            message.set_program(program);
+            message.set_bank(self.bank);
+            messages.push(message.into());
+        }
+        for (control, data) in self.control_changes.iter() {
+            if !is_chased_control(*control) {
+                continue;
+            }
+            let mut message = ControlChange::<[u32; 4]>::new();
+            message.set_control(*control);
+            message.set_control_change_data(*data);
+            messages.push(message.into());
+        }
+        if let Some(data) = self.channel_pressure {
+            let mut message = ChannelPressure::<[u32; 4]>::new();
+            message.set_channel_pressure_data(data);
+            messages.push(message.into());
+        }
+        if let Some(data) = self.pitch_bend {
+            let mut message = ChannelPitchBend::<[u32; 4]>::new();
+            message.set_pitch_bend_data(data);
+            messages.push(message.into());
+        }
+        for ((bank, index), data) in self.registered_controllers.iter() {
+            let mut message = RegisteredController::<[u32; 4]>::new();
+            message.set_bank(*bank);
+            message.set_index(*index);
+            message.set_controller_data(*data);
+            messages.push(message.into());
+        }
+        for ((bank, index), data) in self.assignable_controllers.iter() {
+            let mut message = AssignableController::<[u32; 4]>::new();
+            message.set_bank(*bank);
+            message.set_index(*index);
+            message.set_controller_data(*data);
+            messages.push(message.into());
+        }
+    }
+
+    #[cfg(feature = "channel-voice1")]
+    fn has_parameters(&self) -> bool {
+        !self.registered_controllers.is_empty() || !self.assignable_controllers.is_empty()
+    }
+
+    #[cfg(feature = "channel-voice1")]
+    fn set_parameter(&mut self, registered: bool, parameter: u14, value: u32) {
+        *self.parameter(registered, parameter) = value;
+    }
+
+    /// Data increment and decrement step the 14 bit data entry value,
+    /// saturating at the bounds of the range.
+    #[cfg(feature = "channel-voice1")]
+    fn step_parameter(&mut self, registered: bool, parameter: u14, step: i32) {
+        use crate::translation;
+
+        let value = self.parameter(registered, parameter);
+        let data = translation::scale_down(*value, 32, 14) as i32;
+        let data = (data + step).clamp(0, 0x3FFF) as u32;
+        *value = translation::scale_up(data, 14, 32);
+    }
+
+    #[cfg(feature = "channel-voice1")]
+    fn parameter(&mut self, registered: bool, parameter: u14) -> &mut u32 {
+        let parameter = u16::from(parameter);
+        let address = (
+            u7::new((parameter >> 7) as u8),
+            u7::new((parameter & 0x7F) as u8),
+        );
+        if registered {
+            self.registered_controllers.entry(address).or_default()
+        } else {
+            self.assignable_controllers.entry(address).or_default()
+        }
+    }
+}
+
+/// Control changes which are sent by the state chase.
+fn is_chased_control(control: u7) -> bool {
+    !matches!(
+        u8::from(control),
+        BANK_SELECT_MSB
+            | BANK_SELECT_LSB
+            | DATA_ENTRY_MSB
+            | DATA_ENTRY_LSB
+            | DATA_INCREMENT
+            | DATA_DECREMENT
+            | NRPN_LSB
+            | NRPN_MSB
+            | RPN_LSB
+            | RPN_MSB
+            | CHANNEL_MODE_MESSAGES..
+    )
+}
+
+impl Note {
+    /// The registered per-note controller with the given index.
+    pub fn registered_controller(&self, index: u8) -> Option<Controller> {
+        self.registered_controllers
+            .get(&index)
+            .map(|data| controller::from_index_and_data(index, *data))
+    }
+
+    pub fn assignable_controller(&self, index: u8) -> Option<u32> {
+        self.assignable_controllers.get(&index).copied()
+    }
+
+    /// The per-note pitch bend.
+    ///
+    /// A [PerNoteManagement](crate::channel_voice2::PerNoteManagement) reset
+    /// returns it to the center value.
+    pub fn pitch_bend(&self) -> Option<u32> {
+        self.pitch_bend
+    }
+
+    pub fn key_pressure(&self) -> Option<u32> {
+        self.key_pressure
+    }
+}
+
+/// Relative controller data is a two's complement delta.
+fn apply_delta(value: u32, delta: u32) -> u32 {
+    let result = value as i64 + delta as i32 as i64;
+    result.clamp(0, u32::MAX as i64) as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::channel_voice2::{
+        AssignablePerNoteController, ChannelPitchBend, PerNoteManagement, PerNotePitchBend,
+        ProgramChange, RegisteredController, RegisteredPerNoteController,
+        RelativeAssignableController, RelativeRegisteredController,
+    };
+    use crate::traits::Data;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn unknown_channel() {
+        assert_eq!(ChannelState::new().channel(u4::new(0), u4::new(0)), None);
+    }
+
+    #[test]
+    fn channels_are_independent() {
+        let mut state = ChannelState::new();
+        let mut message = ChannelPitchBend::<[u32; 4]>::new();
+        message.set_group(u4::new(0x2));
+        message.set_channel(u4::new(0x5));
+        message.set_pitch_bend_data(0x1234_5678);
+        state.receive(&message.into());
+
+        assert_eq!(
+            state
+                .channel(u4::new(0x2), u4::new(0x5))
+                .unwrap()
+                .pitch_bend(),
+            Some(0x1234_5678)
+        );
+        assert_eq!(state.channel(u4::new(0x0), u4::new(0x5)), None);
+        assert_eq!(state.channels().count(), 1);
+    }
+
+    #[test]
+    fn program_change_keeps_previous_bank() {
+        let mut state = ChannelState::new();
+        let mut message = ProgramChange::<[u32; 4]>::new();
+        message.set_program(u7::new(0x10));
+        message.set_bank(Some(u14::new(0x0102)));
+        state.receive(&message.clone().into());
+        message.set_program(u7::new(0x11));
+        message.set_bank(None);
+        state.receive(&message.into());
+
+        let channel = state.channel(u4::new(0), u4::new(0)).unwrap();
+        assert_eq!(channel.program(), Some(u7::new(0x11)));
+        assert_eq!(channel.bank(), Some(u14::new(0x0102)));
+    }
+
+    #[test]
+    fn relative_registered_controller() {
+        let mut state = ChannelState::new();
+        let mut absolute = RegisteredController::<[u32; 4]>::new();
+        absolute.set_bank(u7::new(0x0));
+        absolute.set_index(u7::new(0x1));
+        absolute.set_controller_data(0x1000);
+        state.receive(&absolute.into());
+
+        let mut relative = RelativeRegisteredController::<[u32; 4]>::new();
+        relative.set_bank(u7::new(0x0));
+        relative.set_index(u7::new(0x1));
+        relative.set_controller_data((-0x100_i32) as u32);
+        state.receive(&relative.into());
+
+        assert_eq!(
+            state
+                .channel(u4::new(0), u4::new(0))
+                .unwrap()
+                .registered_controller(u7::new(0x0), u7::new(0x1)),
+            Some(0x0F00)
+        );
+    }
+
+    #[test]
+    fn relative_assignable_controller_saturates() {
+        let mut state = ChannelState::new();
+        let mut relative = RelativeAssignableController::<[u32; 4]>::new();
+        relative.set_bank(u7::new(0x2));
+        relative.set_index(u7::new(0x3));
+        relative.set_controller_data((-1_i32) as u32);
+        state.receive(&relative.clone().into());
+
+        let channel = state.channel(u4::new(0), u4::new(0)).unwrap();
+        assert_eq!(
+            channel.assignable_controller(u7::new(0x2), u7::new(0x3)),
+            Some(0x0)
+        );
+
+        relative.set_controller_data(i32::MAX as u32);
+        state.receive(&relative.clone().into());
+        state.receive(&relative.clone().into());
+        state.receive(&relative.into());
+        let channel = state.channel(u4::new(0), u4::new(0)).unwrap();
+        assert_eq!(
+            channel.assignable_controller(u7::new(0x2), u7::new(0x3)),
+            Some(u32::MAX)
+        );
+    }
+
+    #[test]
+    fn per_note_controllers() {
+        let mut state = ChannelState::new();
+        let mut registered = RegisteredPerNoteController::<[u32; 4]>::new();
+        registered.set_note_number(u7::new(0x3C));
+        registered.set_controller(Controller::Volume(0xABCD));
+        state.receive(&registered.into());
+
+        let mut assignable = AssignablePerNoteController::<[u32; 4]>::new();
+        assignable.set_note_number(u7::new(0x3C));
+        assignable.set_index(0x10);
+        assignable.set_controller_data(0x1234);
+        state.receive(&assignable.into());
+
+        let note = state
+            .channel(u4::new(0), u4::new(0))
+            .unwrap()
+            .note(u7::new(0x3C))
+            .unwrap();
+        assert_eq!(
+            note.registered_controller(7),
+            Some(Controller::Volume(0xABCD))
+        );
+        assert_eq!(note.assignable_controller(0x10), Some(0x1234));
+        assert_eq!(note.assignable_controller(0x11), None);
+    }
+
+    fn note_with_pitch_bend() -> ChannelState {
+        let mut state = ChannelState::new();
+        let mut pitch_bend = PerNotePitchBend::<[u32; 4]>::new();
+        pitch_bend.set_note_number(u7::new(0x3C));
+        pitch_bend.set_pitch_bend_data(0x9000_0000);
+        state.receive(&pitch_bend.into());
+        state
+    }
+
+    #[test]
+    fn per_note_management_detach() {
+        let mut state = note_with_pitch_bend();
+        let mut management = PerNoteManagement::<[u32; 4]>::new();
+        management.set_note_number(u7::new(0x3C));
+        management.set_detach(true);
+        state.receive(&management.into());
+
+        let channel = state.channel(u4::new(0), u4::new(0)).unwrap();
+        assert_eq!(channel.note(u7::new(0x3C)), None);
+    }
+
+    #[test]
+    fn per_note_management_reset() {
+        let mut state = note_with_pitch_bend();
+        let mut management = PerNoteManagement::<[u32; 4]>::new();
+        management.set_note_number(u7::new(0x3C));
+        management.set_reset(true);
+        state.receive(&management.into());
+
+        let note = state
+            .channel(u4::new(0), u4::new(0))
+            .unwrap()
+            .note(u7::new(0x3C))
+            .unwrap();
+        assert_eq!(note.pitch_bend(), Some(PITCH_BEND_CENTER));
+    }
+
+    #[test]
+    fn snapshot() {
+        let mut state = note_with_pitch_bend();
+        let snapshot = state.clone();
+        state.clear();
+        assert_eq!(state.channels().count(), 0);
+        assert_eq!(snapshot.channels().count(), 1);
+    }
+
+    #[test]
+    fn chase_midi2() {
+        let mut state = ChannelState::new();
+        for message in [
+            &[0x40E1_0000, 0x1234_5678][..],
+            &[0x4021_0001, 0xAAAA_AAAA][..],
+            &[0x40B1_0A00, 0x8000_0000][..],
+            &[0x40B1_6500, 0xFFFF_FFFF][..],
+            &[0x40B1_7B00, 0x0000_0000][..],
+            &[0x40C1_0001, 0x0500_0302][..],
+            &[0x4091_3C00, 0x8000_0000][..],
+        ] {
+            state.receive(&ChannelVoice2::try_from(message).unwrap());
+        }
+        assert_eq!(
+            state
+                .chase_midi2()
+                .iter()
+                .map(|m| m.data().to_vec())
+                .collect::<std::vec::Vec<_>>(),
+            std::vec![
+                std::vec![0x40C1_0001, 0x0500_0302],
+                std::vec![0x40B1_0A00, 0x8000_0000],
+                std::vec![0x40E1_0000, 0x1234_5678],
+                std::vec![0x4021_0001, 0xAAAA_AAAA],
+            ]
+        );
+    }
+
+    #[test]
+    fn chase_orders_channels() {
+        let mut state = ChannelState::new();
+        for message in [
+            &[0x41E2_0000, 0x8000_0000][..],
+            &[0x40E3_0000, 0x8000_0000][..],
+        ] {
+            state.receive(&ChannelVoice2::try_from(message).unwrap());
+        }
+        assert_eq!(
+            state
+                .chase_midi2()
+                .iter()
+                .map(|m| m.data()[0])
+                .collect::<std::vec::Vec<_>>(),
+            std::vec![0x40E3_0000, 0x41E2_0000]
+        );
+    }
+
+    #[cfg(feature = "channel-voice1")]
+    #[test]
+    fn receive_channel_voice1() {
+        use crate::channel_voice1::ChannelVoice1;
+
+        let mut state = ChannelState::new();
+        for message in [
+            0x20B2_0001,
+            0x20B2_2002,
+            0x20C2_0500,
+            0x20B2_6500,
+            0x20B2_6401,
+            0x20B2_0640,
+            0x20B2_2600,
+            0x20B2_0740,
+            0x20E2_0040,
+        ] {
+            state.receive_channel_voice1(&ChannelVoice1::try_from(&[message][..]).unwrap());
+        }
+        let channel = state.channel(u4::new(0x0), u4::new(0x2)).unwrap();
+        assert_eq!(channel.program(), Some(u7::new(0x05)));
+        assert_eq!(channel.bank(), Some(u14::new(0x0082)));
+        assert_eq!(
+            channel.registered_controller(u7::new(0x00), u7::new(0x01)),
+            Some(0x8000_0000)
+        );
+        assert_eq!(channel.control_change(u7::new(0x07)), Some(0x8000_0000));
+        assert_eq!(channel.control_change(u7::new(0x06)), None);
+        assert_eq!(channel.pitch_bend(), Some(0x8000_0000));
+    }
+
+    #[cfg(feature = "channel-voice1")]
+    #[test]
+    fn data_entry_without_parameter_is_ignored() {
+        use crate::channel_voice1::ChannelVoice1;
+
+        let mut state = ChannelState::new();
+        for message in [0x20B0_0640, 0x20B0_657F, 0x20B0_647F, 0x20B0_0640] {
+            state.receive_channel_voice1(&ChannelVoice1::try_from(&[message][..]).unwrap());
+        }
+        let channel = state.channel(u4::new(0x0), u4::new(0x0)).unwrap();
+        assert_eq!(
+            channel.registered_controller(u7::new(0x7F), u7::new(0x7F)),
+            None
+        );
+        assert_eq!(channel.control_change(u7::new(0x06)), None);
+    }
+
+    #[cfg(feature = "channel-voice1")]
+    #[test]
+    fn data_increment_and_decrement() {
+        use crate::channel_voice1::ChannelVoice1;
+
+        let mut state = ChannelState::new();
+        for message in [
+            // pitch bend sensitivity of two semitones, stepped up twice
+            0x20B0_6500,
+            0x20B0_6400,
+            0x20B0_0602,
+            0x20B0_6000,
+            0x20B0_6000,
+            // nrpn stepped down from zero
+            0x20B0_6301,
+            0x20B0_6202,
+            0x20B0_6100,
+        ] {
+            state.receive_channel_voice1(&ChannelVoice1::try_from(&[message][..]).unwrap());
+        }
+        let channel = state.channel(u4::new(0x0), u4::new(0x0)).unwrap();
+        assert_eq!(
+            channel.registered_controller(u7::new(0x00), u7::new(0x00)),
+            Some(crate::translation::scale_up(0x0102, 14, 32))
+        );
+        assert_eq!(
+            channel.assignable_controller(u7::new(0x01), u7::new(0x02)),
+            Some(0x0)
+        );
+        assert_eq!(
+            state
+                .chase_midi2()
+                .iter()
+                .map(|m| m.data().to_vec())
+                .collect::<std::vec::Vec<_>>(),
+            std::vec![
+                std::vec![0x4020_0000, crate::translation::scale_up(0x0102, 14, 32)],
+                std::vec![0x4030_0102, 0x0],
+            ]
+        );
+    }
+
+    #[cfg(feature = "channel-voice1")]
+    #[test]
+    fn chase_midi1() {
+        use crate::channel_voice1::ChannelVoice1;
+
+        let mut state = ChannelState::new();
+        for message in [
+            0x20B2_0001,
+            0x20B2_2002,
+            0x20C2_0500,
+            0x20B2_6500,
+            0x20B2_6401,
+            0x20B2_0640,
+            0x20B2_2600,
+            0x20B2_0740,
+        ] {
+            state.receive_channel_voice1(&ChannelVoice1::try_from(&[message][..]).unwrap());
+        }
+        assert_eq!(
+            state
+                .chase_midi1()
+                .iter()
+                .map(|m| m.data()[0])
+                .collect::<std::vec::Vec<_>>(),
+            std::vec![
+                0x20B2_0001,
+                0x20B2_2002,
+                0x20C2_0500,
+                0x20B2_0740,
+                0x20B2_6500,
+                0x20B2_6401,
+                0x20B2_0640,
+                0x20B2_2600,
+                0x20B2_657F,
+                0x20B2_647F,
+            ]
+        );
+    }
+}
@@ -1,13 +1,21 @@
-use crate::{
-    detail::{property, BitOps},
-    error::InvalidData,
-};
+use crate::detail::{property, BitOps};
 
+/// The registered per-note controllers.
+///
+/// Sound controllers are numbered `1..=10` and effect depths `1..=5`.
+/// Out of range numbers are clamped when written.
+/// Several sound controllers and effect depths also have a named variant;
+/// these are written with the same index as their numbered counterpart
+/// and are always read back as the numbered variant.
+/// Controllers with an index which isn't defined are read as
+/// [Unknown](Controller::Unknown), keeping the index and data.
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 #[non_exhaustive]
 pub enum Controller {
     Modulation(u32),
     Breath(u32),
+    /// The absolute pitch of the note.
+    /// See [Pitch](crate::channel_voice2::Pitch) for conversions.
     Pitch7_25(crate::num::Fixed7_25),
     Volume(u32),
     Balance(u32),
@@ -25,37 +33,22 @@ pub enum Controller {
     Undefined(u32),
     ReverbSendLevel(u32),
     ChorusSendLevel(u32),
-    SoundController { index: u8, data: u32 },
-    EffectDepth { index: u8, data: u32 },
+    SoundController {
+        index: u8,
+        data: u32,
+    },
+    EffectDepth {
+        index: u8,
+        data: u32,
+    },
+    Unknown {
+        index: u8,
+        data: u32,
+    },
 }
 
-pub fn validate_index(index: u8) -> Result<(), crate::error::InvalidData> {
-    match index {
-        1 => Ok(()),
-        2 => Ok(()),
-        3 => Ok(()),
-        7 => Ok(()),
-        8 => Ok(()),
-        10 => Ok(()),
-        11 => Ok(()),
-        70 => Ok(()),
-        71 => Ok(()),
-        72 => Ok(()),
-        73 => Ok(()),
-        74 => Ok(()),
-        75 => Ok(()),
-        76 => Ok(()),
-        77 => Ok(()),
-        78 => Ok(()),
-        79 => Ok(()),
-        91 => Ok(()),
-        92 => Ok(()),
-        93 => Ok(()),
-        94 => Ok(()),
-        95 => Ok(()),
-        _ => Err(InvalidData("Couldn't interpret controller index")),
-    }
-}
+const SOUND_CONTROLLER_OFFSET: u8 = 69;
+const EFFECT_DEPTH_OFFSET: u8 = 90;
 
 pub fn from_index_and_data(index: u8, data: u32) -> Controller {
     match index {
@@ -66,26 +59,21 @@ pub fn from_index_and_data(index: u8, data: u32) -> Controller {
         8 => Controller::Balance(data),
         10 => Controller::Pan(data),
         11 => Controller::Expression(data),
-        70 => Controller::SoundController { index: 1, data },
-        71 => Controller::SoundController { index: 2, data },
-        72 => Controller::SoundController { index: 3, data },
-        73 => Controller::SoundController { index: 4, data },
-        74 => Controller::SoundController { index: 5, data },
-        75 => Controller::SoundController { index: 6, data },
-        76 => Controller::SoundController { index: 7, data },
-        77 => Controller::SoundController { index: 8, data },
-        78 => Controller::SoundController { index: 9, data },
-        79 => Controller::SoundController { index: 10, data },
-        91 => Controller::EffectDepth { index: 1, data },
-        92 => Controller::EffectDepth { index: 2, data },
-        93 => Controller::EffectDepth { index: 3, data },
-        94 => Controller::EffectDepth { index: 4, data },
-        95 => Controller::EffectDepth { index: 5, data },
-        _ => panic!("Invalid index"),
+        70..=79 => Controller::SoundController {
+            index: index - SOUND_CONTROLLER_OFFSET,
+            data,
+        },
+        91..=95 => Controller::EffectDepth {
+            index: index - EFFECT_DEPTH_OFFSET,
+            data,
+        },
+        _ => Controller::Unknown { index, data },
     }
 }
 
 pub fn to_index_and_data(c: Controller) -> (u8, u32) {
+    let sound_controller = |index: u8| SOUND_CONTROLLER_OFFSET + index.clamp(1, 10);
+    let effect_depth = |index: u8| EFFECT_DEPTH_OFFSET + index.clamp(1, 5);
     match c {
         Controller::Modulation(data) => (1, data),
         Controller::Breath(data) => (2, data),
@@ -94,33 +82,21 @@ pub fn to_index_and_data(c: Controller) -> (u8, u32) {
         Controller::Balance(data) => (8, data),
         Controller::Pan(data) => (10, data),
         Controller::Expression(data) => (11, data),
-        Controller::SoundController { index: 1, data } => (70, data),
-        Controller::SoundVariation(data) => (70, data),
-        Controller::SoundController { index: 2, data } => (71, data),
-        Controller::Timbre(data) => (71, data),
-        Controller::SoundController { index: 3, data } => (72, data),
-        Controller::ReleaseTime(data) => (72, data),
-        Controller::SoundController { index: 4, data } => (73, data),
-        Controller::AttackTime(data) => (73, data),
-        Controller::SoundController { index: 5, data } => (74, data),
-        Controller::Brightness(data) => (74, data),
-        Controller::SoundController { index: 6, data } => (75, data),
-        Controller::DecayTime(data) => (75, data),
-        Controller::SoundController { index: 7, data } => (76, data),
-        Controller::VebratoRate(data) => (76, data),
-        Controller::SoundController { index: 8, data } => (77, data),
-        Controller::VebratoDepth(data) => (77, data),
-        Controller::SoundController { index: 9, data } => (78, data),
-        Controller::VebratoDelay(data) => (78, data),
-        Controller::SoundController { index: 10, data } => (79, data),
-        Controller::Undefined(data) => (79, data),
-        Controller::EffectDepth { index: 1, data } => (91, data),
-        Controller::ReverbSendLevel(data) => (91, data),
-        Controller::EffectDepth { index: 2, data } => (92, data),
-        Controller::EffectDepth { index: 3, data } => (93, data),
-        Controller::ChorusSendLevel(data) => (91, data),
-        Controller::EffectDepth { index: 4, data } => (94, data),
-        _ => unreachable!(),
+        Controller::SoundController { index, data } => (sound_controller(index), data),
+        Controller::SoundVariation(data) => (sound_controller(1), data),
+        Controller::Timbre(data) => (sound_controller(2), data),
+        Controller::ReleaseTime(data) => (sound_controller(3), data),
+        Controller::AttackTime(data) => (sound_controller(4), data),
+        Controller::Brightness(data) => (sound_controller(5), data),
+        Controller::DecayTime(data) => (sound_controller(6), data),
+        Controller::VebratoRate(data) => (sound_controller(7), data),
+        Controller::VebratoDepth(data) => (sound_controller(8), data),
+        Controller::VebratoDelay(data) => (sound_controller(9), data),
+        Controller::Undefined(data) => (sound_controller(10), data),
+        Controller::EffectDepth { index, data } => (effect_depth(index), data),
+        Controller::ReverbSendLevel(data) => (effect_depth(1), data),
+        Controller::ChorusSendLevel(data) => (effect_depth(3), data),
+        Controller::Unknown { index, data } => (index, data),
     }
 }
 
@@ -131,9 +107,8 @@ impl<B: crate::buffer::Ump> property::Property<B> for ControllerProperty {
 }
 
 impl<'a, B: crate::buffer::Ump> property::ReadProperty<'a, B> for ControllerProperty {
-    fn validate(buffer: &B) -> Result<(), crate::error::InvalidData> {
-        let buffer = buffer.buffer();
-        validate_index(buffer[0].octet(3))
+    fn validate(_buffer: &B) -> Result<(), crate::error::InvalidData> {
+        Ok(())
     }
     fn read(buffer: &'a B) -> Self::Type {
         let buffer = buffer.buffer();
@@ -164,3 +139,130 @@ impl core::default::Default for Controller {
         Controller::Modulation(0x0)
     }
 }
+
+impl core::convert::TryFrom<crate::channel_voice2::NoteAttribute> for Controller {
+    type Error = crate::error::InvalidData;
+    /// A [Pitch7_9](crate::channel_voice2::NoteAttribute::Pitch7_9) note attribute
+    /// as the equivalent [Pitch7_25](Controller::Pitch7_25) controller.
+    fn try_from(attribute: crate::channel_voice2::NoteAttribute) -> Result<Self, Self::Error> {
+        match attribute {
+            crate::channel_voice2::NoteAttribute::Pitch7_9(pitch) => Ok(Controller::Pitch7_25(
+                crate::num::Fixed7_25::from_num(pitch),
+            )),
+            _ => Err(crate::error::InvalidData(ERR_NOT_A_PITCH)),
+        }
+    }
+}
+
+impl core::convert::TryFrom<Controller> for crate::channel_voice2::NoteAttribute {
+    type Error = crate::error::InvalidData;
+    /// A [Pitch7_25](Controller::Pitch7_25) controller as a
+    /// [Pitch7_9](crate::channel_voice2::NoteAttribute::Pitch7_9) note attribute,
+    /// discarding the least significant bits of the fraction.
+    fn try_from(controller: Controller) -> Result<Self, Self::Error> {
+        match controller {
+            Controller::Pitch7_25(pitch) => Ok(crate::channel_voice2::NoteAttribute::Pitch7_9(
+                crate::num::Fixed7_9::from_bits((pitch.to_bits() >> 16) as u16),
+            )),
+            _ => Err(crate::error::InvalidData(ERR_NOT_A_PITCH)),
+        }
+    }
+}
+
+const ERR_NOT_A_PITCH: &str = "Expected a pitch 7.25 controller or pitch 7.9 attribute";
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        channel_voice2::NoteAttribute,
+        num::{Fixed7_25, Fixed7_9},
+    };
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn unknown_index() {
+        assert_eq!(
+            from_index_and_data(0x40, 0x1234_5678),
+            Controller::Unknown {
+                index: 0x40,
+                data: 0x1234_5678
+            }
+        );
+        assert_eq!(
+            to_index_and_data(Controller::Unknown {
+                index: 0x40,
+                data: 0x1234_5678
+            }),
+            (0x40, 0x1234_5678)
+        );
+    }
+
+    #[test]
+    fn every_defined_index_round_trips() {
+        for index in [1, 2, 3, 7, 8, 10, 11]
+            .into_iter()
+            .chain(70..=79)
+            .chain(91..=95)
+        {
+            let controller = from_index_and_data(index, 0xABCD_0123);
+            assert!(!matches!(controller, Controller::Unknown { .. }));
+            assert_eq!(to_index_and_data(controller), (index, 0xABCD_0123));
+        }
+    }
+
+    #[test]
+    fn named_variants() {
+        assert_eq!(
+            to_index_and_data(Controller::ChorusSendLevel(0x1)),
+            (93, 0x1)
+        );
+        assert_eq!(
+            to_index_and_data(Controller::ReverbSendLevel(0x1)),
+            (91, 0x1)
+        );
+        assert_eq!(to_index_and_data(Controller::Undefined(0x1)), (79, 0x1));
+    }
+
+    #[test]
+    fn out_of_range_numbers_are_clamped() {
+        assert_eq!(
+            to_index_and_data(Controller::EffectDepth {
+                index: 6,
+                data: 0x1
+            }),
+            (95, 0x1)
+        );
+        assert_eq!(
+            to_index_and_data(Controller::SoundController {
+                index: 0,
+                data: 0x1
+            }),
+            (70, 0x1)
+        );
+    }
+
+    #[test]
+    fn from_pitch7_9_attribute() {
+        assert_eq!(
+            Controller::try_from(NoteAttribute::Pitch7_9(Fixed7_9::from_num(60.5))),
+            Ok(Controller::Pitch7_25(Fixed7_25::from_num(60.5))),
+        );
+    }
+
+    #[test]
+    fn to_pitch7_9_attribute() {
+        assert_eq!(
+            NoteAttribute::try_from(Controller::Pitch7_25(Fixed7_25::from_num(60.5))),
+            Ok(NoteAttribute::Pitch7_9(Fixed7_9::from_num(60.5))),
+        );
+    }
+
+    #[test]
+    fn non_pitch_conversion() {
+        assert_eq!(
+            NoteAttribute::try_from(Controller::Volume(0x0)),
+            Err(crate::error::InvalidData(ERR_NOT_A_PITCH)),
+        );
+    }
+}
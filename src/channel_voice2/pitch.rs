@@ -0,0 +1,212 @@
+use crate::ux::u7;
+
+const A4_NOTE_NUMBER: f64 = 69.0;
+const A4_FREQUENCY: f64 = 440.0;
+
+/// Conversions of the absolute note pitches carried by
+/// [Pitch7_25](crate::channel_voice2::Controller::Pitch7_25) controllers and
+/// [Pitch7_9](crate::channel_voice2::NoteAttribute::Pitch7_9) note attributes.
+///
+/// A pitch is a note number with a fractional part,
+/// with A4 at 440 Hz as note number 69 in twelve tone equal temperament.
+///
+/// ```rust
+/// use midi2::{channel_voice2::Pitch, num::{Fixed7_25, Fixed7_9}, ux::u7};
+///
+/// let pitch = Fixed7_25::from_semitones(69.5);
+/// assert_eq!(pitch.note_number(), u7::new(69));
+/// assert_eq!(pitch.cents(), 50.0);
+/// assert!((pitch.frequency() - 452.893).abs() < 0.001);
+///
+/// let pitch = Fixed7_9::from_frequency(261.6256);
+/// assert_eq!(pitch.note_number(), u7::new(60));
+/// ```
+pub trait Pitch: Sized {
+    /// The pitch in semitones.
+    fn semitones(&self) -> f64;
+
+    /// The pitch closest to the given semitones,
+    /// saturating at the bounds of the representable range.
+    /// NaN gives the lowest pitch.
+    fn from_semitones(semitones: f64) -> Self;
+
+    /// The integer part of the pitch.
+    fn note_number(&self) -> u7 {
+        u7::new(self.semitones() as u8)
+    }
+
+    /// The fractional part of the pitch in cents above the note number.
+    fn cents(&self) -> f64 {
+        (self.semitones() - u8::from(self.note_number()) as f64) * 100.0
+    }
+
+    /// The frequency of the pitch in Hz.
+    fn frequency(&self) -> f64 {
+        A4_FREQUENCY * exp2((self.semitones() - A4_NOTE_NUMBER) / 12.0)
+    }
+
+    /// The pitch closest to the given note number and cents above it.
+    fn from_note_number_and_cents(note_number: u7, cents: f64) -> Self {
+        Self::from_semitones(u8::from(note_number) as f64 + cents / 100.0)
+    }
+
+    /// The pitch closest to the given frequency in Hz.
+    /// NaN gives the lowest pitch.
+    fn from_frequency(frequency: f64) -> Self {
+        Self::from_semitones(A4_NOTE_NUMBER + 12.0 * log2(frequency / A4_FREQUENCY))
+    }
+}
+
+impl Pitch for crate::num::Fixed7_25 {
+    fn semitones(&self) -> f64 {
+        self.to_num()
+    }
+    fn from_semitones(semitones: f64) -> Self {
+        if semitones.is_nan() {
+            return Self::ZERO;
+        }
+        Self::saturating_from_num(semitones)
+    }
+}
+
+impl Pitch for crate::num::Fixed7_9 {
+    fn semitones(&self) -> f64 {
+        self.to_num()
+    }
+    fn from_semitones(semitones: f64) -> Self {
+        if semitones.is_nan() {
+            return Self::ZERO;
+        }
+        Self::saturating_from_num(semitones)
+    }
+}
+
+// core has no floating point transcendental functions,
+// the ranges involved here are small enough for plain series.
+
+pub(crate) fn exp2(x: f64) -> f64 {
+    if x.is_nan() {
+        return x;
+    }
+    // beyond the exponent range of f64
+    if x >= 1024.0 {
+        return f64::INFINITY;
+    }
+    if x < -1022.0 {
+        return 0.0;
+    }
+    let whole = if (x as i32) as f64 > x {
+        x as i32 - 1
+    } else {
+        x as i32
+    };
+    let fraction = (x - whole as f64) * core::f64::consts::LN_2;
+    let mut term = 1.0;
+    let mut sum = 1.0;
+    for n in 1..20 {
+        term *= fraction / n as f64;
+        sum += term;
+    }
+    sum * f64::from_bits(((whole + 1023) as u64) << 52)
+}
+
+pub(crate) fn log2(x: f64) -> f64 {
+    if x.is_nan() {
+        return x;
+    }
+    if x <= 0.0 {
+        return f64::NEG_INFINITY;
+    }
+    // split into exponent and a mantissa in [1, 2)
+    let bits = x.to_bits();
+    let exponent = ((bits >> 52) & 0x7FF) as i64 - 1023;
+    let mantissa = f64::from_bits((bits & 0x000F_FFFF_FFFF_FFFF) | 0x3FF0_0000_0000_0000);
+    // ln(m) = 2 atanh((m - 1) / (m + 1))
+    let z = (mantissa - 1.0) / (mantissa + 1.0);
+    let z2 = z * z;
+    let mut term = z;
+    let mut sum = 0.0;
+    for n in 0..20 {
+        sum += term / (2 * n + 1) as f64;
+        term *= z2;
+    }
+    exponent as f64 + 2.0 * sum / core::f64::consts::LN_2
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::num::{Fixed7_25, Fixed7_9};
+    use pretty_assertions::assert_eq;
+
+    fn assert_close(a: f64, b: f64) {
+        assert!((a - b).abs() < 1e-6, "{a} != {b}");
+    }
+
+    #[test]
+    fn a4_frequency() {
+        assert_close(Fixed7_25::from_semitones(69.0).frequency(), 440.0);
+    }
+
+    #[test]
+    fn octaves() {
+        assert_close(Fixed7_25::from_semitones(57.0).frequency(), 220.0);
+        assert_close(Fixed7_25::from_semitones(93.0).frequency(), 1760.0);
+        assert_close(Fixed7_25::from_semitones(9.0).frequency(), 13.75);
+    }
+
+    #[test]
+    fn middle_c() {
+        assert_close(
+            Fixed7_25::from_semitones(60.0).frequency(),
+            261.625_565_300_6,
+        );
+    }
+
+    #[test]
+    fn from_frequency() {
+        assert_close(Fixed7_25::from_frequency(440.0).semitones(), 69.0);
+        assert_close(
+            Fixed7_25::from_frequency(261.625_565_300_6).semitones(),
+            60.0,
+        );
+        assert_close(Fixed7_25::from_frequency(27.5).semitones(), 21.0);
+    }
+
+    #[test]
+    fn note_number_and_cents() {
+        let pitch = Fixed7_25::from_note_number_and_cents(u7::new(64), 25.0);
+        assert_eq!(pitch.note_number(), u7::new(64));
+        assert_close(pitch.cents(), 25.0);
+    }
+
+    #[test]
+    fn pitch7_9() {
+        let pitch = Fixed7_9::from_semitones(70.5);
+        assert_eq!(pitch.note_number(), u7::new(70));
+        assert_eq!(pitch.cents(), 50.0);
+    }
+
+    #[test]
+    fn exp2_out_of_range() {
+        assert_eq!(exp2(100.0), 2.0_f64.powi(100));
+        assert_eq!(exp2(-100.0), 2.0_f64.powi(-100));
+        assert_eq!(exp2(f64::INFINITY), f64::INFINITY);
+        assert_eq!(exp2(f64::NEG_INFINITY), 0.0);
+        assert!(exp2(f64::NAN).is_nan());
+    }
+
+    #[test]
+    fn saturates() {
+        assert_eq!(Fixed7_25::from_semitones(200.0), Fixed7_25::MAX);
+        assert_eq!(Fixed7_25::from_semitones(-1.0), Fixed7_25::ZERO);
+    }
+
+    #[test]
+    fn nan() {
+        assert_eq!(Fixed7_25::from_semitones(f64::NAN), Fixed7_25::ZERO);
+        assert_eq!(Fixed7_9::from_semitones(f64::NAN), Fixed7_9::ZERO);
+        assert_eq!(Fixed7_25::from_frequency(f64::NAN), Fixed7_25::ZERO);
+        assert_eq!(Fixed7_9::from_frequency(f64::NAN), Fixed7_9::ZERO);
+    }
+}
@@ -1,5 +1,5 @@
 use crate::{
-    channel_voice2::UMP_MESSAGE_TYPE,
+    channel_voice2::{registered_parameter, UMP_MESSAGE_TYPE},
     detail::{common_properties, schema},
     ux::{u4, u7},
 };
@@ -25,6 +25,8 @@ struct RegisteredController {
     index: u7,
     #[property(common_properties::UmpSchemaProperty<u32, schema::Ump<0x0000_0000, 0xFFFF_FFFF, 0x0, 0x0>>)]
     controller_data: u32,
+    #[property(registered_parameter::RegisteredParameterProperty)]
+    registered_parameter: registered_parameter::RegisteredParameter,
 }
 
 #[cfg(test)]
@@ -69,6 +71,28 @@ mod tests {
         );
     }
 
+    #[test]
+    pub fn registered_parameter() {
+        assert_eq!(
+            RegisteredController::try_from(&[0x4A2B_0001, 0x8000_0000][..])
+                .unwrap()
+                .registered_parameter(),
+            registered_parameter::RegisteredParameter::ChannelFineTuning(0),
+        );
+    }
+
+    #[test]
+    pub fn set_registered_parameter() {
+        let mut message = RegisteredController::<[u32; 4]>::new();
+        message.set_registered_parameter(
+            registered_parameter::RegisteredParameter::ChannelCoarseTuning(-1),
+        );
+        assert_eq!(
+            message,
+            RegisteredController([0x4020_0002, 0x7E00_0000, 0x0, 0x0]),
+        );
+    }
+
     #[test]
     pub fn controller_data() {
         assert_eq!(
@@ -0,0 +1,354 @@
+use crate::{
+    detail::{property, BitOps},
+    ux::{u14, u4, u7},
+};
+
+/// The registered parameters defined by the MIDI 1.0 specification and its
+/// recommended practices, with their data decoded.
+///
+/// In a MIDI 1.0 RPN sequence the data is the 14 bit data entry value.
+/// In a MIDI 2.0 [RegisteredController](crate::channel_voice2::RegisteredController)
+/// the data entry value occupies the most significant 14 bits of the controller data.
+///
+/// ```rust
+/// use midi2::{prelude::*, channel_voice2::{RegisteredController, RegisteredParameter}};
+///
+/// let mut message = RegisteredController::<[u32; 4]>::new();
+/// message.set_registered_parameter(RegisteredParameter::PitchBendSensitivity {
+///     semitones: u7::new(12),
+///     cents: u7::new(50),
+/// });
+///
+/// assert_eq!(message.data(), &[0x4020_0000, 0x18C8_0000]);
+/// assert_eq!(message.bank(), u7::new(0x0));
+/// assert_eq!(message.index(), u7::new(0x0));
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum RegisteredParameter {
+    PitchBendSensitivity {
+        semitones: u7,
+        cents: u7,
+    },
+    /// Offset from A440 in units of 100/8192 cents, in the range `-8192..=8191`.
+    ChannelFineTuning(i16),
+    /// Offset in semitones, in the range `-64..=63`.
+    ChannelCoarseTuning(i8),
+    TuningProgramSelect(u7),
+    TuningBankSelect(u7),
+    ModulationDepthRange {
+        semitones: u7,
+        /// In units of 100/128 cents.
+        fraction: u7,
+    },
+    /// Sent on the manager channel of an MPE zone.
+    /// Zero member channels disables the zone.
+    MpeConfiguration {
+        member_channels: u4,
+    },
+    AzimuthAngle(u14),
+    ElevationAngle(u14),
+    Gain(u14),
+    DistanceRatio(u14),
+    MaximumDistance(u14),
+    GainAtMaximumDistance(u14),
+    ReferenceDistanceRatio(u14),
+    PanSpreadAngle(u14),
+    RollAngle(u14),
+    /// Deselects the current parameter so that following data entry is ignored.
+    Null,
+    Unknown {
+        bank: u7,
+        index: u7,
+        data: u32,
+    },
+}
+
+const BANK_GENERAL: u8 = 0x00;
+const BANK_3D_SOUND: u8 = 0x3D;
+const NULL: u8 = 0x7F;
+const DATA_ENTRY_SHIFT: u32 = 18;
+
+impl RegisteredParameter {
+    /// Decode the parameter of a MIDI 1.0 RPN sequence from the
+    /// parameter number and the 14 bit data entry value.
+    pub fn from_data_entry(bank: u7, index: u7, data_entry: u14) -> Self {
+        from_bank_index_and_data(
+            u8::from(bank),
+            u8::from(index),
+            u32::from(u16::from(data_entry)) << DATA_ENTRY_SHIFT,
+        )
+    }
+
+    /// The parameter number as `(bank, index)`,
+    /// sent with the RPN MSB and LSB control changes in MIDI 1.0.
+    pub fn parameter_number(&self) -> (u7, u7) {
+        let (bank, index, _) = to_bank_index_and_data(*self);
+        (u7::new(bank), u7::new(index))
+    }
+
+    /// The 14 bit data entry value of a MIDI 1.0 RPN sequence.
+    pub fn data_entry(&self) -> u14 {
+        let (_, _, data) = to_bank_index_and_data(*self);
+        u14::new((data >> DATA_ENTRY_SHIFT) as u16)
+    }
+
+    /// The MIDI 1.0 control change sequence which sets the parameter:
+    /// RPN MSB, RPN LSB, data entry MSB and data entry LSB.
+    #[cfg(feature = "channel-voice1")]
+    pub fn control_changes(&self) -> [crate::channel_voice1::ControlChange<[u32; 4]>; 4] {
+        let (bank, index) = self.parameter_number();
+        let data_entry = u16::from(self.data_entry());
+        [
+            (101, bank),
+            (100, index),
+            (6, u7::new((data_entry >> 7) as u8)),
+            (38, u7::new((data_entry & 0x7F) as u8)),
+        ]
+        .map(|(control, data)| {
+            let mut message = crate::channel_voice1::ControlChange::<[u32; 4]>::new();
+            message.set_control(u7::new(control));
+            message.set_control_data(data);
+            message
+        })
+    }
+}
+
+pub fn from_bank_index_and_data(bank: u8, index: u8, data: u32) -> RegisteredParameter {
+    use RegisteredParameter::*;
+
+    let data_entry = (data >> DATA_ENTRY_SHIFT) as u16;
+    let msb = u7::new((data_entry >> 7) as u8);
+    let lsb = u7::new((data_entry & 0x7F) as u8);
+    match (bank, index) {
+        (BANK_GENERAL, 0x00) => PitchBendSensitivity {
+            semitones: msb,
+            cents: lsb,
+        },
+        (BANK_GENERAL, 0x01) => ChannelFineTuning(data_entry as i16 - 0x2000),
+        (BANK_GENERAL, 0x02) => ChannelCoarseTuning(u8::from(msb) as i8 - 0x40),
+        (BANK_GENERAL, 0x03) => TuningProgramSelect(msb),
+        (BANK_GENERAL, 0x04) => TuningBankSelect(msb),
+        (BANK_GENERAL, 0x05) => ModulationDepthRange {
+            semitones: msb,
+            fraction: lsb,
+        },
+        (BANK_GENERAL, 0x06) => MpeConfiguration {
+            member_channels: u4::new(u8::from(msb).min(0xF)),
+        },
+        (BANK_3D_SOUND, 0x00) => AzimuthAngle(u14::new(data_entry)),
+        (BANK_3D_SOUND, 0x01) => ElevationAngle(u14::new(data_entry)),
+        (BANK_3D_SOUND, 0x02) => Gain(u14::new(data_entry)),
+        (BANK_3D_SOUND, 0x03) => DistanceRatio(u14::new(data_entry)),
+        (BANK_3D_SOUND, 0x04) => MaximumDistance(u14::new(data_entry)),
+        (BANK_3D_SOUND, 0x05) => GainAtMaximumDistance(u14::new(data_entry)),
+        (BANK_3D_SOUND, 0x06) => ReferenceDistanceRatio(u14::new(data_entry)),
+        (BANK_3D_SOUND, 0x07) => PanSpreadAngle(u14::new(data_entry)),
+        (BANK_3D_SOUND, 0x08) => RollAngle(u14::new(data_entry)),
+        (NULL, NULL) => Null,
+        _ => Unknown {
+            bank: u7::new(bank),
+            index: u7::new(index),
+            data,
+        },
+    }
+}
+
+pub fn to_bank_index_and_data(parameter: RegisteredParameter) -> (u8, u8, u32) {
+    use RegisteredParameter::*;
+
+    let msb_lsb = |msb: u7, lsb: u7| (u8::from(msb) as u16) << 7 | u8::from(lsb) as u16;
+    let (bank, index, data_entry) = match parameter {
+        PitchBendSensitivity { semitones, cents } => {
+            (BANK_GENERAL, 0x00, msb_lsb(semitones, cents))
+        }
+        ChannelFineTuning(offset) => (
+            BANK_GENERAL,
+            0x01,
+            (offset.clamp(-0x2000, 0x1FFF) + 0x2000) as u16,
+        ),
+        ChannelCoarseTuning(offset) => (
+            BANK_GENERAL,
+            0x02,
+            ((offset.clamp(-0x40, 0x3F) + 0x40) as u16) << 7,
+        ),
+        TuningProgramSelect(program) => (BANK_GENERAL, 0x03, msb_lsb(program, u7::new(0))),
+        TuningBankSelect(bank) => (BANK_GENERAL, 0x04, msb_lsb(bank, u7::new(0))),
+        ModulationDepthRange {
+            semitones,
+            fraction,
+        } => (BANK_GENERAL, 0x05, msb_lsb(semitones, fraction)),
+        MpeConfiguration { member_channels } => {
+            (BANK_GENERAL, 0x06, (u8::from(member_channels) as u16) << 7)
+        }
+        AzimuthAngle(v) => (BANK_3D_SOUND, 0x00, u16::from(v)),
+        ElevationAngle(v) => (BANK_3D_SOUND, 0x01, u16::from(v)),
+        Gain(v) => (BANK_3D_SOUND, 0x02, u16::from(v)),
+        DistanceRatio(v) => (BANK_3D_SOUND, 0x03, u16::from(v)),
+        MaximumDistance(v) => (BANK_3D_SOUND, 0x04, u16::from(v)),
+        GainAtMaximumDistance(v) => (BANK_3D_SOUND, 0x05, u16::from(v)),
+        ReferenceDistanceRatio(v) => (BANK_3D_SOUND, 0x06, u16::from(v)),
+        PanSpreadAngle(v) => (BANK_3D_SOUND, 0x07, u16::from(v)),
+        RollAngle(v) => (BANK_3D_SOUND, 0x08, u16::from(v)),
+        Null => (NULL, NULL, 0x0),
+        Unknown { bank, index, data } => return (u8::from(bank), u8::from(index), data),
+    };
+    (bank, index, (data_entry as u32) << DATA_ENTRY_SHIFT)
+}
+
+pub struct RegisteredParameterProperty;
+
+impl<B: crate::buffer::Ump> property::Property<B> for RegisteredParameterProperty {
+    type Type = RegisteredParameter;
+}
+
+impl<'a, B: crate::buffer::Ump> property::ReadProperty<'a, B> for RegisteredParameterProperty {
+    fn validate(_buffer: &B) -> Result<(), crate::error::InvalidData> {
+        Ok(())
+    }
+    fn read(buffer: &'a B) -> Self::Type {
+        let buffer = buffer.buffer();
+        from_bank_index_and_data(
+            buffer[0].octet(2) & 0x7F,
+            buffer[0].octet(3) & 0x7F,
+            buffer[1],
+        )
+    }
+}
+
+impl<B: crate::buffer::Ump + crate::buffer::BufferMut> property::WriteProperty<B>
+    for RegisteredParameterProperty
+{
+    fn validate(_v: &Self::Type) -> Result<(), crate::error::InvalidData> {
+        Ok(())
+    }
+    fn default() -> Self::Type {
+        Default::default()
+    }
+    fn write(buffer: &mut B, v: Self::Type) {
+        let buffer = buffer.buffer_mut();
+        let (bank, index, data) = to_bank_index_and_data(v);
+        buffer[0].set_octet(2, bank);
+        buffer[0].set_octet(3, index);
+        buffer[1] = data;
+    }
+}
+
+impl core::default::Default for RegisteredParameter {
+    /// Default value is RegisteredParameter::PitchBendSensitivity with zero range
+    fn default() -> Self {
+        RegisteredParameter::PitchBendSensitivity {
+            semitones: u7::new(0),
+            cents: u7::new(0),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn pitch_bend_sensitivity() {
+        assert_eq!(
+            RegisteredParameter::from_data_entry(u7::new(0x0), u7::new(0x0), u14::new(0x0C << 7)),
+            RegisteredParameter::PitchBendSensitivity {
+                semitones: u7::new(12),
+                cents: u7::new(0),
+            }
+        );
+    }
+
+    #[test]
+    fn fine_tuning() {
+        assert_eq!(
+            RegisteredParameter::from_data_entry(u7::new(0x0), u7::new(0x1), u14::new(0x0000)),
+            RegisteredParameter::ChannelFineTuning(-8192),
+        );
+        assert_eq!(
+            RegisteredParameter::ChannelFineTuning(0).data_entry(),
+            u14::new(0x2000)
+        );
+    }
+
+    #[test]
+    fn coarse_tuning() {
+        assert_eq!(
+            RegisteredParameter::from_data_entry(u7::new(0x0), u7::new(0x2), u14::new(0x3E << 7)),
+            RegisteredParameter::ChannelCoarseTuning(-2),
+        );
+        assert_eq!(
+            RegisteredParameter::ChannelCoarseTuning(12).data_entry(),
+            u14::new(0x4C << 7)
+        );
+    }
+
+    #[test]
+    fn mpe_configuration() {
+        let parameter = RegisteredParameter::MpeConfiguration {
+            member_channels: u4::new(0xF),
+        };
+        assert_eq!(parameter.parameter_number(), (u7::new(0x0), u7::new(0x6)));
+        assert_eq!(parameter.data_entry(), u14::new(0x0F << 7));
+    }
+
+    #[test]
+    fn three_dimensional_sound() {
+        let parameter = RegisteredParameter::RollAngle(u14::new(0x1234));
+        assert_eq!(parameter.parameter_number(), (u7::new(0x3D), u7::new(0x8)));
+        assert_eq!(
+            RegisteredParameter::from_data_entry(u7::new(0x3D), u7::new(0x8), u14::new(0x1234)),
+            parameter,
+        );
+    }
+
+    #[test]
+    fn null() {
+        assert_eq!(
+            RegisteredParameter::from_data_entry(u7::new(0x7F), u7::new(0x7F), u14::new(0x0)),
+            RegisteredParameter::Null,
+        );
+    }
+
+    #[test]
+    fn unknown_keeps_data() {
+        let parameter = from_bank_index_and_data(0x10, 0x20, 0x1234_5678);
+        assert_eq!(
+            parameter,
+            RegisteredParameter::Unknown {
+                bank: u7::new(0x10),
+                index: u7::new(0x20),
+                data: 0x1234_5678,
+            }
+        );
+        assert_eq!(to_bank_index_and_data(parameter), (0x10, 0x20, 0x1234_5678));
+    }
+
+    #[test]
+    fn reads_scaled_up_data() {
+        // 0x3FFF scaled up to 32 bits with the min-center-max algorithm
+        assert_eq!(
+            from_bank_index_and_data(0x0, 0x5, 0xFFFF_FFFF),
+            RegisteredParameter::ModulationDepthRange {
+                semitones: u7::new(0x7F),
+                fraction: u7::new(0x7F),
+            }
+        );
+    }
+
+    #[cfg(feature = "channel-voice1")]
+    #[test]
+    fn control_changes() {
+        use crate::traits::Data;
+
+        let parameter = RegisteredParameter::PitchBendSensitivity {
+            semitones: u7::new(2),
+            cents: u7::new(0x10),
+        };
+        assert_eq!(
+            parameter.control_changes().map(|m| m.data()[0]),
+            [0x20B0_6500, 0x20B0_6400, 0x20B0_0602, 0x20B0_2610],
+        );
+    }
+}
@@ -65,4 +65,17 @@ mod tests {
             controller::Controller::Volume(0xE1E35E92),
         );
     }
+
+    #[test]
+    fn unknown_controller() {
+        assert_eq!(
+            RegisteredPerNoteController::try_from(&[0x4405_6C40, 0x1234_5678][..])
+                .unwrap()
+                .controller(),
+            controller::Controller::Unknown {
+                index: 0x40,
+                data: 0x1234_5678
+            },
+        );
+    }
 }
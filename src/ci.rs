@@ -1,22 +1,30 @@
 #![doc = include_str!("ci/README.md")]
 
+mod agent;
 mod common_properties;
 mod device_id;
 mod discovery;
+mod discovery_reply;
+mod invalidate_muid;
+mod muid;
 mod version;
 
+pub use agent::*;
 pub use device_id::*;
 pub use discovery::*;
+pub use discovery_reply::*;
+pub use invalidate_muid::*;
+pub use muid::*;
 pub use version::*;
 
 pub trait Ci<B: crate::buffer::Bytes> {
     fn device_id(&self) -> device_id::DeviceId
     where
         Self: version::CiVersion<0x1>;
-    fn source(&self) -> ux::u28
+    fn source(&self) -> muid::Muid
     where
         Self: version::CiVersion<0x1>;
-    fn destination(&self) -> ux::u28
+    fn destination(&self) -> muid::Muid
     where
         Self: version::CiVersion<0x1>;
 }
@@ -0,0 +1,717 @@
+use crate::{
+    ci::{
+        discovery, discovery_reply, invalidate_muid, DiscoveryQuery, DiscoveryReply,
+        InvalidateMuid, Muid, Rng,
+    },
+    detail::{Encode7Bit, Queue},
+    sysex7::Sysex7,
+    traits::Sysex,
+};
+use core::time::Duration;
+
+/// The size of the buffers backing the messages produced by an [Agent].
+pub const AGENT_MESSAGE_SIZE: usize = 64;
+
+/// The type of the messages produced by an [Agent].
+pub type AgentMessage = Sysex7<[u8; AGENT_MESSAGE_SIZE]>;
+
+// discovery and MUID management address the whole function block
+const FUNCTION_BLOCK_DEVICE_ID: u8 = 0x7F;
+
+const DEFAULT_REPLY_TIMEOUT: Duration = Duration::from_secs(3);
+const QUEUE_SIZE: usize = 8;
+
+/// A monotonic time source used to enforce reply timeouts.
+pub trait Clock {
+    fn now(&self) -> Duration;
+}
+
+impl<F: Fn() -> Duration> Clock for F {
+    fn now(&self) -> Duration {
+        self()
+    }
+}
+
+/// The identity and capabilities a device advertises during discovery.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct DeviceInfo {
+    pub manufacturer: crate::ManufacturerId,
+    pub family: ux::u14,
+    pub model: ux::u14,
+    pub software_version: [ux::u7; 4],
+    pub process_inquiry_supported: bool,
+    pub property_exchange_supported: bool,
+    pub profile_configuration_supported: bool,
+    pub protocol_negotiation_supported: bool,
+    pub max_sysex_size: ux::u28,
+}
+
+/// A device discovered by an [Agent].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RemoteDevice {
+    pub muid: Muid,
+    pub version: u8,
+    pub info: DeviceInfo,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Event {
+    /// A new remote device answered a discovery or sent us a discovery query.
+    DeviceDiscovered(RemoteDevice),
+    /// A remote device has been invalidated and removed from the device table.
+    DeviceRemoved(Muid),
+    /// Our MUID has been regenerated, either because of a collision
+    /// or because a remote device invalidated it.
+    MuidChanged { old: Muid, new: Muid },
+    /// The reply window opened by [Agent::discover] has elapsed.
+    DiscoveryFinished,
+}
+
+/// A transport agnostic MIDI-CI agent.
+///
+/// The agent owns a randomly generated MUID, answers discovery queries
+/// and keeps a table of the remote devices it has discovered.
+/// Received messages are fed in with [Agent::receive] and the
+/// messages the agent wants to send are retrieved with [Agent::poll_message].
+///
+/// The agent doesn't allocate. At most `MAX_DEVICES` remote devices are tracked,
+/// further devices are ignored. The outgoing message and event queues should be
+/// drained after each call to [Agent::receive] or [Agent::discover]; if they
+/// overflow the oldest entries are dropped.
+///
+/// ```rust
+/// use midi2::{ci::{Agent, DeviceInfo}, Sysex};
+/// use core::time::Duration;
+///
+/// let mut seed = 0x1234_5678_u32;
+/// let rng = move || {
+///     seed = seed.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+///     seed
+/// };
+/// let mut agent = Agent::<_, _>::new(|| Duration::ZERO, rng, DeviceInfo::default());
+///
+/// agent.discover();
+/// let query = agent.poll_message().unwrap();
+/// assert_eq!(query.payload().nth(3), Some(ux::u7::new(0x70)));
+/// ```
+#[derive(Debug)]
+pub struct Agent<C: Clock, R: Rng, const MAX_DEVICES: usize = 8> {
+    clock: C,
+    rng: R,
+    info: DeviceInfo,
+    muid: Muid,
+    reply_timeout: Duration,
+    discovery_deadline: Option<Duration>,
+    remote_devices: [Option<RemoteDevice>; MAX_DEVICES],
+    messages: Queue<AgentMessage, QUEUE_SIZE>,
+    events: Queue<Event, QUEUE_SIZE>,
+}
+
+impl<C: Clock, R: Rng, const MAX_DEVICES: usize> Agent<C, R, MAX_DEVICES> {
+    pub fn new(clock: C, mut rng: R, info: DeviceInfo) -> Self {
+        let muid = Muid::random(&mut rng);
+        Agent {
+            clock,
+            rng,
+            info,
+            muid,
+            reply_timeout: DEFAULT_REPLY_TIMEOUT,
+            discovery_deadline: None,
+            remote_devices: [None; MAX_DEVICES],
+            messages: Default::default(),
+            events: Default::default(),
+        }
+    }
+
+    /// The MUID currently used by the agent.
+    pub fn muid(&self) -> Muid {
+        self.muid
+    }
+
+    pub fn info(&self) -> &DeviceInfo {
+        &self.info
+    }
+
+    /// The time window in which discovery replies are accepted.
+    ///
+    /// Defaults to 3 seconds.
+    pub fn set_reply_timeout(&mut self, timeout: Duration) {
+        self.reply_timeout = timeout;
+    }
+
+    pub fn remote_devices(&self) -> impl Iterator<Item = &RemoteDevice> {
+        self.remote_devices.iter().flatten()
+    }
+
+    /// Broadcast a discovery query and open the reply window.
+    pub fn discover(&mut self) {
+        let mut message = DiscoveryQuery::<0x2, [u8; AGENT_MESSAGE_SIZE]>::try_new()
+            .expect("Buffer is large enough");
+        message.set_source(self.muid);
+        message.set_device_manufacturer(self.info.manufacturer);
+        message.set_device_family(self.info.family);
+        message.set_model_number(self.info.model);
+        message.set_software_version(self.info.software_version);
+        message.set_process_inquiry_supported(self.info.process_inquiry_supported);
+        message.set_property_exchange_supported(self.info.property_exchange_supported);
+        message.set_profile_configuration_supported(self.info.profile_configuration_supported);
+        message.set_protocol_negotiation_supported(self.info.protocol_negotiation_supported);
+        message.set_max_sysex_size(self.info.max_sysex_size);
+        self.messages.push(message.into());
+        self.discovery_deadline = Some(self.clock.now() + self.reply_timeout);
+    }
+
+    /// Whether the reply window opened by [Agent::discover] is still open.
+    pub fn is_discovering(&mut self) -> bool {
+        self.update_timers();
+        self.discovery_deadline.is_some()
+    }
+
+    pub fn poll_message(&mut self) -> Option<AgentMessage> {
+        self.update_timers();
+        self.messages.pop()
+    }
+
+    pub fn poll_event(&mut self) -> Option<Event> {
+        self.update_timers();
+        self.events.pop()
+    }
+
+    /// Process a received sysex message.
+    ///
+    /// Messages which aren't MIDI-CI, which aren't addressed to the function block,
+    /// or which the agent doesn't handle, are ignored.
+    /// Discovery queries are only handled when sent to the broadcast MUID.
+    /// Malformed MIDI-CI messages are reported as errors.
+    pub fn receive<B: crate::buffer::Buffer>(
+        &mut self,
+        message: &Sysex7<B>,
+    ) -> Result<(), crate::error::InvalidData> {
+        self.update_timers();
+
+        let mut buffer = [0x0_u8; AGENT_MESSAGE_SIZE];
+        let Some(size) = copy_message(message, &mut buffer) else {
+            return Ok(());
+        };
+        if size < 15 || buffer[1] != 0x7E || buffer[3] != 0x0D {
+            return Ok(());
+        }
+        if buffer[2] != FUNCTION_BLOCK_DEVICE_ID {
+            return Ok(());
+        }
+
+        let version = buffer[5];
+        if version == 0 {
+            return Err(crate::error::InvalidData("Unsupported CI version"));
+        }
+        // newer versions are backward compatible with the fields we understand
+        buffer[5] = version.min(0x2);
+        let data = &buffer[..size];
+
+        let source = Muid::from_u7s(&data[6..10]);
+        if data[4] == discovery::STATUS && !Muid::from_u7s(&data[10..14]).is_broadcast() {
+            return Ok(());
+        }
+        if source == self.muid {
+            self.resolve_collision();
+            return Ok(());
+        }
+
+        match data[4] {
+            discovery::STATUS => {
+                let info = if version == 0x1 {
+                    query_info(&DiscoveryQuery::<0x1, _>::try_from(data)?)
+                } else {
+                    query_info(&DiscoveryQuery::<0x2, _>::try_from(data)?)
+                };
+                self.add_remote_device(RemoteDevice {
+                    muid: source,
+                    version,
+                    info,
+                });
+                self.reply_to_discovery(source);
+            }
+            discovery_reply::STATUS => {
+                let (destination, info) = if version == 0x1 {
+                    let reply = DiscoveryReply::<0x1, _>::try_from(data)?;
+                    (crate::ci::Ci::destination(&reply), reply_info(&reply))
+                } else {
+                    let reply = DiscoveryReply::<0x2, _>::try_from(data)?;
+                    (crate::ci::Ci::destination(&reply), reply_info(&reply))
+                };
+                if destination == self.muid && self.discovery_deadline.is_some() {
+                    self.add_remote_device(RemoteDevice {
+                        muid: source,
+                        version,
+                        info,
+                    });
+                }
+            }
+            invalidate_muid::STATUS => {
+                let target = if version == 0x1 {
+                    InvalidateMuid::<0x1, _>::try_from(data)?.target_muid()
+                } else {
+                    InvalidateMuid::<0x2, _>::try_from(data)?.target_muid()
+                };
+                if target == self.muid {
+                    self.regenerate_muid();
+                    self.discover();
+                } else {
+                    self.remove_remote_device(target);
+                }
+            }
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    fn update_timers(&mut self) {
+        if let Some(deadline) = self.discovery_deadline {
+            if self.clock.now() >= deadline {
+                self.discovery_deadline = None;
+                self.events.push(Event::DiscoveryFinished);
+            }
+        }
+    }
+
+    fn resolve_collision(&mut self) {
+        let old = self.regenerate_muid();
+        let mut message = InvalidateMuid::<0x2, [u8; AGENT_MESSAGE_SIZE]>::try_new()
+            .expect("Buffer is large enough");
+        message.set_source(self.muid);
+        message.set_target_muid(old);
+        self.messages.push(message.into());
+    }
+
+    fn regenerate_muid(&mut self) -> Muid {
+        let old = self.muid;
+        let remote_devices = &self.remote_devices;
+        self.muid = random_muid(&mut self.rng, |muid| {
+            muid == old || remote_devices.iter().flatten().any(|d| d.muid == muid)
+        });
+        self.events.push(Event::MuidChanged {
+            old,
+            new: self.muid,
+        });
+        old
+    }
+
+    fn reply_to_discovery(&mut self, destination: Muid) {
+        let mut message = DiscoveryReply::<0x2, [u8; AGENT_MESSAGE_SIZE]>::try_new()
+            .expect("Buffer is large enough");
+        message.set_source(self.muid);
+        message.set_destination(destination);
+        message.set_device_manufacturer(self.info.manufacturer);
+        message.set_device_family(self.info.family);
+        message.set_model_number(self.info.model);
+        message.set_software_version(self.info.software_version);
+        message.set_process_inquiry_supported(self.info.process_inquiry_supported);
+        message.set_property_exchange_supported(self.info.property_exchange_supported);
+        message.set_profile_configuration_supported(self.info.profile_configuration_supported);
+        message.set_protocol_negotiation_supported(self.info.protocol_negotiation_supported);
+        message.set_max_sysex_size(self.info.max_sysex_size);
+        self.messages.push(message.into());
+    }
+
+    fn add_remote_device(&mut self, device: RemoteDevice) {
+        if let Some(existing) = self
+            .remote_devices
+            .iter_mut()
+            .flatten()
+            .find(|d| d.muid == device.muid)
+        {
+            *existing = device;
+            return;
+        }
+        if let Some(slot) = self.remote_devices.iter_mut().find(|d| d.is_none()) {
+            *slot = Some(device);
+            self.events.push(Event::DeviceDiscovered(device));
+        }
+    }
+
+    fn remove_remote_device(&mut self, muid: Muid) {
+        for slot in self.remote_devices.iter_mut() {
+            if slot.is_some_and(|d| d.muid == muid) {
+                *slot = None;
+                self.events.push(Event::DeviceRemoved(muid));
+            }
+        }
+    }
+}
+
+fn random_muid<R: Rng>(rng: &mut R, reject: impl Fn(Muid) -> bool) -> Muid {
+    loop {
+        let muid = Muid::random(rng);
+        if !reject(muid) {
+            return muid;
+        }
+    }
+}
+
+fn copy_message<B: crate::buffer::Buffer>(
+    message: &Sysex7<B>,
+    buffer: &mut [u8; AGENT_MESSAGE_SIZE],
+) -> Option<usize> {
+    buffer[0] = 0xF0;
+    let mut size = 1;
+    for byte in message.payload() {
+        if size == AGENT_MESSAGE_SIZE - 1 {
+            return None;
+        }
+        buffer[size] = byte.into();
+        size += 1;
+    }
+    buffer[size] = 0xF7;
+    Some(size + 1)
+}
+
+fn query_info<const VERSION: u8>(message: &DiscoveryQuery<VERSION, &[u8]>) -> DeviceInfo
+where
+    for<'a> DiscoveryQuery<VERSION, &'a [u8]>: crate::ci::CiVersion<0x1>,
+{
+    DeviceInfo {
+        manufacturer: message.device_manufacturer(),
+        family: message.device_family(),
+        model: message.model_number(),
+        software_version: message.software_version(),
+        process_inquiry_supported: message.process_inquiry_supported(),
+        property_exchange_supported: message.property_exchange_supported(),
+        profile_configuration_supported: message.profile_configuration_supported(),
+        protocol_negotiation_supported: message.protocol_negotiation_supported(),
+        max_sysex_size: message.max_sysex_size(),
+    }
+}
+
+fn reply_info<const VERSION: u8>(message: &DiscoveryReply<VERSION, &[u8]>) -> DeviceInfo
+where
+    for<'a> DiscoveryReply<VERSION, &'a [u8]>: crate::ci::CiVersion<0x1>,
+{
+    DeviceInfo {
+        manufacturer: message.device_manufacturer(),
+        family: message.device_family(),
+        model: message.model_number(),
+        software_version: message.software_version(),
+        process_inquiry_supported: message.process_inquiry_supported(),
+        property_exchange_supported: message.property_exchange_supported(),
+        profile_configuration_supported: message.profile_configuration_supported(),
+        protocol_negotiation_supported: message.protocol_negotiation_supported(),
+        max_sysex_size: message.max_sysex_size(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::cell::Cell;
+    use pretty_assertions::assert_eq;
+
+    fn rng(seed: u32) -> impl FnMut() -> u32 {
+        let mut state = seed;
+        move || {
+            state = state.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+            state
+        }
+    }
+
+    fn sequence(values: &'static [u32]) -> impl FnMut() -> u32 {
+        let mut iter = values.iter().cycle();
+        move || *iter.next().unwrap()
+    }
+
+    fn deliver<C1: Clock, R1: Rng, C2: Clock, R2: Rng>(
+        from: &mut Agent<C1, R1>,
+        to: &mut Agent<C2, R2>,
+    ) {
+        while let Some(message) = from.poll_message() {
+            to.receive(&message).unwrap();
+        }
+    }
+
+    fn info() -> DeviceInfo {
+        DeviceInfo {
+            manufacturer: crate::ManufacturerId::Extended(ux::u7::new(0x21), ux::u7::new(0x09)),
+            family: ux::u14::new(0x1234),
+            model: ux::u14::new(0x0567),
+            software_version: [ux::u7::new(0x1); 4],
+            profile_configuration_supported: true,
+            max_sysex_size: ux::u28::new(512),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn muid_is_in_valid_range() {
+        let agent = Agent::<_, _>::new(
+            || Duration::ZERO,
+            sequence(&[0xFFFF_FFFF, 0x0FFF_FF00, 0x1FFF_FEFF]),
+            info(),
+        );
+        assert_eq!(agent.muid(), Muid::new(ux::u28::new(0xFFFFEFF)));
+    }
+
+    #[test]
+    fn discovery_query() {
+        use crate::Data;
+
+        let mut agent = Agent::<_, _>::new(|| Duration::ZERO, sequence(&[0x0123_4567]), info());
+        agent.discover();
+        assert_eq!(
+            agent.poll_message().unwrap().data(),
+            &[
+                0xF0,
+                0x7E,
+                0x7F,
+                0x0D,
+                0x70,
+                0x02,
+                0x67,
+                0x0A,
+                0x0D,
+                0x09,
+                0x7F,
+                0x7F,
+                0x7F,
+                0x7F,
+                0x00,
+                0x21,
+                0x09,
+                0x34,
+                0x24,
+                0x67,
+                0x0A,
+                0x01,
+                0x01,
+                0x01,
+                0x01,
+                0b0000_0100,
+                0x00,
+                0x04,
+                0x00,
+                0x00,
+                0x00,
+                0xF7,
+            ]
+        );
+        assert_eq!(agent.poll_message(), None);
+    }
+
+    #[test]
+    fn two_agents_discover_each_other() {
+        let mut initiator = Agent::<_, _>::new(|| Duration::ZERO, rng(1), info());
+        let mut responder = Agent::<_, _>::new(|| Duration::ZERO, rng(2), DeviceInfo::default());
+
+        initiator.discover();
+        deliver(&mut initiator, &mut responder);
+        deliver(&mut responder, &mut initiator);
+
+        assert_eq!(
+            responder.poll_event(),
+            Some(Event::DeviceDiscovered(RemoteDevice {
+                muid: initiator.muid(),
+                version: 0x2,
+                info: info(),
+            }))
+        );
+        assert_eq!(
+            initiator.poll_event(),
+            Some(Event::DeviceDiscovered(RemoteDevice {
+                muid: responder.muid(),
+                version: 0x2,
+                info: DeviceInfo::default(),
+            }))
+        );
+        assert_eq!(initiator.remote_devices().count(), 1);
+        assert_eq!(responder.remote_devices().count(), 1);
+    }
+
+    #[test]
+    fn rediscovery_does_not_duplicate_remote_devices() {
+        let mut initiator = Agent::<_, _>::new(|| Duration::ZERO, rng(1), info());
+        let mut responder = Agent::<_, _>::new(|| Duration::ZERO, rng(2), info());
+
+        for _ in 0..2 {
+            initiator.discover();
+            deliver(&mut initiator, &mut responder);
+            deliver(&mut responder, &mut initiator);
+        }
+
+        assert_eq!(initiator.remote_devices().count(), 1);
+        assert!(matches!(
+            initiator.poll_event(),
+            Some(Event::DeviceDiscovered(_))
+        ));
+        assert_eq!(initiator.poll_event(), None);
+    }
+
+    #[test]
+    fn replies_after_timeout_are_ignored() {
+        let now = Cell::new(Duration::ZERO);
+        let mut initiator = Agent::<_, _>::new(|| now.get(), rng(1), info());
+        let mut responder = Agent::<_, _>::new(|| Duration::ZERO, rng(2), info());
+        initiator.set_reply_timeout(Duration::from_millis(100));
+
+        initiator.discover();
+        deliver(&mut initiator, &mut responder);
+        now.set(Duration::from_millis(100));
+        assert_eq!(initiator.poll_event(), Some(Event::DiscoveryFinished));
+        assert!(!initiator.is_discovering());
+
+        deliver(&mut responder, &mut initiator);
+        assert_eq!(initiator.remote_devices().count(), 0);
+        assert_eq!(initiator.poll_event(), None);
+    }
+
+    #[test]
+    fn replies_to_other_devices_are_ignored() {
+        let mut initiator = Agent::<_, _>::new(|| Duration::ZERO, rng(1), info());
+        let mut other = Agent::<_, _>::new(|| Duration::ZERO, rng(3), info());
+        let mut responder = Agent::<_, _>::new(|| Duration::ZERO, rng(2), info());
+
+        initiator.discover();
+        other.discover();
+        deliver(&mut other, &mut responder);
+        deliver(&mut responder, &mut initiator);
+
+        assert_eq!(initiator.remote_devices().count(), 0);
+    }
+
+    #[test]
+    fn muid_collision_regenerates_and_invalidates() {
+        let mut first = Agent::<_, _>::new(|| Duration::ZERO, sequence(&[0x1, 0x2]), info());
+        let mut second = Agent::<_, _>::new(|| Duration::ZERO, sequence(&[0x1, 0x3]), info());
+        assert_eq!(first.muid(), second.muid());
+
+        first.discover();
+        deliver(&mut first, &mut second);
+
+        assert_eq!(second.muid(), Muid::new(ux::u28::new(0x3)));
+        assert_eq!(
+            second.poll_event(),
+            Some(Event::MuidChanged {
+                old: Muid::new(ux::u28::new(0x1)),
+                new: Muid::new(ux::u28::new(0x3)),
+            })
+        );
+
+        use crate::Data;
+        let invalidate = second.poll_message().unwrap();
+        assert_eq!(
+            InvalidateMuid::<0x2, _>::try_from(invalidate.data())
+                .unwrap()
+                .target_muid(),
+            Muid::new(ux::u28::new(0x1))
+        );
+    }
+
+    #[test]
+    fn invalidate_targeting_us_regenerates_and_rediscovers() {
+        let mut agent = Agent::<_, _>::new(|| Duration::ZERO, sequence(&[0x1, 0x2]), info());
+        let mut message = InvalidateMuid::<0x2, std::vec::Vec<u8>>::new();
+        message.set_source(Muid::new(ux::u28::new(0x5)));
+        message.set_target_muid(Muid::new(ux::u28::new(0x1)));
+
+        agent.receive(&Sysex7::from(message)).unwrap();
+
+        assert_eq!(agent.muid(), Muid::new(ux::u28::new(0x2)));
+        assert_eq!(
+            agent.poll_event(),
+            Some(Event::MuidChanged {
+                old: Muid::new(ux::u28::new(0x1)),
+                new: Muid::new(ux::u28::new(0x2)),
+            })
+        );
+        assert_eq!(
+            agent.poll_message().unwrap().payload().nth(3),
+            Some(ux::u7::new(discovery::STATUS))
+        );
+        assert!(agent.is_discovering());
+    }
+
+    #[test]
+    fn invalidate_removes_remote_device() {
+        let mut initiator = Agent::<_, _>::new(|| Duration::ZERO, rng(1), info());
+        let mut responder = Agent::<_, _>::new(|| Duration::ZERO, rng(2), info());
+        initiator.discover();
+        deliver(&mut initiator, &mut responder);
+        deliver(&mut responder, &mut initiator);
+        initiator.poll_event();
+
+        let mut message = InvalidateMuid::<0x2, std::vec::Vec<u8>>::new();
+        message.set_source(Muid::new(ux::u28::new(0x5)));
+        message.set_target_muid(responder.muid());
+        initiator.receive(&Sysex7::from(message)).unwrap();
+
+        assert_eq!(
+            initiator.poll_event(),
+            Some(Event::DeviceRemoved(responder.muid()))
+        );
+        assert_eq!(initiator.remote_devices().count(), 0);
+    }
+
+    #[test]
+    fn device_table_is_bounded() {
+        let mut agent = Agent::<_, _, 1>::new(|| Duration::ZERO, sequence(&[0x1]), info());
+        for source in [0x5, 0x6] {
+            let mut message = DiscoveryQuery::<0x2, std::vec::Vec<u8>>::new();
+            message.set_source(Muid::new(ux::u28::new(source)));
+            agent.receive(&Sysex7::from(message)).unwrap();
+        }
+        assert_eq!(agent.remote_devices().count(), 1);
+        assert_eq!(
+            agent.remote_devices().next().unwrap().muid,
+            Muid::new(ux::u28::new(0x5))
+        );
+    }
+
+    #[test]
+    fn other_device_ids_are_ignored() {
+        use crate::Data;
+
+        let mut agent = Agent::<_, _>::new(|| Duration::ZERO, sequence(&[0x1]), info());
+        let mut message = DiscoveryQuery::<0x2, std::vec::Vec<u8>>::new();
+        message.set_source(Muid::new(ux::u28::new(0x1)));
+        let mut data = message.data().to_vec();
+        data[2] = 0x05;
+
+        assert_eq!(agent.receive(&Sysex7::try_from(&data[..]).unwrap()), Ok(()));
+        assert_eq!(agent.muid(), Muid::new(ux::u28::new(0x1)));
+        assert_eq!(agent.poll_event(), None);
+        assert_eq!(agent.poll_message(), None);
+    }
+
+    #[test]
+    fn queries_to_other_muids_are_ignored() {
+        use crate::Data;
+
+        let mut agent = Agent::<_, _>::new(|| Duration::ZERO, sequence(&[0x1]), info());
+        let mut message = DiscoveryQuery::<0x2, std::vec::Vec<u8>>::new();
+        message.set_source(Muid::new(ux::u28::new(0x5)));
+        let mut data = message.data().to_vec();
+        data[10..14].copy_from_slice(&[0x02, 0x00, 0x00, 0x00]);
+
+        assert_eq!(agent.receive(&Sysex7::try_from(&data[..]).unwrap()), Ok(()));
+        assert_eq!(agent.remote_devices().count(), 0);
+        assert_eq!(agent.poll_message(), None);
+    }
+
+    #[test]
+    fn non_ci_messages_are_ignored() {
+        let mut agent = Agent::<_, _>::new(|| Duration::ZERO, rng(1), info());
+        let message = Sysex7::try_from(&[0xF0_u8, 0x43, 0x10, 0x4C, 0xF7][..]).unwrap();
+        assert_eq!(agent.receive(&message), Ok(()));
+        assert_eq!(agent.poll_message(), None);
+    }
+
+    #[test]
+    fn ump_messages() {
+        use crate::traits::TryIntoUmp;
+
+        let mut initiator = Agent::<_, _>::new(|| Duration::ZERO, rng(1), info());
+        let mut responder = Agent::<_, _>::new(|| Duration::ZERO, rng(2), info());
+        initiator.discover();
+        let query: Sysex7<[u32; 64]> = initiator.poll_message().unwrap().try_into_ump().unwrap();
+        responder.receive(&query).unwrap();
+        assert_eq!(responder.remote_devices().count(), 1);
+    }
+}
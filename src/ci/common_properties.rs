@@ -3,7 +3,7 @@ use crate::{
     detail::property,
 };
 
-use super::device_id;
+use super::{device_id, muid};
 
 pub struct UniversalSystemExclusiveByteProperty;
 
@@ -172,7 +172,7 @@ impl<const STATUS: u8, B: Bytes + BufferMut> property::WriteProperty<B>
 pub struct SourceProperty;
 
 impl<B: Bytes> property::Property<B> for SourceProperty {
-    type Type = ux::u28;
+    type Type = muid::Muid;
 }
 
 impl<'a, B: Bytes> property::ReadProperty<'a, B> for SourceProperty {
@@ -181,7 +181,7 @@ impl<'a, B: Bytes> property::ReadProperty<'a, B> for SourceProperty {
     }
     fn read(buffer: &'a B) -> Self::Type {
         use crate::detail::Encode7Bit;
-        ux::u28::from_u7s(&buffer.buffer()[6..10])
+        muid::Muid::from_u7s(&buffer.buffer()[6..10])
     }
 }
 
@@ -201,7 +201,7 @@ impl<B: Bytes + BufferMut> property::WriteProperty<B> for SourceProperty {
 pub struct DestinationProperty;
 
 impl<B: Bytes> property::Property<B> for DestinationProperty {
-    type Type = ux::u28;
+    type Type = muid::Muid;
 }
 
 impl<'a, B: Bytes> property::ReadProperty<'a, B> for DestinationProperty {
@@ -210,7 +210,7 @@ impl<'a, B: Bytes> property::ReadProperty<'a, B> for DestinationProperty {
     }
     fn read(buffer: &'a B) -> Self::Type {
         use crate::detail::Encode7Bit;
-        ux::u28::from_u7s(&buffer.buffer()[10..14])
+        muid::Muid::from_u7s(&buffer.buffer()[10..14])
     }
 }
 
@@ -237,7 +237,7 @@ impl<'a, B: Bytes> property::ReadProperty<'a, B> for BroadcastDestinationPropert
     fn validate(buffer: &B) -> Result<(), crate::error::InvalidData> {
         use crate::detail::Encode7Bit;
 
-        if ux::u28::from_u7s(&buffer.buffer()[10..14]) == ux::u28::MAX {
+        if muid::Muid::from_u7s(&buffer.buffer()[10..14]).is_broadcast() {
             Ok(())
         } else {
             Err(crate::error::InvalidData(
@@ -256,7 +256,7 @@ impl<B: Bytes + BufferMut> property::WriteProperty<B> for BroadcastDestinationPr
     }
     fn write(buffer: &mut B, _v: Self::Type) {
         use crate::detail::Encode7Bit;
-        ux::u28::MAX.to_u7s(&mut buffer.buffer_mut()[10..14]);
+        muid::Muid::BROADCAST.to_u7s(&mut buffer.buffer_mut()[10..14]);
     }
     fn default() -> Self::Type {
         Default::default()
@@ -21,13 +21,13 @@ struct DiscoveryQuery {
     sysex_sub_id2_ci: (),
     #[property(ci::common_properties::SourceProperty)]
     #[version(0x1)]
-    source: ux::u28,
+    source: ci::Muid,
     #[property(ci::common_properties::BroadcastDestinationProperty)]
     #[version(0x1)]
     broadcast_destination: (),
     #[property(DeviceManufacturerProperty)]
     #[version(0x1)]
-    device_manufacturer: [ux::u7; 3],
+    device_manufacturer: crate::ManufacturerId,
     #[property(DeviceFamilyProperty)]
     #[version(0x1)]
     device_family: ux::u14,
@@ -57,22 +57,18 @@ struct DiscoveryQuery {
     output_path_id: ux::u7,
 }
 
-struct DeviceManufacturerProperty;
+pub(crate) struct DeviceManufacturerProperty;
 
 impl<B: crate::buffer::Bytes> property::Property<B> for DeviceManufacturerProperty {
-    type Type = [ux::u7; 3];
+    type Type = crate::ManufacturerId;
 }
 
 impl<'a, B: crate::buffer::Bytes> property::ReadProperty<'a, B> for DeviceManufacturerProperty {
     fn read(buffer: &'a B) -> Self::Type {
-        [
-            ux::u7::new(buffer.buffer()[14]),
-            ux::u7::new(buffer.buffer()[15]),
-            ux::u7::new(buffer.buffer()[16]),
-        ]
+        crate::ManufacturerId::try_from(device_manufacturer_bytes(buffer)).unwrap()
     }
-    fn validate(_buffer: &B) -> Result<(), crate::error::InvalidData> {
-        Ok(())
+    fn validate(buffer: &B) -> Result<(), crate::error::InvalidData> {
+        crate::ManufacturerId::try_from(device_manufacturer_bytes(buffer)).map(|_| ())
     }
 }
 
@@ -86,13 +82,22 @@ impl<B: crate::buffer::Bytes + crate::buffer::BufferMut> property::WriteProperty
         Default::default()
     }
     fn write(buffer: &mut B, v: Self::Type) {
+        let v = <[ux::u7; 3]>::from(v);
         buffer.buffer_mut()[14] = v[0].into();
         buffer.buffer_mut()[15] = v[1].into();
         buffer.buffer_mut()[16] = v[2].into();
     }
 }
 
-struct DeviceFamilyProperty;
+fn device_manufacturer_bytes<B: crate::buffer::Bytes>(buffer: &B) -> [ux::u7; 3] {
+    [
+        ux::u7::new(buffer.buffer()[14]),
+        ux::u7::new(buffer.buffer()[15]),
+        ux::u7::new(buffer.buffer()[16]),
+    ]
+}
+
+pub(crate) struct DeviceFamilyProperty;
 
 impl<B: crate::buffer::Bytes> property::Property<B> for DeviceFamilyProperty {
     type Type = ux::u14;
@@ -123,7 +128,7 @@ impl<B: crate::buffer::Bytes + crate::buffer::BufferMut> property::WriteProperty
     }
 }
 
-struct DeviceModelNumberProperty;
+pub(crate) struct DeviceModelNumberProperty;
 
 impl<B: crate::buffer::Bytes> property::Property<B> for DeviceModelNumberProperty {
     type Type = ux::u14;
@@ -154,7 +159,7 @@ impl<B: crate::buffer::Bytes + crate::buffer::BufferMut> property::WriteProperty
     }
 }
 
-struct SoftwareVersionProperty;
+pub(crate) struct SoftwareVersionProperty;
 
 impl<B: crate::buffer::Bytes> property::Property<B> for SoftwareVersionProperty {
     type Type = [ux::u7; 4];
@@ -191,7 +196,7 @@ impl<B: crate::buffer::Bytes + crate::buffer::BufferMut> property::WriteProperty
     }
 }
 
-struct CiSupportProperty<const BIT: usize>;
+pub(crate) struct CiSupportProperty<const BIT: usize>;
 
 impl<const BIT: usize, B: crate::buffer::Bytes> property::Property<B> for CiSupportProperty<BIT> {
     type Type = bool;
@@ -224,7 +229,7 @@ impl<const BIT: usize, B: crate::buffer::Bytes + crate::buffer::BufferMut>
     }
 }
 
-struct MaxSysexSizeProperty;
+pub(crate) struct MaxSysexSizeProperty;
 
 impl<B: crate::buffer::Bytes> property::Property<B> for MaxSysexSizeProperty {
     type Type = ux::u28;
@@ -255,7 +260,7 @@ impl<B: crate::buffer::Bytes + crate::buffer::BufferMut> property::WriteProperty
     }
 }
 
-struct OutputPathIdProperty;
+pub(crate) struct OutputPathIdProperty;
 
 impl<B: crate::buffer::Bytes> property::Property<B> for OutputPathIdProperty {
     type Type = ux::u7;
@@ -294,8 +299,11 @@ mod tests {
         use crate::Data;
 
         let mut message = DiscoveryQuery::<0x2, std::vec::Vec<u8>>::new();
-        message.set_source(ux::u28::new(0xB48D9D9));
-        message.set_device_manufacturer([ux::u7::new(0x21), ux::u7::new(0x66), ux::u7::new(0x61)]);
+        message.set_source(ci::Muid::new(ux::u28::new(0xB48D9D9)));
+        message.set_device_manufacturer(crate::ManufacturerId::Extended(
+            ux::u7::new(0x66),
+            ux::u7::new(0x61),
+        ));
         message.set_device_family(ux::u14::new(0x278A));
         message.set_model_number(ux::u14::new(0x2269));
         message.set_software_version([
@@ -328,7 +336,7 @@ mod tests {
                 0x7F,
                 0x7F,
                 0x7F,
-                0x21,
+                0x00,
                 0x66,
                 0x61,
                 0x0A,
@@ -422,6 +430,18 @@ mod tests {
         .expect("Valid data");
     }
 
+    #[test]
+    fn try_from_slice_device_manufacturer_non_zero_padding() {
+        assert!(DiscoveryQuery::<0x2, _>::try_from(
+            &[
+                0xF0, 0x7E, 0x7F, 0x0D, 0x70, 0x02, 0x0, 0x0, 0x0, 0x0, 0x7F, 0x7F, 0x7F, 0x7F,
+                0x21, 0x66, 0x61, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0,
+                0x0, 0xF7,
+            ][..],
+        )
+        .is_err());
+    }
+
     #[test]
     fn rebuffer_from() {
         use crate::{Data, RebufferFrom};
@@ -475,15 +495,15 @@ mod tests {
     fn source() {
         use crate::ci::Ci;
         let mut message = DiscoveryQuery::<0x2, std::vec::Vec<u8>>::new();
-        message.set_source(ux::u28::new(0xEAEB42D));
-        assert_eq!(message.source(), ux::u28::new(0xEAEB42D));
+        message.set_source(ci::Muid::new(ux::u28::new(0xEAEB42D)));
+        assert_eq!(message.source(), ci::Muid::new(ux::u28::new(0xEAEB42D)));
     }
 
     #[test]
     fn destination() {
         use crate::ci::Ci;
         let message = DiscoveryQuery::<0x2, std::vec::Vec<u8>>::new();
-        assert_eq!(message.destination(), ux::u28::MAX);
+        assert_eq!(message.destination(), ci::Muid::BROADCAST);
     }
 
     #[test]
@@ -497,13 +517,13 @@ mod tests {
     fn device_manufactuter() {
         use crate::Data;
 
-        let id = [ux::u7::new(0x47), ux::u7::new(0x13), ux::u7::new(0x01)];
+        let id = crate::ManufacturerId::Standard(ux::u7::new(0x47));
 
         let mut message = DiscoveryQuery::<0x2, std::vec::Vec<u8>>::new();
         message.set_device_manufacturer(id);
 
         assert_eq!(message.device_manufacturer(), id);
-        assert_eq!(&message.data()[14..17], &[0x47, 0x13, 0x01]);
+        assert_eq!(&message.data()[14..17], &[0x47, 0x00, 0x00]);
     }
 
     #[test]
@@ -511,11 +531,11 @@ mod tests {
         use crate::Data;
 
         let mut message = DiscoveryQuery::<0x1, std::vec::Vec<u8>>::new();
-        let id = [ux::u7::new(0x47), ux::u7::new(0x13), ux::u7::new(0x01)];
+        let id = crate::ManufacturerId::Extended(ux::u7::new(0x13), ux::u7::new(0x01));
         message.set_device_manufacturer(id);
 
         assert_eq!(message.device_manufacturer(), id);
-        assert_eq!(&message.data()[14..17], &[0x47, 0x13, 0x01]);
+        assert_eq!(&message.data()[14..17], &[0x00, 0x13, 0x01]);
     }
 
     #[test]
@@ -0,0 +1,125 @@
+use crate::{ci, detail::property};
+
+pub(crate) const STATUS: u8 = 0x7E;
+
+#[midi2_proc::generate_ci(
+    SupportedVersion(version = 0x1, min_size = 19),
+    SupportedVersion(version = 0x2, min_size = 19)
+)]
+struct InvalidateMuid {
+    #[property(ci::common_properties::UniversalSystemExclusiveByteProperty)]
+    #[version(0x1)]
+    universal_sysex_byte: (),
+    #[property(ci::common_properties::DeviceIdToFunctionBlockProperty)]
+    #[version(0x1)]
+    device_id: (),
+    #[property(ci::common_properties::UniversalSysexSubId1MidiCi)]
+    #[version(0x1)]
+    sysex_sub_id1_ci: (),
+    #[property(ci::common_properties::UniversalSysexSubId2<STATUS>)]
+    #[version(0x1)]
+    sysex_sub_id2_ci: (),
+    #[property(ci::common_properties::SourceProperty)]
+    #[version(0x1)]
+    source: ci::Muid,
+    #[property(ci::common_properties::BroadcastDestinationProperty)]
+    #[version(0x1)]
+    broadcast_destination: (),
+    #[property(TargetMuidProperty)]
+    #[version(0x1)]
+    target_muid: ci::Muid,
+}
+
+struct TargetMuidProperty;
+
+impl<B: crate::buffer::Bytes> property::Property<B> for TargetMuidProperty {
+    type Type = ci::Muid;
+}
+
+impl<'a, B: crate::buffer::Bytes> property::ReadProperty<'a, B> for TargetMuidProperty {
+    fn validate(_buffer: &B) -> Result<(), crate::error::InvalidData> {
+        Ok(())
+    }
+    fn read(buffer: &'a B) -> Self::Type {
+        use crate::detail::Encode7Bit;
+        ci::Muid::from_u7s(&buffer.buffer()[14..18])
+    }
+}
+
+impl<B: crate::buffer::Bytes + crate::buffer::BufferMut> property::WriteProperty<B>
+    for TargetMuidProperty
+{
+    fn validate(_v: &Self::Type) -> Result<(), crate::error::InvalidData> {
+        Ok(())
+    }
+    fn write(buffer: &mut B, v: Self::Type) {
+        use crate::detail::Encode7Bit;
+        v.to_u7s(&mut buffer.buffer_mut()[14..18]);
+    }
+    fn default() -> Self::Type {
+        Default::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn setters() {
+        use crate::Data;
+
+        let mut message = InvalidateMuid::<0x2, std::vec::Vec<u8>>::new();
+        message.set_source(ci::Muid::new(ux::u28::new(0xB48D9D9)));
+        message.set_target_muid(ci::Muid::new(ux::u28::new(0x1234567)));
+
+        assert_eq!(
+            message.data(),
+            &[
+                0xF0, 0x7E, 0x7F, 0x0D, 0x7E, 0x02, 0x59, 0x33, 0x23, 0x5A, 0x7F, 0x7F, 0x7F, 0x7F,
+                0x67, 0x0A, 0x0D, 0x09, 0xF7,
+            ]
+        );
+    }
+
+    #[test]
+    fn try_new_v1() {
+        use crate::Data;
+
+        let message = InvalidateMuid::<0x1, [u8; 19]>::try_new().expect("Buffer is large enough");
+        assert_eq!(
+            message.data(),
+            &[
+                0xF0, 0x7E, 0x7F, 0x0D, 0x7E, 0x01, 0x0, 0x0, 0x0, 0x0, 0x7F, 0x7F, 0x7F, 0x7F,
+                0x0, 0x0, 0x0, 0x0, 0xF7,
+            ]
+        );
+    }
+
+    #[test]
+    fn target_muid() {
+        assert_eq!(
+            InvalidateMuid::<0x2, _>::try_from(
+                &[
+                    0xF0, 0x7E, 0x7F, 0x0D, 0x7E, 0x02, 0x59, 0x33, 0x23, 0x5A, 0x7F, 0x7F, 0x7F,
+                    0x7F, 0x67, 0x0A, 0x0D, 0x09, 0xF7,
+                ][..]
+            )
+            .unwrap()
+            .target_muid(),
+            ci::Muid::new(ux::u28::new(0x1234567)),
+        );
+    }
+
+    #[test]
+    fn try_from_slice_without_broadcast_destination() {
+        assert!(InvalidateMuid::<0x2, _>::try_from(
+            &[
+                0xF0, 0x7E, 0x7F, 0x0D, 0x7E, 0x02, 0x59, 0x33, 0x23, 0x5A, 0x00, 0x7F, 0x7F, 0x7F,
+                0x67, 0x0A, 0x0D, 0x09, 0xF7,
+            ][..]
+        )
+        .is_err());
+    }
+}
@@ -0,0 +1,129 @@
+use crate::detail::{Byte, Encode7Bit};
+
+const RESERVED_START: u32 = 0x0FFF_FF00;
+
+/// A source of randomness used to generate MUIDs.
+pub trait Rng {
+    fn next_u32(&mut self) -> u32;
+}
+
+impl<F: FnMut() -> u32> Rng for F {
+    fn next_u32(&mut self) -> u32 {
+        self()
+    }
+}
+
+/// A MIDI-CI Unique Identifier.
+///
+/// MUIDs are 28 bit values, transmitted as four 7 bit bytes, least significant first.
+/// The top of the range is reserved: `0x0FFFFF00..=0x0FFFFFFE` may not be used
+/// by devices and `0x0FFFFFFF` addresses every device on the port.
+///
+/// ```rust
+/// use midi2::ci::Muid;
+///
+/// let muid = Muid::new(ux::u28::new(0x0FFF_FF10));
+/// assert!(muid.is_reserved());
+/// assert!(!muid.is_broadcast());
+/// assert!(Muid::BROADCAST.is_broadcast());
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Muid(ux::u28);
+
+impl Muid {
+    /// The MUID addressing every device on the port.
+    pub const BROADCAST: Muid = Muid(ux::u28::MAX);
+
+    pub const fn new(value: ux::u28) -> Self {
+        Muid(value)
+    }
+
+    pub fn value(self) -> ux::u28 {
+        self.0
+    }
+
+    pub fn is_broadcast(self) -> bool {
+        self == Self::BROADCAST
+    }
+
+    /// True for the values reserved by the specification,
+    /// which a device may not use as its own MUID.
+    /// The broadcast MUID is not considered reserved.
+    pub fn is_reserved(self) -> bool {
+        let v = u32::from(self.0);
+        (RESERVED_START..u32::from(ux::u28::MAX)).contains(&v)
+    }
+
+    /// A random MUID which is neither reserved nor broadcast.
+    pub fn random<R: Rng>(rng: &mut R) -> Self {
+        loop {
+            let v = rng.next_u32() & 0x0FFF_FFFF;
+            if v < RESERVED_START {
+                return Muid(ux::u28::new(v));
+            }
+        }
+    }
+}
+
+impl core::convert::From<ux::u28> for Muid {
+    fn from(value: ux::u28) -> Self {
+        Muid(value)
+    }
+}
+
+impl core::convert::From<Muid> for ux::u28 {
+    fn from(value: Muid) -> Self {
+        value.0
+    }
+}
+
+impl Encode7Bit for Muid {
+    fn from_u7s<T: Byte>(u7s: &[T]) -> Self {
+        Muid(ux::u28::from_u7s(u7s))
+    }
+    fn to_u7s<T: Byte>(&self, data: &mut [T]) {
+        self.0.to_u7s(data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn broadcast() {
+        assert_eq!(Muid::BROADCAST.value(), ux::u28::new(0x0FFF_FFFF));
+        assert!(Muid::BROADCAST.is_broadcast());
+        assert!(!Muid::BROADCAST.is_reserved());
+    }
+
+    #[test]
+    fn reserved() {
+        assert!(!Muid::new(ux::u28::new(0x0FFF_FEFF)).is_reserved());
+        assert!(Muid::new(ux::u28::new(0x0FFF_FF00)).is_reserved());
+        assert!(Muid::new(ux::u28::new(0x0FFF_FFFE)).is_reserved());
+    }
+
+    #[test]
+    fn random_skips_reserved_values() {
+        let mut values = [0xFFFF_FFFF_u32, 0x0FFF_FF00, 0x1FFF_FEFF].into_iter();
+        let mut rng = move || values.next().unwrap();
+        assert_eq!(Muid::random(&mut rng), Muid::new(ux::u28::new(0x0FFF_FEFF)));
+    }
+
+    #[test]
+    fn to_u7s() {
+        let mut data = [0x0_u8; 4];
+        Muid::new(ux::u28::new(0xB48D9D9)).to_u7s(&mut data[..]);
+        assert_eq!(data, [0x59, 0x33, 0x23, 0x5A]);
+    }
+
+    #[test]
+    fn from_u7s() {
+        assert_eq!(
+            Muid::from_u7s(&[0x7F_u8, 0x7F, 0x7F, 0x7F][..]),
+            Muid::BROADCAST
+        );
+    }
+}
@@ -1,5 +1,7 @@
 mod bit_ops;
 mod encode_7bit;
+#[cfg(any(feature = "ci", feature = "ump-stream"))]
+mod queue;
 
 pub mod common_properties;
 pub mod helpers;
@@ -10,4 +12,8 @@ pub mod schema;
 pub mod test_support;
 
 pub use bit_ops::BitOps;
+#[cfg(feature = "ci")]
+pub use encode_7bit::Byte;
 pub use encode_7bit::Encode7Bit;
+#[cfg(any(feature = "ci", feature = "ump-stream"))]
+pub use queue::Queue;
@@ -0,0 +1,72 @@
+/// A fixed capacity FIFO queue.
+///
+/// When the queue is full, pushing a new element drops the oldest one.
+#[derive(Clone, Debug)]
+pub struct Queue<T, const N: usize> {
+    data: [Option<T>; N],
+    head: usize,
+    len: usize,
+}
+
+impl<T, const N: usize> Default for Queue<T, N> {
+    fn default() -> Self {
+        Queue {
+            data: core::array::from_fn(|_| None),
+            head: 0,
+            len: 0,
+        }
+    }
+}
+
+impl<T, const N: usize> Queue<T, N> {
+    pub fn push(&mut self, value: T) {
+        if N == 0 {
+            return;
+        }
+        let tail = (self.head + self.len) % N;
+        self.data[tail] = Some(value);
+        if self.len == N {
+            self.head = (self.head + 1) % N;
+        } else {
+            self.len += 1;
+        }
+    }
+
+    pub fn pop(&mut self) -> Option<T> {
+        if self.len == 0 {
+            return None;
+        }
+        let value = self.data[self.head].take();
+        self.head = (self.head + 1) % N;
+        self.len -= 1;
+        value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn push_pop() {
+        let mut queue = Queue::<u8, 3>::default();
+        queue.push(0x1);
+        queue.push(0x2);
+        assert_eq!(queue.pop(), Some(0x1));
+        assert_eq!(queue.pop(), Some(0x2));
+        assert_eq!(queue.pop(), None);
+    }
+
+    #[test]
+    fn push_when_full_drops_oldest() {
+        let mut queue = Queue::<u8, 3>::default();
+        for i in 0..5 {
+            queue.push(i);
+        }
+        assert_eq!(queue.pop(), Some(0x2));
+        assert_eq!(queue.pop(), Some(0x3));
+        assert_eq!(queue.pop(), Some(0x4));
+        assert_eq!(queue.pop(), None);
+    }
+}
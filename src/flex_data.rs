@@ -807,6 +807,7 @@ pub(crate) const PERFORMANCE_TEXT_BANK: u8 = 0x2;
     derive_more::From,
     midi2_proc::Data,
     midi2_proc::Packets,
+    midi2_proc::Remappable,
     midi2_proc::Grouped,
     midi2_proc::RebufferFrom,
     midi2_proc::TryRebufferFrom,
@@ -1048,15 +1049,19 @@ impl<B: Ump + BufferMut> WriteProperty<B> for OptionalChannelProperty {
 
 fn optional_channel_to_slice(data: &mut [u32], channel: Option<crate::ux::u4>) {
     use crate::ux::{u2, u4};
-    match channel {
-        Some(channel) => {
-            data[0].set_crumb(5, u2::new(0x0));
-            data[0].set_nibble(3, channel);
-        }
-        None => {
-            data[0].set_crumb(5, u2::new(0x1));
-            data[0].set_nibble(3, u4::new(0x0));
-        }
+    let (address, channel) = match channel {
+        Some(channel) => (u2::new(0x0), channel),
+        None => (u2::new(0x1), u4::new(0x0)),
+    };
+    data[0].set_crumb(5, address);
+    data[0].set_nibble(3, channel);
+    for packet in data
+        .chunks_exact_mut(4)
+        .skip(1)
+        .take_while(|packet| u8::from(packet[0].nibble(0)) == UMP_MESSAGE_TYPE)
+    {
+        packet[0].set_crumb(5, address);
+        packet[0].set_nibble(3, channel);
     }
 }
 
@@ -0,0 +1,288 @@
+//! General MIDI sound set and percussion map lookup tables.
+//!
+//! Every entry is an enum variant with a static display name.
+//! [Program] and [Family] cover the General MIDI 1 sound set,
+//! [Sound] adds the General MIDI 2 variations selected with the bank,
+//! and [Percussion] maps the note numbers of the percussion channel.
+//!
+//! ```rust
+//! use midi2::{gm::*, ux::u7};
+//!
+//! let program = Program::from(u7::new(40));
+//! assert_eq!(program, Program::Violin);
+//! assert_eq!(program.name(), "Violin");
+//! assert_eq!(program.family(), Family::Strings);
+//!
+//! let sound = Sound::from_bank_select(u7::new(MELODY_BANK_MSB), u7::new(0x1), u7::new(40));
+//! assert_eq!(sound, Some(Sound::ViolinSlowAttack));
+//! assert_eq!(sound.unwrap().name(), "Violin (slow attack)");
+//!
+//! assert_eq!(Percussion::from_note(u7::new(38)), Some(Percussion::AcousticSnare));
+//! ```
+
+use crate::ux::u7;
+
+macro_rules! lookup_table {
+    (
+        $(#[$meta:meta])*
+        pub enum $name:ident {
+            $($variant:ident => $text:literal,)*
+        }
+    ) => {
+        $(#[$meta])*
+        #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+        pub enum $name {
+            $($variant,)*
+        }
+
+        impl $name {
+            const ALL: &'static [$name] = &[$($name::$variant,)*];
+
+            pub fn name(&self) -> &'static str {
+                match self {
+                    $($name::$variant => $text,)*
+                }
+            }
+        }
+    };
+    (
+        $(#[$meta:meta])*
+        pub enum $name:ident {
+            $($variant:ident($program:literal, $variation:literal) => $text:literal,)*
+        }
+    ) => {
+        $(#[$meta])*
+        #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+        pub enum $name {
+            $($variant,)*
+        }
+
+        impl $name {
+            const ALL: &'static [$name] = &[$($name::$variant,)*];
+
+            pub fn name(&self) -> &'static str {
+                match self {
+                    $($name::$variant => $text,)*
+                }
+            }
+
+            // the program number and bank select lsb of the entry
+            fn key(&self) -> (u8, u8) {
+                match self {
+                    $($name::$variant => ($program, $variation),)*
+                }
+            }
+        }
+    };
+}
+
+mod percussion;
+mod sound;
+
+pub use percussion::*;
+pub use sound::*;
+
+lookup_table! {
+    /// The General MIDI instrument families, each covering eight programs.
+    pub enum Family {
+        Piano => "Piano",
+        ChromaticPercussion => "Chromatic Percussion",
+        Organ => "Organ",
+        Guitar => "Guitar",
+        Bass => "Bass",
+        Strings => "Strings",
+        Ensemble => "Ensemble",
+        Brass => "Brass",
+        Reed => "Reed",
+        Pipe => "Pipe",
+        SynthLead => "Synth Lead",
+        SynthPad => "Synth Pad",
+        SynthEffects => "Synth Effects",
+        Ethnic => "Ethnic",
+        Percussive => "Percussive",
+        SoundEffects => "Sound Effects",
+    }
+}
+
+lookup_table! {
+    /// The General MIDI 1 sound set, in program number order.
+    ///
+    /// Program numbers are zero based, as sent in program change messages.
+    pub enum Program {
+        AcousticGrandPiano => "Acoustic Grand Piano",
+        BrightAcousticPiano => "Bright Acoustic Piano",
+        ElectricGrandPiano => "Electric Grand Piano",
+        HonkyTonkPiano => "Honky-tonk Piano",
+        ElectricPiano1 => "Electric Piano 1",
+        ElectricPiano2 => "Electric Piano 2",
+        Harpsichord => "Harpsichord",
+        Clavi => "Clavi",
+        Celesta => "Celesta",
+        Glockenspiel => "Glockenspiel",
+        MusicBox => "Music Box",
+        Vibraphone => "Vibraphone",
+        Marimba => "Marimba",
+        Xylophone => "Xylophone",
+        TubularBells => "Tubular Bells",
+        Dulcimer => "Dulcimer",
+        DrawbarOrgan => "Drawbar Organ",
+        PercussiveOrgan => "Percussive Organ",
+        RockOrgan => "Rock Organ",
+        ChurchOrgan => "Church Organ",
+        ReedOrgan => "Reed Organ",
+        Accordion => "Accordion",
+        Harmonica => "Harmonica",
+        TangoAccordion => "Tango Accordion",
+        AcousticGuitarNylon => "Acoustic Guitar (nylon)",
+        AcousticGuitarSteel => "Acoustic Guitar (steel)",
+        ElectricGuitarJazz => "Electric Guitar (jazz)",
+        ElectricGuitarClean => "Electric Guitar (clean)",
+        ElectricGuitarMuted => "Electric Guitar (muted)",
+        OverdrivenGuitar => "Overdriven Guitar",
+        DistortionGuitar => "Distortion Guitar",
+        GuitarHarmonics => "Guitar Harmonics",
+        AcousticBass => "Acoustic Bass",
+        ElectricBassFinger => "Electric Bass (finger)",
+        ElectricBassPick => "Electric Bass (pick)",
+        FretlessBass => "Fretless Bass",
+        SlapBass1 => "Slap Bass 1",
+        SlapBass2 => "Slap Bass 2",
+        SynthBass1 => "Synth Bass 1",
+        SynthBass2 => "Synth Bass 2",
+        Violin => "Violin",
+        Viola => "Viola",
+        Cello => "Cello",
+        Contrabass => "Contrabass",
+        TremoloStrings => "Tremolo Strings",
+        PizzicatoStrings => "Pizzicato Strings",
+        OrchestralHarp => "Orchestral Harp",
+        Timpani => "Timpani",
+        StringEnsemble1 => "String Ensemble 1",
+        StringEnsemble2 => "String Ensemble 2",
+        SynthStrings1 => "SynthStrings 1",
+        SynthStrings2 => "SynthStrings 2",
+        ChoirAahs => "Choir Aahs",
+        VoiceOohs => "Voice Oohs",
+        SynthVoice => "Synth Voice",
+        OrchestraHit => "Orchestra Hit",
+        Trumpet => "Trumpet",
+        Trombone => "Trombone",
+        Tuba => "Tuba",
+        MutedTrumpet => "Muted Trumpet",
+        FrenchHorn => "French Horn",
+        BrassSection => "Brass Section",
+        SynthBrass1 => "SynthBrass 1",
+        SynthBrass2 => "SynthBrass 2",
+        SopranoSax => "Soprano Sax",
+        AltoSax => "Alto Sax",
+        TenorSax => "Tenor Sax",
+        BaritoneSax => "Baritone Sax",
+        Oboe => "Oboe",
+        EnglishHorn => "English Horn",
+        Bassoon => "Bassoon",
+        Clarinet => "Clarinet",
+        Piccolo => "Piccolo",
+        Flute => "Flute",
+        Recorder => "Recorder",
+        PanFlute => "Pan Flute",
+        BlownBottle => "Blown Bottle",
+        Shakuhachi => "Shakuhachi",
+        Whistle => "Whistle",
+        Ocarina => "Ocarina",
+        Lead1Square => "Lead 1 (square)",
+        Lead2Sawtooth => "Lead 2 (sawtooth)",
+        Lead3Calliope => "Lead 3 (calliope)",
+        Lead4Chiff => "Lead 4 (chiff)",
+        Lead5Charang => "Lead 5 (charang)",
+        Lead6Voice => "Lead 6 (voice)",
+        Lead7Fifths => "Lead 7 (fifths)",
+        Lead8BassAndLead => "Lead 8 (bass + lead)",
+        Pad1NewAge => "Pad 1 (new age)",
+        Pad2Warm => "Pad 2 (warm)",
+        Pad3Polysynth => "Pad 3 (polysynth)",
+        Pad4Choir => "Pad 4 (choir)",
+        Pad5Bowed => "Pad 5 (bowed)",
+        Pad6Metallic => "Pad 6 (metallic)",
+        Pad7Halo => "Pad 7 (halo)",
+        Pad8Sweep => "Pad 8 (sweep)",
+        Fx1Rain => "FX 1 (rain)",
+        Fx2Soundtrack => "FX 2 (soundtrack)",
+        Fx3Crystal => "FX 3 (crystal)",
+        Fx4Atmosphere => "FX 4 (atmosphere)",
+        Fx5Brightness => "FX 5 (brightness)",
+        Fx6Goblins => "FX 6 (goblins)",
+        Fx7Echoes => "FX 7 (echoes)",
+        Fx8SciFi => "FX 8 (sci-fi)",
+        Sitar => "Sitar",
+        Banjo => "Banjo",
+        Shamisen => "Shamisen",
+        Koto => "Koto",
+        Kalimba => "Kalimba",
+        BagPipe => "Bag pipe",
+        Fiddle => "Fiddle",
+        Shanai => "Shanai",
+        TinkleBell => "Tinkle Bell",
+        Agogo => "Agogo",
+        SteelDrums => "Steel Drums",
+        Woodblock => "Woodblock",
+        TaikoDrum => "Taiko Drum",
+        MelodicTom => "Melodic Tom",
+        SynthDrum => "Synth Drum",
+        ReverseCymbal => "Reverse Cymbal",
+        GuitarFretNoise => "Guitar Fret Noise",
+        BreathNoise => "Breath Noise",
+        Seashore => "Seashore",
+        BirdTweet => "Bird Tweet",
+        TelephoneRing => "Telephone Ring",
+        Helicopter => "Helicopter",
+        Applause => "Applause",
+        Gunshot => "Gunshot",
+    }
+}
+
+impl Program {
+    pub fn number(&self) -> u7 {
+        u7::new(*self as u8)
+    }
+
+    pub fn family(&self) -> Family {
+        Family::ALL[*self as usize / 8]
+    }
+}
+
+impl core::convert::From<u7> for Program {
+    fn from(number: u7) -> Self {
+        Program::ALL[u8::from(number) as usize]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn first_program() {
+        assert_eq!(Program::from(u7::new(0)), Program::AcousticGrandPiano);
+        assert_eq!(Program::AcousticGrandPiano.name(), "Acoustic Grand Piano");
+    }
+
+    #[test]
+    fn last_program() {
+        assert_eq!(Program::from(u7::new(127)), Program::Gunshot);
+        assert_eq!(Program::Gunshot.family(), Family::SoundEffects);
+    }
+
+    #[test]
+    fn program_numbers_round_trip() {
+        for number in 0..128 {
+            assert_eq!(Program::from(u7::new(number)).number(), u7::new(number));
+        }
+    }
+
+    #[test]
+    fn family() {
+        assert_eq!(Program::Lead1Square.family(), Family::SynthLead);
+        assert_eq!(Family::SynthLead.name(), "Synth Lead");
+    }
+}
@@ -0,0 +1,133 @@
+use crate::ux::u7;
+
+const FIRST_NOTE: u8 = 27;
+const GENERAL_MIDI_1_NOTES: core::ops::RangeInclusive<u8> = 35..=81;
+
+lookup_table! {
+    /// The General MIDI percussion key map, in note number order.
+    ///
+    /// General MIDI 1 maps notes 35 to 81,
+    /// and General MIDI 2 extends the map to notes 27 to 87.
+    pub enum Percussion {
+        HighQ => "High Q",
+        Slap => "Slap",
+        ScratchPush => "Scratch Push",
+        ScratchPull => "Scratch Pull",
+        Sticks => "Sticks",
+        SquareClick => "Square Click",
+        MetronomeClick => "Metronome Click",
+        MetronomeBell => "Metronome Bell",
+        AcousticBassDrum => "Acoustic Bass Drum",
+        BassDrum1 => "Bass Drum 1",
+        SideStick => "Side Stick",
+        AcousticSnare => "Acoustic Snare",
+        HandClap => "Hand Clap",
+        ElectricSnare => "Electric Snare",
+        LowFloorTom => "Low Floor Tom",
+        ClosedHiHat => "Closed Hi-Hat",
+        HighFloorTom => "High Floor Tom",
+        PedalHiHat => "Pedal Hi-Hat",
+        LowTom => "Low Tom",
+        OpenHiHat => "Open Hi-Hat",
+        LowMidTom => "Low-Mid Tom",
+        HiMidTom => "Hi-Mid Tom",
+        CrashCymbal1 => "Crash Cymbal 1",
+        HighTom => "High Tom",
+        RideCymbal1 => "Ride Cymbal 1",
+        ChineseCymbal => "Chinese Cymbal",
+        RideBell => "Ride Bell",
+        Tambourine => "Tambourine",
+        SplashCymbal => "Splash Cymbal",
+        Cowbell => "Cowbell",
+        CrashCymbal2 => "Crash Cymbal 2",
+        Vibraslap => "Vibraslap",
+        RideCymbal2 => "Ride Cymbal 2",
+        HiBongo => "Hi Bongo",
+        LowBongo => "Low Bongo",
+        MuteHiConga => "Mute Hi Conga",
+        OpenHiConga => "Open Hi Conga",
+        LowConga => "Low Conga",
+        HighTimbale => "High Timbale",
+        LowTimbale => "Low Timbale",
+        HighAgogo => "High Agogo",
+        LowAgogo => "Low Agogo",
+        Cabasa => "Cabasa",
+        Maracas => "Maracas",
+        ShortWhistle => "Short Whistle",
+        LongWhistle => "Long Whistle",
+        ShortGuiro => "Short Guiro",
+        LongGuiro => "Long Guiro",
+        Claves => "Claves",
+        HiWoodBlock => "Hi Wood Block",
+        LowWoodBlock => "Low Wood Block",
+        MuteCuica => "Mute Cuica",
+        OpenCuica => "Open Cuica",
+        MuteTriangle => "Mute Triangle",
+        OpenTriangle => "Open Triangle",
+        Shaker => "Shaker",
+        JingleBell => "Jingle Bell",
+        Belltree => "Belltree",
+        Castanets => "Castanets",
+        MuteSurdo => "Mute Surdo",
+        OpenSurdo => "Open Surdo",
+    }
+}
+
+impl Percussion {
+    /// Returns `None` for notes outside of the map.
+    pub fn from_note(note: u7) -> Option<Self> {
+        let index = u8::from(note).checked_sub(FIRST_NOTE)?;
+        Percussion::ALL.get(index as usize).copied()
+    }
+
+    pub fn note(&self) -> u7 {
+        u7::new(*self as u8 + FIRST_NOTE)
+    }
+
+    /// Whether the note is part of the General MIDI 1 map.
+    pub fn is_general_midi_1(&self) -> bool {
+        GENERAL_MIDI_1_NOTES.contains(&u8::from(self.note()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn bounds() {
+        assert_eq!(Percussion::from_note(u7::new(26)), None);
+        assert_eq!(Percussion::from_note(u7::new(27)), Some(Percussion::HighQ));
+        assert_eq!(
+            Percussion::from_note(u7::new(87)),
+            Some(Percussion::OpenSurdo)
+        );
+        assert_eq!(Percussion::from_note(u7::new(88)), None);
+    }
+
+    #[test]
+    fn notes_round_trip() {
+        for note in 27..=87 {
+            assert_eq!(
+                Percussion::from_note(u7::new(note)).map(|p| p.note()),
+                Some(u7::new(note))
+            );
+        }
+    }
+
+    #[test]
+    fn general_midi_1() {
+        assert!(!Percussion::MetronomeBell.is_general_midi_1());
+        assert!(Percussion::AcousticBassDrum.is_general_midi_1());
+        assert!(Percussion::OpenTriangle.is_general_midi_1());
+        assert!(!Percussion::Shaker.is_general_midi_1());
+    }
+
+    #[test]
+    fn names() {
+        assert_eq!(Percussion::ClosedHiHat.note(), u7::new(42));
+        assert_eq!(Percussion::ClosedHiHat.name(), "Closed Hi-Hat");
+        assert_eq!(Percussion::OpenTriangle.note(), u7::new(81));
+    }
+}
@@ -0,0 +1,406 @@
+use crate::{
+    gm::{Family, Program},
+    ux::{u14, u7},
+};
+
+/// The bank select msb of the General MIDI 2 melody sounds.
+pub const MELODY_BANK_MSB: u8 = 0x79;
+
+#[cfg(feature = "channel-voice2")]
+const ERR_NOT_MELODY_BANK: &str = "Expected a General MIDI 2 melody bank";
+
+lookup_table! {
+    /// The General MIDI 2 sound set.
+    ///
+    /// Each program has a capital sound, selected with bank select lsb zero,
+    /// and some have variations selected with higher lsb values.
+    /// The capital sounds are those of the General MIDI 1 [Program]s.
+    pub enum Sound {
+        AcousticGrandPiano(0, 0) => "Acoustic Grand Piano",
+        WideAcousticGrand(0, 1) => "Wide Acoustic Grand",
+        DarkAcousticGrand(0, 2) => "Dark Acoustic Grand",
+        BrightAcousticPiano(1, 0) => "Bright Acoustic Piano",
+        WideBrightAcoustic(1, 1) => "Wide Bright Acoustic",
+        ElectricGrandPiano(2, 0) => "Electric Grand Piano",
+        WideElectricGrand(2, 1) => "Wide Electric Grand",
+        HonkyTonkPiano(3, 0) => "Honky-tonk Piano",
+        WideHonkyTonk(3, 1) => "Wide Honky-tonk",
+        ElectricPiano1(4, 0) => "Electric Piano 1",
+        DetunedElectricPiano1(4, 1) => "Detuned Electric Piano 1",
+        ElectricPiano1Variation(4, 2) => "Electric Piano 1 Variation",
+        SixtiesElectricPiano(4, 3) => "60's Electric Piano",
+        ElectricPiano2(5, 0) => "Electric Piano 2",
+        DetunedElectricPiano2(5, 1) => "Detuned Electric Piano 2",
+        ElectricPiano2Variation(5, 2) => "Electric Piano 2 Variation",
+        EpLegend(5, 3) => "EP Legend",
+        EpPhase(5, 4) => "EP Phase",
+        Harpsichord(6, 0) => "Harpsichord",
+        HarpsichordOctaveMix(6, 1) => "Harpsichord Octave Mix",
+        WideHarpsichord(6, 2) => "Wide Harpsichord",
+        HarpsichordWithKeyOff(6, 3) => "Harpsichord with Key Off",
+        Clavi(7, 0) => "Clavi",
+        PulseClavi(7, 1) => "Pulse Clavi",
+        Celesta(8, 0) => "Celesta",
+        Glockenspiel(9, 0) => "Glockenspiel",
+        MusicBox(10, 0) => "Music Box",
+        Vibraphone(11, 0) => "Vibraphone",
+        WetVibraphone(11, 1) => "Wet Vibraphone",
+        Marimba(12, 0) => "Marimba",
+        WideMarimba(12, 1) => "Wide Marimba",
+        Xylophone(13, 0) => "Xylophone",
+        TubularBells(14, 0) => "Tubular Bells",
+        ChurchBell(14, 1) => "Church Bell",
+        Carillon(14, 2) => "Carillon",
+        Dulcimer(15, 0) => "Dulcimer",
+        DrawbarOrgan(16, 0) => "Drawbar Organ",
+        DetunedDrawbarOrgan(16, 1) => "Detuned Drawbar Organ",
+        ItalianSixtiesOrgan(16, 2) => "Italian 60's Organ",
+        DrawbarOrgan2(16, 3) => "Drawbar Organ 2",
+        PercussiveOrgan(17, 0) => "Percussive Organ",
+        DetunedPercussiveOrgan(17, 1) => "Detuned Percussive Organ",
+        PercussiveOrgan2(17, 2) => "Percussive Organ 2",
+        RockOrgan(18, 0) => "Rock Organ",
+        ChurchOrgan(19, 0) => "Church Organ",
+        ChurchOrganOctaveMix(19, 1) => "Church Organ Octave Mix",
+        DetunedChurchOrgan(19, 2) => "Detuned Church Organ",
+        ReedOrgan(20, 0) => "Reed Organ",
+        PuffOrgan(20, 1) => "Puff Organ",
+        Accordion(21, 0) => "Accordion",
+        Accordion2(21, 1) => "Accordion 2",
+        Harmonica(22, 0) => "Harmonica",
+        TangoAccordion(23, 0) => "Tango Accordion",
+        AcousticGuitarNylon(24, 0) => "Acoustic Guitar (nylon)",
+        Ukulele(24, 1) => "Ukulele",
+        AcousticGuitarNylonKeyOff(24, 2) => "Acoustic Guitar (nylon + key off)",
+        AcousticGuitarNylon2(24, 3) => "Acoustic Guitar (nylon 2)",
+        AcousticGuitarSteel(25, 0) => "Acoustic Guitar (steel)",
+        TwelveStringsGuitar(25, 1) => "12-Strings Guitar",
+        Mandolin(25, 2) => "Mandolin",
+        SteelGuitarWithBodySound(25, 3) => "Steel Guitar with Body Sound",
+        ElectricGuitarJazz(26, 0) => "Electric Guitar (jazz)",
+        ElectricGuitarPedalSteel(26, 1) => "Electric Guitar (pedal steel)",
+        ElectricGuitarClean(27, 0) => "Electric Guitar (clean)",
+        ElectricGuitarDetunedClean(27, 1) => "Electric Guitar (detuned clean)",
+        MidToneGuitar(27, 2) => "Mid Tone Guitar",
+        ElectricGuitarMuted(28, 0) => "Electric Guitar (muted)",
+        ElectricGuitarFunkyCutting(28, 1) => "Electric Guitar (funky cutting)",
+        ElectricGuitarMutedVelocitySwitch(28, 2) => "Electric Guitar (muted velo-sw)",
+        JazzMan(28, 3) => "Jazz Man",
+        OverdrivenGuitar(29, 0) => "Overdriven Guitar",
+        GuitarPinch(29, 1) => "Guitar Pinch",
+        DistortionGuitar(30, 0) => "Distortion Guitar",
+        DistortionGuitarWithFeedback(30, 1) => "Distortion Guitar (with feedback)",
+        DistortedRhythmGuitar(30, 2) => "Distorted Rhythm Guitar",
+        GuitarHarmonics(31, 0) => "Guitar Harmonics",
+        GuitarFeedback(31, 1) => "Guitar Feedback",
+        AcousticBass(32, 0) => "Acoustic Bass",
+        ElectricBassFinger(33, 0) => "Electric Bass (finger)",
+        FingerSlapBass(33, 1) => "Finger Slap Bass",
+        ElectricBassPick(34, 0) => "Electric Bass (pick)",
+        FretlessBass(35, 0) => "Fretless Bass",
+        SlapBass1(36, 0) => "Slap Bass 1",
+        SlapBass2(37, 0) => "Slap Bass 2",
+        SynthBass1(38, 0) => "Synth Bass 1",
+        SynthBassWarm(38, 1) => "Synth Bass (warm)",
+        SynthBass3Resonance(38, 2) => "Synth Bass 3 (resonance)",
+        ClaviBass(38, 3) => "Clavi Bass",
+        Hammer(38, 4) => "Hammer",
+        SynthBass2(39, 0) => "Synth Bass 2",
+        SynthBass4Attack(39, 1) => "Synth Bass 4 (attack)",
+        SynthBassRubber(39, 2) => "Synth Bass (rubber)",
+        AttackPulse(39, 3) => "Attack Pulse",
+        Violin(40, 0) => "Violin",
+        ViolinSlowAttack(40, 1) => "Violin (slow attack)",
+        Viola(41, 0) => "Viola",
+        Cello(42, 0) => "Cello",
+        Contrabass(43, 0) => "Contrabass",
+        TremoloStrings(44, 0) => "Tremolo Strings",
+        PizzicatoStrings(45, 0) => "Pizzicato Strings",
+        OrchestralHarp(46, 0) => "Orchestral Harp",
+        YangChin(46, 1) => "Yang Chin",
+        Timpani(47, 0) => "Timpani",
+        StringEnsemble1(48, 0) => "String Ensemble 1",
+        StringsAndBrass(48, 1) => "Strings and Brass",
+        SixtiesStrings(48, 2) => "60s Strings",
+        StringEnsemble2(49, 0) => "String Ensemble 2",
+        SynthStrings1(50, 0) => "SynthStrings 1",
+        SynthStrings3(50, 1) => "SynthStrings 3",
+        SynthStrings2(51, 0) => "SynthStrings 2",
+        ChoirAahs(52, 0) => "Choir Aahs",
+        ChoirAahs2(52, 1) => "Choir Aahs 2",
+        VoiceOohs(53, 0) => "Voice Oohs",
+        Humming(53, 1) => "Humming",
+        SynthVoice(54, 0) => "Synth Voice",
+        AnalogVoice(54, 1) => "Analog Voice",
+        OrchestraHit(55, 0) => "Orchestra Hit",
+        BassHitPlus(55, 1) => "Bass Hit Plus",
+        SixthHit(55, 2) => "6th Hit",
+        EuroHit(55, 3) => "Euro Hit",
+        Trumpet(56, 0) => "Trumpet",
+        DarkTrumpetSoft(56, 1) => "Dark Trumpet Soft",
+        Trombone(57, 0) => "Trombone",
+        Trombone2(57, 1) => "Trombone 2",
+        BrightTrombone(57, 2) => "Bright Trombone",
+        Tuba(58, 0) => "Tuba",
+        MutedTrumpet(59, 0) => "Muted Trumpet",
+        MutedTrumpet2(59, 1) => "Muted Trumpet 2",
+        FrenchHorn(60, 0) => "French Horn",
+        FrenchHorn2Warm(60, 1) => "French Horn 2 (warm)",
+        BrassSection(61, 0) => "Brass Section",
+        BrassSection2OctaveMix(61, 1) => "Brass Section 2 (octave mix)",
+        SynthBrass1(62, 0) => "SynthBrass 1",
+        SynthBrass3(62, 1) => "SynthBrass 3",
+        AnalogSynthBrass1(62, 2) => "Analog SynthBrass 1",
+        JumpBrass(62, 3) => "Jump Brass",
+        SynthBrass2(63, 0) => "SynthBrass 2",
+        SynthBrass4(63, 1) => "SynthBrass 4",
+        AnalogSynthBrass2(63, 2) => "Analog SynthBrass 2",
+        SopranoSax(64, 0) => "Soprano Sax",
+        AltoSax(65, 0) => "Alto Sax",
+        TenorSax(66, 0) => "Tenor Sax",
+        BaritoneSax(67, 0) => "Baritone Sax",
+        Oboe(68, 0) => "Oboe",
+        EnglishHorn(69, 0) => "English Horn",
+        Bassoon(70, 0) => "Bassoon",
+        Clarinet(71, 0) => "Clarinet",
+        Piccolo(72, 0) => "Piccolo",
+        Flute(73, 0) => "Flute",
+        Recorder(74, 0) => "Recorder",
+        PanFlute(75, 0) => "Pan Flute",
+        BlownBottle(76, 0) => "Blown Bottle",
+        Shakuhachi(77, 0) => "Shakuhachi",
+        Whistle(78, 0) => "Whistle",
+        Ocarina(79, 0) => "Ocarina",
+        Lead1Square(80, 0) => "Lead 1 (square)",
+        Lead1aSquare2(80, 1) => "Lead 1a (square 2)",
+        Lead1bSine(80, 2) => "Lead 1b (sine)",
+        Lead2Sawtooth(81, 0) => "Lead 2 (sawtooth)",
+        Lead2aSawtooth2(81, 1) => "Lead 2a (sawtooth 2)",
+        Lead2bSawAndPulse(81, 2) => "Lead 2b (saw + pulse)",
+        Lead2cDoubleSawtooth(81, 3) => "Lead 2c (double sawtooth)",
+        Lead2dSequencedAnalog(81, 4) => "Lead 2d (sequenced analog)",
+        Lead3Calliope(82, 0) => "Lead 3 (calliope)",
+        Lead4Chiff(83, 0) => "Lead 4 (chiff)",
+        Lead5Charang(84, 0) => "Lead 5 (charang)",
+        Lead5aWireLead(84, 1) => "Lead 5a (wire lead)",
+        Lead6Voice(85, 0) => "Lead 6 (voice)",
+        Lead7Fifths(86, 0) => "Lead 7 (fifths)",
+        Lead8BassAndLead(87, 0) => "Lead 8 (bass + lead)",
+        Lead8aSoftWrl(87, 1) => "Lead 8a (soft wrl)",
+        Pad1NewAge(88, 0) => "Pad 1 (new age)",
+        Pad2Warm(89, 0) => "Pad 2 (warm)",
+        Pad2aSinePad(89, 1) => "Pad 2a (sine pad)",
+        Pad3Polysynth(90, 0) => "Pad 3 (polysynth)",
+        Pad4Choir(91, 0) => "Pad 4 (choir)",
+        Pad4aItopia(91, 1) => "Pad 4a (itopia)",
+        Pad5Bowed(92, 0) => "Pad 5 (bowed)",
+        Pad6Metallic(93, 0) => "Pad 6 (metallic)",
+        Pad7Halo(94, 0) => "Pad 7 (halo)",
+        Pad8Sweep(95, 0) => "Pad 8 (sweep)",
+        Fx1Rain(96, 0) => "FX 1 (rain)",
+        Fx2Soundtrack(97, 0) => "FX 2 (soundtrack)",
+        Fx3Crystal(98, 0) => "FX 3 (crystal)",
+        Fx3aSynthMallet(98, 1) => "FX 3a (synth mallet)",
+        Fx4Atmosphere(99, 0) => "FX 4 (atmosphere)",
+        Fx5Brightness(100, 0) => "FX 5 (brightness)",
+        Fx6Goblins(101, 0) => "FX 6 (goblins)",
+        Fx7Echoes(102, 0) => "FX 7 (echoes)",
+        Fx7aEchoBell(102, 1) => "FX 7a (echo bell)",
+        Fx7bEchoPan(102, 2) => "FX 7b (echo pan)",
+        Fx8SciFi(103, 0) => "FX 8 (sci-fi)",
+        Sitar(104, 0) => "Sitar",
+        Sitar2Bend(104, 1) => "Sitar 2 (bend)",
+        Banjo(105, 0) => "Banjo",
+        Shamisen(106, 0) => "Shamisen",
+        Koto(107, 0) => "Koto",
+        TaishoKoto(107, 1) => "Taisho Koto",
+        Kalimba(108, 0) => "Kalimba",
+        BagPipe(109, 0) => "Bag pipe",
+        Fiddle(110, 0) => "Fiddle",
+        Shanai(111, 0) => "Shanai",
+        TinkleBell(112, 0) => "Tinkle Bell",
+        Agogo(113, 0) => "Agogo",
+        SteelDrums(114, 0) => "Steel Drums",
+        Woodblock(115, 0) => "Woodblock",
+        Castanets(115, 1) => "Castanets",
+        TaikoDrum(116, 0) => "Taiko Drum",
+        ConcertBassDrum(116, 1) => "Concert Bass Drum",
+        MelodicTom(117, 0) => "Melodic Tom",
+        MelodicTom2Power(117, 1) => "Melodic Tom 2 (power)",
+        SynthDrum(118, 0) => "Synth Drum",
+        RhythmBoxTom(118, 1) => "Rhythm Box Tom",
+        ElectricDrum(118, 2) => "Electric Drum",
+        ReverseCymbal(119, 0) => "Reverse Cymbal",
+        GuitarFretNoise(120, 0) => "Guitar Fret Noise",
+        GuitarCuttingNoise(120, 1) => "Guitar Cutting Noise",
+        AcousticBassStringSlap(120, 2) => "Acoustic Bass String Slap",
+        BreathNoise(121, 0) => "Breath Noise",
+        FluteKeyClick(121, 1) => "Flute Key Click",
+        Seashore(122, 0) => "Seashore",
+        Rain(122, 1) => "Rain",
+        Thunder(122, 2) => "Thunder",
+        Wind(122, 3) => "Wind",
+        Stream(122, 4) => "Stream",
+        Bubble(122, 5) => "Bubble",
+        BirdTweet(123, 0) => "Bird Tweet",
+        Dog(123, 1) => "Dog",
+        HorseGallop(123, 2) => "Horse Gallop",
+        BirdTweet2(123, 3) => "Bird Tweet 2",
+        TelephoneRing(124, 0) => "Telephone Ring",
+        TelephoneRing2(124, 1) => "Telephone Ring 2",
+        DoorCreaking(124, 2) => "Door Creaking",
+        Door(124, 3) => "Door",
+        Scratch(124, 4) => "Scratch",
+        WindChime(124, 5) => "Wind Chime",
+        Helicopter(125, 0) => "Helicopter",
+        CarEngine(125, 1) => "Car Engine",
+        CarStop(125, 2) => "Car Stop",
+        CarPass(125, 3) => "Car Pass",
+        CarCrash(125, 4) => "Car Crash",
+        Siren(125, 5) => "Siren",
+        Train(125, 6) => "Train",
+        Jetplane(125, 7) => "Jetplane",
+        Starship(125, 8) => "Starship",
+        BurstNoise(125, 9) => "Burst Noise",
+        Applause(126, 0) => "Applause",
+        Laughing(126, 1) => "Laughing",
+        Screaming(126, 2) => "Screaming",
+        Punch(126, 3) => "Punch",
+        HeartBeat(126, 4) => "Heart Beat",
+        Footsteps(126, 5) => "Footsteps",
+        Gunshot(127, 0) => "Gunshot",
+        MachineGun(127, 1) => "Machine Gun",
+        Lasergun(127, 2) => "Lasergun",
+        Explosion(127, 3) => "Explosion",
+    }
+}
+
+impl Sound {
+    /// The sound selected by a program change with an optional bank,
+    /// holding the bank select msb in the upper seven bits.
+    ///
+    /// Without a bank the capital sound is selected.
+    /// Returns `None` for banks other than [MELODY_BANK_MSB],
+    /// or variations which don't exist.
+    pub fn new(bank: Option<u14>, program: u7) -> Option<Self> {
+        let variation = match bank.map(u16::from) {
+            None => 0x0,
+            Some(bank) if bank >> 7 == MELODY_BANK_MSB as u16 => (bank & 0x7F) as u8,
+            Some(_) => return None,
+        };
+        let key = (u8::from(program), variation);
+        Sound::ALL.iter().find(|sound| sound.key() == key).copied()
+    }
+
+    /// The sound selected by a MIDI 1.0 program change,
+    /// following bank select msb and lsb control changes.
+    pub fn from_bank_select(msb: u7, lsb: u7, program: u7) -> Option<Self> {
+        let bank = u16::from(u8::from(msb)) << 7 | u16::from(u8::from(lsb));
+        Sound::new(Some(u14::new(bank)), program)
+    }
+
+    pub fn program(&self) -> Program {
+        Program::from(u7::new(self.key().0))
+    }
+
+    /// The bank select lsb of the sound.
+    pub fn variation(&self) -> u7 {
+        u7::new(self.key().1)
+    }
+
+    pub fn family(&self) -> Family {
+        self.program().family()
+    }
+}
+
+impl core::convert::From<Program> for Sound {
+    fn from(program: Program) -> Self {
+        Sound::new(None, program.number()).unwrap()
+    }
+}
+
+#[cfg(feature = "channel-voice2")]
+impl<'a, B: crate::buffer::Ump> core::convert::TryFrom<&'a crate::channel_voice2::ProgramChange<B>>
+    for Sound
+{
+    type Error = crate::error::InvalidData;
+    fn try_from(message: &'a crate::channel_voice2::ProgramChange<B>) -> Result<Self, Self::Error> {
+        Sound::new(message.bank(), message.program())
+            .ok_or(crate::error::InvalidData(ERR_NOT_MELODY_BANK))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn capital_sounds_match_programs() {
+        for number in 0..128 {
+            let program = Program::from(u7::new(number));
+            let sound = Sound::from(program);
+            assert_eq!(sound.name(), program.name());
+            assert_eq!(sound.program(), program);
+            assert_eq!(sound.variation(), u7::new(0x0));
+        }
+    }
+
+    #[test]
+    fn variation() {
+        assert_eq!(
+            Sound::from_bank_select(u7::new(MELODY_BANK_MSB), u7::new(0x9), u7::new(125)),
+            Some(Sound::BurstNoise)
+        );
+        assert_eq!(Sound::BurstNoise.family(), Family::SoundEffects);
+    }
+
+    #[test]
+    fn missing_variation() {
+        assert_eq!(
+            Sound::from_bank_select(u7::new(MELODY_BANK_MSB), u7::new(0x1), u7::new(8)),
+            None
+        );
+    }
+
+    #[test]
+    fn not_melody_bank() {
+        assert_eq!(
+            Sound::from_bank_select(u7::new(0x78), u7::new(0x0), u7::new(0)),
+            None
+        );
+    }
+
+    #[test]
+    fn sound_set_size() {
+        assert_eq!(Sound::ALL.len(), 256);
+    }
+
+    #[cfg(feature = "channel-voice2")]
+    #[test]
+    fn from_program_change() {
+        let mut message = crate::channel_voice2::ProgramChange::<[u32; 4]>::new();
+        message.set_program(u7::new(4));
+        message.set_bank(Some(u14::new((MELODY_BANK_MSB as u16) << 7 | 0x3)));
+        assert_eq!(Sound::try_from(&message), Ok(Sound::SixtiesElectricPiano));
+    }
+
+    #[cfg(feature = "channel-voice2")]
+    #[test]
+    fn from_program_change_without_bank() {
+        let mut message = crate::channel_voice2::ProgramChange::<[u32; 4]>::new();
+        message.set_program(u7::new(4));
+        assert_eq!(Sound::try_from(&message), Ok(Sound::ElectricPiano1));
+    }
+
+    #[cfg(feature = "channel-voice2")]
+    #[test]
+    fn from_program_change_other_bank() {
+        let mut message = crate::channel_voice2::ProgramChange::<[u32; 4]>::new();
+        message.set_bank(Some(u14::new(0x0)));
+        assert_eq!(
+            Sound::try_from(&message),
+            Err(crate::error::InvalidData(ERR_NOT_MELODY_BANK))
+        );
+    }
+}
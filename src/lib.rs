@@ -5,6 +5,8 @@
 #[cfg(any(feature = "std", test))]
 extern crate std;
 
+#[cfg(any(feature = "channel-voice1", feature = "channel-voice2"))]
+pub mod active_notes;
 #[cfg(feature = "channel-voice1")]
 pub mod channel_voice1;
 #[cfg(feature = "channel-voice2")]
@@ -13,12 +15,18 @@ pub mod channel_voice2;
 pub mod ci;
 #[cfg(feature = "flex-data")]
 pub mod flex_data;
+#[cfg(all(feature = "channel-voice1", feature = "channel-voice2"))]
+pub mod mpe;
 #[cfg(feature = "sysex7")]
 pub mod sysex7;
 #[cfg(feature = "sysex8")]
 pub mod sysex8;
 #[cfg(feature = "system-common")]
 pub mod system_common;
+#[cfg(all(feature = "channel-voice1", feature = "channel-voice2"))]
+pub mod translation;
+#[cfg(all(feature = "channel-voice2", feature = "sysex7"))]
+pub mod tuning;
 #[cfg(feature = "ump-stream")]
 pub mod ump_stream;
 #[cfg(feature = "utility")]
@@ -26,14 +34,28 @@ pub mod utility;
 
 pub mod buffer;
 pub mod error;
+pub mod gm;
+#[cfg(any(
+    feature = "channel-voice1",
+    feature = "channel-voice2",
+    feature = "flex-data",
+    feature = "sysex7",
+    feature = "sysex8",
+    feature = "system-common",
+    feature = "ump-stream",
+    feature = "utility"
+))]
+pub mod router;
 
 mod detail;
+mod manufacturer_id;
 mod message;
 mod packets;
 mod traits;
 
 pub use ux;
 
+pub use manufacturer_id::*;
 pub use message::*;
 pub use packets::*;
 pub use traits::*;
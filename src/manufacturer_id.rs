@@ -0,0 +1,339 @@
+use crate::{error::InvalidData, ux::u7};
+
+const EXTENDED: u8 = 0x00;
+const NON_COMMERCIAL: u8 = 0x7D;
+const UNIVERSAL_NON_REAL_TIME: u8 = 0x7E;
+const UNIVERSAL_REAL_TIME: u8 = 0x7F;
+
+const ERR_NO_MANUFACTURER_ID: &str = "Expected a manufacturer id";
+const ERR_NON_ZERO_PADDING: &str = "One byte manufacturer ids must be followed by two zero bytes";
+const ERR_INVALID_STANDARD_ID: &str = "One byte manufacturer ids must be in the range 0x01 to 0x7C";
+
+/// The id at the start of every system exclusive payload.
+///
+/// Manufacturers are assigned either a one byte id,
+/// or a three byte id starting with `0x00`.
+/// MIDI-CI and [DeviceIdentity](crate::ump_stream::DeviceIdentity) messages
+/// always hold three bytes, following one byte ids with two zero bytes;
+/// converting to and from `[u7; 3]` uses this form,
+/// and fields with non-zero bytes after a one byte id are rejected.
+///
+/// With the `sysex7` feature the id can be read from the start of a
+/// [Sysex7](crate::sysex7::Sysex7) message with `try_from`.
+///
+/// ```rust
+/// use midi2::{ux::u7, ManufacturerId};
+///
+/// let mut payload = [0x00, 0x21, 0x09, 0x01].map(u7::new).into_iter();
+/// let id = ManufacturerId::read(&mut payload).unwrap();
+/// assert_eq!(id, ManufacturerId::Extended(u7::new(0x21), u7::new(0x09)));
+/// assert_eq!(id.name(), Some("Native Instruments"));
+/// assert_eq!(payload.next(), Some(u7::new(0x01)));
+///
+/// let payload: Vec<u7> = ManufacturerId::Standard(u7::new(0x43)).payload().collect();
+/// assert_eq!(payload, [u7::new(0x43)]);
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ManufacturerId {
+    /// A one byte id, from `0x01` to `0x7C`.
+    ///
+    /// Other values are reserved for the remaining variants;
+    /// use [ManufacturerId::standard] to construct a checked id.
+    Standard(u7),
+    /// A three byte id, holding the two bytes which follow `0x00`.
+    Extended(u7, u7),
+    /// Reserved for research, education and other non-commercial use.
+    NonCommercial,
+    UniversalNonRealTime,
+    UniversalRealTime,
+}
+
+impl ManufacturerId {
+    /// A one byte id, rejecting `0x00` and the reserved `0x7D` to `0x7F`.
+    pub fn standard(id: u7) -> Result<Self, InvalidData> {
+        match u8::from(id) {
+            0x01..=0x7C => Ok(ManufacturerId::Standard(id)),
+            _ => Err(InvalidData(ERR_INVALID_STANDARD_ID)),
+        }
+    }
+
+    /// Reads the id from the start of a sysex payload,
+    /// leaving the rest of the payload in the iterator.
+    pub fn read<I: Iterator<Item = u7>>(payload: &mut I) -> Result<Self, InvalidData> {
+        let mut next = || payload.next().ok_or(InvalidData(ERR_NO_MANUFACTURER_ID));
+        let first = next()?;
+        Ok(match u8::from(first) {
+            EXTENDED => ManufacturerId::Extended(next()?, next()?),
+            NON_COMMERCIAL => ManufacturerId::NonCommercial,
+            UNIVERSAL_NON_REAL_TIME => ManufacturerId::UniversalNonRealTime,
+            UNIVERSAL_REAL_TIME => ManufacturerId::UniversalRealTime,
+            _ => ManufacturerId::Standard(first),
+        })
+    }
+
+    /// The one or three bytes which start a sysex payload.
+    pub fn payload(&self) -> impl Iterator<Item = u7> {
+        let (bytes, len) = match *self {
+            ManufacturerId::Extended(msb, lsb) => ([u7::new(EXTENDED), msb, lsb], 3),
+            _ => (<[u7; 3]>::from(*self), 1),
+        };
+        bytes.into_iter().take(len)
+    }
+
+    /// The name of the manufacturer, if it's in the built-in registry.
+    pub fn name(&self) -> Option<&'static str> {
+        match self {
+            ManufacturerId::NonCommercial => Some("Non-Commercial"),
+            ManufacturerId::UniversalNonRealTime => Some("Universal Non-Real Time"),
+            ManufacturerId::UniversalRealTime => Some("Universal Real Time"),
+            _ => REGISTRY
+                .iter()
+                .find(|(id, _)| id == self)
+                .map(|(_, name)| *name),
+        }
+    }
+}
+
+impl core::default::Default for ManufacturerId {
+    /// Default is the all zero three byte form.
+    fn default() -> Self {
+        ManufacturerId::Extended(u7::new(0x0), u7::new(0x0))
+    }
+}
+
+impl core::convert::TryFrom<[u7; 3]> for ManufacturerId {
+    type Error = InvalidData;
+    fn try_from(bytes: [u7; 3]) -> Result<Self, Self::Error> {
+        let [first, second, third] = bytes;
+        if u8::from(first) != EXTENDED && (u8::from(second), u8::from(third)) != (0x0, 0x0) {
+            return Err(InvalidData(ERR_NON_ZERO_PADDING));
+        }
+        ManufacturerId::read(&mut bytes.into_iter())
+    }
+}
+
+impl core::convert::From<ManufacturerId> for [u7; 3] {
+    fn from(id: ManufacturerId) -> Self {
+        let first = match id {
+            ManufacturerId::Standard(id) => id,
+            ManufacturerId::Extended(msb, lsb) => return [u7::new(EXTENDED), msb, lsb],
+            ManufacturerId::NonCommercial => u7::new(NON_COMMERCIAL),
+            ManufacturerId::UniversalNonRealTime => u7::new(UNIVERSAL_NON_REAL_TIME),
+            ManufacturerId::UniversalRealTime => u7::new(UNIVERSAL_REAL_TIME),
+        };
+        [first, u7::new(0x0), u7::new(0x0)]
+    }
+}
+
+#[cfg(feature = "sysex7")]
+impl<'a, B: crate::buffer::Buffer> core::convert::TryFrom<&'a crate::sysex7::Sysex7<B>>
+    for ManufacturerId
+{
+    type Error = InvalidData;
+    fn try_from(message: &'a crate::sysex7::Sysex7<B>) -> Result<Self, Self::Error> {
+        use crate::traits::Sysex;
+        ManufacturerId::read(&mut message.payload())
+    }
+}
+
+const fn one_byte(id: u8) -> ManufacturerId {
+    ManufacturerId::Standard(u7::new(id))
+}
+
+const fn three_byte(msb: u8, lsb: u8) -> ManufacturerId {
+    ManufacturerId::Extended(u7::new(msb), u7::new(lsb))
+}
+
+const REGISTRY: &[(ManufacturerId, &str)] = &[
+    (one_byte(0x01), "Sequential Circuits"),
+    (one_byte(0x04), "Moog"),
+    (one_byte(0x06), "Lexicon"),
+    (one_byte(0x07), "Kurzweil"),
+    (one_byte(0x0F), "Ensoniq"),
+    (one_byte(0x10), "Oberheim"),
+    (one_byte(0x11), "Apple"),
+    (one_byte(0x13), "Digidesign"),
+    (one_byte(0x18), "E-mu"),
+    (one_byte(0x1C), "Eventide"),
+    (one_byte(0x33), "Clavia"),
+    (one_byte(0x3A), "Steinberg"),
+    (one_byte(0x3E), "Waldorf"),
+    (one_byte(0x40), "Kawai"),
+    (one_byte(0x41), "Roland"),
+    (one_byte(0x42), "Korg"),
+    (one_byte(0x43), "Yamaha"),
+    (one_byte(0x44), "Casio"),
+    (one_byte(0x47), "Akai"),
+    (one_byte(0x4C), "Sony"),
+    (one_byte(0x51), "Fostex"),
+    (one_byte(0x52), "Zoom"),
+    (three_byte(0x00, 0x0E), "Alesis"),
+    (three_byte(0x00, 0x3B), "Mark of the Unicorn"),
+    (three_byte(0x00, 0x41), "Microsoft"),
+    (three_byte(0x00, 0x66), "Mackie"),
+    (three_byte(0x01, 0x05), "M-Audio"),
+    (three_byte(0x20, 0x1F), "TC Electronic"),
+    (three_byte(0x20, 0x29), "Focusrite / Novation"),
+    (three_byte(0x20, 0x32), "Behringer"),
+    (three_byte(0x20, 0x33), "Access Music"),
+    (three_byte(0x20, 0x3C), "Elektron"),
+    (three_byte(0x20, 0x6B), "Arturia"),
+    (three_byte(0x20, 0x76), "Teenage Engineering"),
+    (three_byte(0x21, 0x09), "Native Instruments"),
+    (three_byte(0x21, 0x27), "Expert Sleepers"),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn read_standard() {
+        let mut payload = [0x41, 0x10, 0x42].map(u7::new).into_iter();
+        assert_eq!(
+            ManufacturerId::read(&mut payload),
+            Ok(ManufacturerId::Standard(u7::new(0x41)))
+        );
+        assert_eq!(payload.next(), Some(u7::new(0x10)));
+    }
+
+    #[test]
+    fn read_extended() {
+        let mut payload = [0x00, 0x20, 0x6B, 0x7F].map(u7::new).into_iter();
+        assert_eq!(
+            ManufacturerId::read(&mut payload),
+            Ok(ManufacturerId::Extended(u7::new(0x20), u7::new(0x6B)))
+        );
+        assert_eq!(payload.next(), Some(u7::new(0x7F)));
+    }
+
+    #[test]
+    fn read_non_commercial() {
+        let mut payload = [0x7D, 0x01].map(u7::new).into_iter();
+        assert_eq!(
+            ManufacturerId::read(&mut payload),
+            Ok(ManufacturerId::NonCommercial)
+        );
+    }
+
+    #[test]
+    fn read_universal() {
+        let mut payload = [0x7E, 0x7F, 0x06, 0x01].map(u7::new).into_iter();
+        assert_eq!(
+            ManufacturerId::read(&mut payload),
+            Ok(ManufacturerId::UniversalNonRealTime)
+        );
+    }
+
+    #[test]
+    fn read_short_extended() {
+        let mut payload = [0x00, 0x20].map(u7::new).into_iter();
+        assert_eq!(
+            ManufacturerId::read(&mut payload),
+            Err(InvalidData(ERR_NO_MANUFACTURER_ID))
+        );
+    }
+
+    #[test]
+    fn read_empty() {
+        assert_eq!(
+            ManufacturerId::read(&mut core::iter::empty()),
+            Err(InvalidData(ERR_NO_MANUFACTURER_ID))
+        );
+    }
+
+    #[test]
+    fn extended_payload() {
+        assert_eq!(
+            ManufacturerId::Extended(u7::new(0x21), u7::new(0x09))
+                .payload()
+                .collect::<std::vec::Vec<u7>>(),
+            [0x00, 0x21, 0x09].map(u7::new),
+        );
+    }
+
+    #[test]
+    fn non_commercial_payload() {
+        assert_eq!(
+            ManufacturerId::NonCommercial
+                .payload()
+                .collect::<std::vec::Vec<u7>>(),
+            [u7::new(0x7D)],
+        );
+    }
+
+    #[test]
+    fn three_byte_form() {
+        assert_eq!(
+            <[u7; 3]>::from(ManufacturerId::Standard(u7::new(0x43))),
+            [0x43, 0x00, 0x00].map(u7::new),
+        );
+        assert_eq!(
+            ManufacturerId::try_from([0x43, 0x00, 0x00].map(u7::new)),
+            Ok(ManufacturerId::Standard(u7::new(0x43))),
+        );
+        assert_eq!(
+            ManufacturerId::try_from([0x00, 0x21, 0x09].map(u7::new)),
+            Ok(ManufacturerId::Extended(u7::new(0x21), u7::new(0x09))),
+        );
+    }
+
+    #[test]
+    fn three_byte_form_non_zero_padding() {
+        assert_eq!(
+            ManufacturerId::try_from([0x43, 0x12, 0x34].map(u7::new)),
+            Err(InvalidData(ERR_NON_ZERO_PADDING)),
+        );
+    }
+
+    #[test]
+    fn standard() {
+        assert_eq!(
+            ManufacturerId::standard(u7::new(0x43)),
+            Ok(ManufacturerId::Standard(u7::new(0x43))),
+        );
+        assert_eq!(
+            ManufacturerId::standard(u7::new(0x7C)),
+            Ok(ManufacturerId::Standard(u7::new(0x7C))),
+        );
+    }
+
+    #[test]
+    fn standard_reserved_ids() {
+        for id in [0x00, 0x7D, 0x7E, 0x7F] {
+            assert_eq!(
+                ManufacturerId::standard(u7::new(id)),
+                Err(InvalidData(ERR_INVALID_STANDARD_ID)),
+            );
+        }
+    }
+
+    #[test]
+    fn default_is_all_zero() {
+        assert_eq!(
+            <[u7; 3]>::from(ManufacturerId::default()),
+            [u7::new(0x0); 3]
+        );
+    }
+
+    #[test]
+    fn names() {
+        assert_eq!(
+            ManufacturerId::Standard(u7::new(0x41)).name(),
+            Some("Roland")
+        );
+        assert_eq!(ManufacturerId::NonCommercial.name(), Some("Non-Commercial"));
+        assert_eq!(ManufacturerId::Standard(u7::new(0x7C)).name(), None);
+    }
+
+    #[cfg(feature = "sysex7")]
+    #[test]
+    fn from_ump_sysex() {
+        let message = crate::sysex7::Sysex7::try_from(&[0x3003_0020_u32, 0x2900_0000][..]).unwrap();
+        assert_eq!(
+            ManufacturerId::try_from(&message),
+            Ok(ManufacturerId::Extended(u7::new(0x20), u7::new(0x29)))
+        );
+    }
+}
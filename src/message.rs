@@ -9,6 +9,19 @@
     PartialEq,
     Eq,
 )]
+#[cfg_attr(
+    any(
+        feature = "channel-voice1",
+        feature = "channel-voice2",
+        feature = "flex-data",
+        feature = "sysex7",
+        feature = "sysex8",
+        feature = "system-common",
+        feature = "ump-stream",
+        feature = "utility"
+    ),
+    derive(midi2_proc::Remappable)
+)]
 #[non_exhaustive]
 pub enum UmpMessage<B: crate::buffer::Ump> {
     #[cfg(feature = "flex-data")]
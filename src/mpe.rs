@@ -0,0 +1,15 @@
+//! MIDI Polyphonic Expression.
+//!
+//! MPE carries per-note expression over MIDI 1.0 by giving each sounding note
+//! its own member channel within a zone.
+//! The zones are configured with the MPE configuration message (registered
+//! parameter `0x0006`) sent on their manager channel: the first channel for the
+//! lower zone and the last channel for the upper zone.
+
+mod to_midi2;
+mod to_mpe;
+mod zones;
+
+pub use to_midi2::*;
+pub use to_mpe::*;
+pub use zones::*;
@@ -0,0 +1,554 @@
+use crate::{
+    channel_voice1::{self, ChannelVoice1, ControlFunction, ControllerDecoder, ControllerEvent},
+    channel_voice2::{self, ChannelVoice2, Controller},
+    mpe::{ChannelRole, Zone, Zones},
+    traits::{Channeled, Grouped},
+    translation::{scale_up, to_midi2},
+    ux::{u14, u4, u7},
+};
+
+const PITCH_BEND_SENSITIVITY: u16 = 0x0000;
+const MPE_CONFIGURATION: u16 = 0x0006;
+const TIMBRE_CONTROL: u8 = 74;
+const BRIGHTNESS_SOUND_CONTROLLER: u8 = 5;
+const DEFAULT_MEMBER_PITCH_BEND_RANGE: u32 = 48 * CENTS_PER_SEMITONE;
+const DEFAULT_PER_NOTE_PITCH_BEND_RANGE: u8 = 48;
+const CENTS_PER_SEMITONE: u32 = 100;
+const PITCH_BEND_CENTER: i64 = 0x8000_0000;
+
+/// Converts MPE over MIDI 1.0 into MIDI 2.0 per-note messages.
+///
+/// The zones are tracked from the MPE configuration messages received on the
+/// manager channels and member channel pitch bend sensitivity from the
+/// registered parameter `0x0000` received on any member channel of a zone.
+///
+/// * Notes on member channels become notes on the manager channel of their zone.
+/// * Member channel pitch bend becomes per-note pitch bend, rescaled from the
+///   pitch bend sensitivity of the zone to the
+///   [per-note pitch bend range](MpeToMidi2::set_per_note_pitch_bend_range)
+///   of the receiver.
+/// * Member channel timbre (CC74) becomes the brightness registered per-note controller.
+/// * Member channel pressure becomes key pressure.
+///
+/// Expression received before a note on is applied to the note
+/// straight after its note on.
+/// Configuration messages are consumed, other (N)RPN data entry becomes
+/// registered and assignable controllers and everything else is translated
+/// with [to_midi2](crate::translation::to_midi2) on its own channel.
+///
+/// ```rust
+/// use midi2::{prelude::*, mpe::MpeToMidi2};
+///
+/// let mut converter = MpeToMidi2::new();
+///
+/// // configure a lower zone with three member channels
+/// for message in [0x20B0_6500, 0x20B0_6406, 0x20B0_0603] {
+///     let buffer = [message];
+///     let message = channel_voice1::ChannelVoice1::try_from(&buffer[..]).unwrap();
+///     assert_eq!(converter.convert(&message).count(), 0);
+/// }
+///
+/// let note_on = channel_voice1::ChannelVoice1::try_from(&[0x2092_3C7F_u32][..]).unwrap();
+/// let mut messages = converter.convert(&note_on);
+/// assert_eq!(messages.next().unwrap().data(), &[0x4090_3C00, 0xFFFF_0000]);
+///
+/// let bend = channel_voice1::ChannelVoice1::try_from(&[0x20E2_0040_u32][..]).unwrap();
+/// let mut messages = converter.convert(&bend);
+/// assert_eq!(messages.next().unwrap().data(), &[0x4060_3C00, 0x8000_0000]);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MpeToMidi2 {
+    zones: Zones,
+    member_pitch_bend_range: [u32; 2],
+    per_note_pitch_bend_range: u8,
+    decoders: [ControllerDecoder; 16],
+    channels: [MemberChannel; 16],
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+struct MemberChannel {
+    notes: u128,
+    pitch_bend: Option<u14>,
+    timbre: Option<u7>,
+    pressure: Option<u7>,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Expression {
+    PitchBend(u32),
+    Timbre(u32),
+    Pressure(u32),
+}
+
+impl core::default::Default for MpeToMidi2 {
+    fn default() -> Self {
+        Self {
+            zones: Zones::new(),
+            member_pitch_bend_range: [DEFAULT_MEMBER_PITCH_BEND_RANGE; 2],
+            per_note_pitch_bend_range: DEFAULT_PER_NOTE_PITCH_BEND_RANGE,
+            decoders: Default::default(),
+            channels: Default::default(),
+        }
+    }
+}
+
+impl MpeToMidi2 {
+    /// No zones are configured and the per-note pitch bend range is 48 semitones.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn zones(&self) -> Zones {
+        self.zones
+    }
+
+    /// Configure the zones directly rather than through MPE configuration messages.
+    ///
+    /// The member channel pitch bend sensitivity of both zones is reset to 48 semitones.
+    pub fn set_zones(&mut self, zones: Zones) {
+        self.zones = zones;
+        self.member_pitch_bend_range = [DEFAULT_MEMBER_PITCH_BEND_RANGE; 2];
+    }
+
+    /// The pitch bend range in semitones which the receiver of the
+    /// converted messages applies to per-note pitch bend.
+    pub fn per_note_pitch_bend_range(&self) -> u8 {
+        self.per_note_pitch_bend_range
+    }
+
+    /// The range is at least one semitone.
+    pub fn set_per_note_pitch_bend_range(&mut self, semitones: u8) {
+        self.per_note_pitch_bend_range = semitones.max(1);
+    }
+
+    /// Convert the next message of the MPE stream.
+    pub fn convert<B: crate::buffer::Ump>(&mut self, message: &ChannelVoice1<B>) -> Midi2Messages {
+        let mut messages = Midi2Messages {
+            group: message.group(),
+            ..Default::default()
+        };
+        let role = self.zones.role(message.channel());
+        match (message, role) {
+            (ChannelVoice1::ControlChange(m), _) => {
+                self.control_change(message, m, role, &mut messages)
+            }
+            (_, Some(ChannelRole::Member(zone))) => {
+                self.member_message(message, zone, &mut messages)
+            }
+            _ => messages.push(to_midi2(message)),
+        }
+        messages
+    }
+
+    fn member_message<B: crate::buffer::Ump>(
+        &mut self,
+        message: &ChannelVoice1<B>,
+        zone: Zone,
+        messages: &mut Midi2Messages,
+    ) {
+        let manager = zone.manager_channel();
+        let channel = usize::from(u8::from(message.channel()));
+        match message {
+            ChannelVoice1::NoteOn(_)
+            | ChannelVoice1::NoteOff(_)
+            | ChannelVoice1::KeyPressure(_) => {
+                let mut translated = to_midi2(message);
+                translated.set_channel(manager);
+                match &translated {
+                    ChannelVoice2::NoteOn(m) => {
+                        let note = m.note_number();
+                        self.channels[channel].notes |= 1 << u8::from(note);
+                        messages.push(translated);
+                        let state = self.channels[channel];
+                        if let Some(bend) = state.pitch_bend {
+                            let bend = self.per_note_pitch_bend(zone, bend);
+                            messages.push(messages.expression(bend, manager, note));
+                        }
+                        if let Some(timbre) = state.timbre {
+                            let timbre =
+                                Expression::Timbre(scale_up(u8::from(timbre).into(), 7, 32));
+                            messages.push(messages.expression(timbre, manager, note));
+                        }
+                        if let Some(pressure) = state.pressure {
+                            let pressure =
+                                Expression::Pressure(scale_up(u8::from(pressure).into(), 7, 32));
+                            messages.push(messages.expression(pressure, manager, note));
+                        }
+                    }
+                    ChannelVoice2::NoteOff(m) => {
+                        self.channels[channel].notes &= !(1 << u8::from(m.note_number()));
+                        messages.push(translated);
+                    }
+                    _ => messages.push(translated),
+                }
+            }
+            ChannelVoice1::PitchBend(m) => {
+                self.channels[channel].pitch_bend = Some(m.bend());
+                let expression = self.per_note_pitch_bend(zone, m.bend());
+                messages.set_expression(expression, manager, self.channels[channel].notes);
+            }
+            ChannelVoice1::ChannelPressure(m) => {
+                self.channels[channel].pressure = Some(m.pressure());
+                let expression =
+                    Expression::Pressure(scale_up(u8::from(m.pressure()).into(), 7, 32));
+                messages.set_expression(expression, manager, self.channels[channel].notes);
+            }
+            _ => messages.push(to_midi2(message)),
+        }
+    }
+
+    fn control_change<B: crate::buffer::Ump>(
+        &mut self,
+        message: &ChannelVoice1<B>,
+        control_change: &channel_voice1::ControlChange<B>,
+        role: Option<ChannelRole>,
+        messages: &mut Midi2Messages,
+    ) {
+        let channel = message.channel();
+        let index = usize::from(u8::from(channel));
+        let Some(event) = self.decoders[index].decode(control_change) else {
+            return;
+        };
+        match (event, role) {
+            (ControllerEvent::Rpn { parameter, value }, _)
+                if u16::from(parameter) == MPE_CONFIGURATION && manager_zone(channel).is_some() =>
+            {
+                let zone = manager_zone(channel).unwrap();
+                self.zones
+                    .set_member_count(zone, (u16::from(value) >> 7) as u8);
+                self.member_pitch_bend_range[zone.index()] = DEFAULT_MEMBER_PITCH_BEND_RANGE;
+            }
+            (ControllerEvent::Rpn { parameter, value }, Some(ChannelRole::Member(zone)))
+                if u16::from(parameter) == PITCH_BEND_SENSITIVITY =>
+            {
+                let value = u32::from(u16::from(value));
+                self.member_pitch_bend_range[zone.index()] =
+                    (value >> 7) * CENTS_PER_SEMITONE + (value & 0x7F);
+            }
+            (ControllerEvent::Rpn { parameter, value }, _) => {
+                let mut m = channel_voice2::RegisteredController::<[u32; 4]>::new();
+                m.set_bank(u7::new((u16::from(parameter) >> 7) as u8));
+                m.set_index(u7::new((u16::from(parameter) & 0x7F) as u8));
+                m.set_controller_data(scale_up(u16::from(value).into(), 14, 32));
+                m.set_channel(channel);
+                messages.push(m.into());
+            }
+            (ControllerEvent::Nrpn { parameter, value }, _) => {
+                let mut m = channel_voice2::AssignableController::<[u32; 4]>::new();
+                m.set_bank(u7::new((u16::from(parameter) >> 7) as u8));
+                m.set_index(u7::new((u16::from(parameter) & 0x7F) as u8));
+                m.set_controller_data(scale_up(u16::from(value).into(), 14, 32));
+                m.set_channel(channel);
+                messages.push(m.into());
+            }
+            (
+                ControllerEvent::Other(ControlFunction::Other { control, data }),
+                Some(ChannelRole::Member(zone)),
+            ) if u8::from(control) == TIMBRE_CONTROL => {
+                self.channels[index].timbre = Some(data);
+                let expression = Expression::Timbre(scale_up(u8::from(data).into(), 7, 32));
+                messages.set_expression(
+                    expression,
+                    zone.manager_channel(),
+                    self.channels[index].notes,
+                );
+            }
+            (
+                ControllerEvent::Other(ControlFunction::AllNotesOff | ControlFunction::AllSoundOff),
+                Some(ChannelRole::Manager(zone)),
+            ) => {
+                for member in self.zones.member_channels(zone) {
+                    self.channels[usize::from(u8::from(member))].notes = 0;
+                }
+                messages.push(to_midi2(message));
+            }
+            _ => messages.push(to_midi2(message)),
+        }
+    }
+
+    fn per_note_pitch_bend(&self, zone: Zone, bend: u14) -> Expression {
+        let offset = scale_up(u16::from(bend).into(), 14, 32) as i64 - PITCH_BEND_CENTER;
+        let scaled = offset * self.member_pitch_bend_range[zone.index()] as i64
+            / (self.per_note_pitch_bend_range as i64 * CENTS_PER_SEMITONE as i64);
+        Expression::PitchBend((PITCH_BEND_CENTER + scaled).clamp(0, u32::MAX as i64) as u32)
+    }
+}
+
+/// The zone configured by an MPE configuration message received on the channel,
+/// which needn't yet be a manager channel.
+fn manager_zone(channel: u4) -> Option<Zone> {
+    [Zone::Lower, Zone::Upper]
+        .into_iter()
+        .find(|zone| zone.manager_channel() == channel)
+}
+
+/// The MIDI 2.0 messages produced by [MpeToMidi2::convert].
+///
+/// Member channel expression is yielded once for every note
+/// sounding on the member channel.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Midi2Messages {
+    messages: [Option<ChannelVoice2<[u32; 4]>>; 4],
+    len: usize,
+    index: usize,
+    group: u4,
+    expression: Option<(Expression, u4)>,
+    notes: u128,
+}
+
+impl Midi2Messages {
+    fn push(&mut self, mut message: ChannelVoice2<[u32; 4]>) {
+        message.set_group(self.group);
+        self.messages[self.len] = Some(message);
+        self.len += 1;
+    }
+
+    fn set_expression(&mut self, expression: Expression, channel: u4, notes: u128) {
+        self.expression = Some((expression, channel));
+        self.notes = notes;
+    }
+
+    fn expression(&self, expression: Expression, channel: u4, note: u7) -> ChannelVoice2<[u32; 4]> {
+        let mut message: ChannelVoice2<[u32; 4]> = match expression {
+            Expression::PitchBend(data) => {
+                let mut m = channel_voice2::PerNotePitchBend::<[u32; 4]>::new();
+                m.set_note_number(note);
+                m.set_pitch_bend_data(data);
+                m.into()
+            }
+            Expression::Timbre(data) => {
+                let mut m = channel_voice2::RegisteredPerNoteController::<[u32; 4]>::new();
+                m.set_note_number(note);
+                m.set_controller(Controller::SoundController {
+                    index: BRIGHTNESS_SOUND_CONTROLLER,
+                    data,
+                });
+                m.into()
+            }
+            Expression::Pressure(data) => {
+                let mut m = channel_voice2::KeyPressure::<[u32; 4]>::new();
+                m.set_note_number(note);
+                m.set_key_pressure_data(data);
+                m.into()
+            }
+        };
+        message.set_group(self.group);
+        message.set_channel(channel);
+        message
+    }
+}
+
+impl core::iter::Iterator for Midi2Messages {
+    type Item = ChannelVoice2<[u32; 4]>;
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.index != self.len {
+            let message = self.messages[self.index].take();
+            self.index += 1;
+            return message;
+        }
+        let (expression, channel) = self.expression?;
+        if self.notes == 0 {
+            return None;
+        }
+        let note = self.notes.trailing_zeros() as u8;
+        self.notes &= self.notes - 1;
+        Some(self.expression(expression, channel, u7::new(note)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::traits::Data;
+    use pretty_assertions::assert_eq;
+    use std::vec::Vec;
+
+    fn convert(converter: &mut MpeToMidi2, ump: u32) -> Vec<Vec<u32>> {
+        let buffer = [ump];
+        let message = ChannelVoice1::try_from(&buffer[..]).unwrap();
+        converter
+            .convert(&message)
+            .map(|m| m.data().to_vec())
+            .collect()
+    }
+
+    fn lower_zone() -> MpeToMidi2 {
+        let mut converter = MpeToMidi2::new();
+        for ump in [0x20B0_6500, 0x20B0_6406, 0x20B0_0603] {
+            assert_eq!(convert(&mut converter, ump), Vec::<Vec<u32>>::new());
+        }
+        converter
+    }
+
+    #[test]
+    fn mpe_configuration() {
+        let converter = lower_zone();
+        assert_eq!(converter.zones().member_count(Zone::Lower), 3);
+        assert_eq!(converter.zones().member_count(Zone::Upper), 0);
+    }
+
+    #[test]
+    fn upper_zone_configuration() {
+        let mut converter = MpeToMidi2::new();
+        for ump in [0x20BF_6500, 0x20BF_6406, 0x20BF_0605] {
+            convert(&mut converter, ump);
+        }
+        assert_eq!(converter.zones().member_count(Zone::Upper), 5);
+    }
+
+    #[test]
+    fn member_note_moves_to_manager_channel() {
+        let mut converter = lower_zone();
+        assert_eq!(
+            convert(&mut converter, 0x2492_3C40),
+            std::vec![std::vec![0x4490_3C00, 0x8000_0000]]
+        );
+        assert_eq!(
+            convert(&mut converter, 0x2482_3C40),
+            std::vec![std::vec![0x4480_3C00, 0x8000_0000]]
+        );
+    }
+
+    #[test]
+    fn pitch_bend() {
+        let mut converter = lower_zone();
+        convert(&mut converter, 0x2091_3C40);
+        assert_eq!(
+            convert(&mut converter, 0x20E1_7F7F),
+            std::vec![std::vec![0x4060_3C00, 0xFFFF_FFFF]]
+        );
+        assert_eq!(
+            convert(&mut converter, 0x20E1_0000),
+            std::vec![std::vec![0x4060_3C00, 0x0000_0000]]
+        );
+    }
+
+    #[test]
+    fn pitch_bend_range_is_rescaled() {
+        let mut converter = lower_zone();
+        // member pitch bend sensitivity of 24 semitones
+        for ump in [0x20B1_6500, 0x20B1_6400, 0x20B1_0618] {
+            assert_eq!(convert(&mut converter, ump), Vec::<Vec<u32>>::new());
+        }
+        convert(&mut converter, 0x2091_3C40);
+        assert_eq!(
+            convert(&mut converter, 0x20E1_0000),
+            std::vec![std::vec![0x4060_3C00, 0x4000_0000]]
+        );
+    }
+
+    #[test]
+    fn per_note_pitch_bend_range() {
+        let mut converter = lower_zone();
+        converter.set_per_note_pitch_bend_range(96);
+        convert(&mut converter, 0x2091_3C40);
+        assert_eq!(
+            convert(&mut converter, 0x20E1_0000),
+            std::vec![std::vec![0x4060_3C00, 0x4000_0000]]
+        );
+    }
+
+    #[test]
+    fn timbre() {
+        let mut converter = lower_zone();
+        convert(&mut converter, 0x2091_3C40);
+        assert_eq!(
+            convert(&mut converter, 0x20B1_4A7F),
+            std::vec![std::vec![0x4000_3C4A, 0xFFFF_FFFF]]
+        );
+    }
+
+    #[test]
+    fn pressure() {
+        let mut converter = lower_zone();
+        convert(&mut converter, 0x2091_3C40);
+        assert_eq!(
+            convert(&mut converter, 0x20D1_4000),
+            std::vec![std::vec![0x40A0_3C00, 0x8000_0000]]
+        );
+    }
+
+    #[test]
+    fn expression_before_note_on() {
+        let mut converter = lower_zone();
+        assert_eq!(convert(&mut converter, 0x20E2_0040), Vec::<Vec<u32>>::new());
+        convert(&mut converter, 0x20B2_4A40);
+        convert(&mut converter, 0x20D2_0000);
+        assert_eq!(
+            convert(&mut converter, 0x2092_3E7F),
+            std::vec![
+                std::vec![0x4090_3E00, 0xFFFF_0000],
+                std::vec![0x4060_3E00, 0x8000_0000],
+                std::vec![0x4000_3E4A, 0x8000_0000],
+                std::vec![0x40A0_3E00, 0x0000_0000],
+            ]
+        );
+    }
+
+    #[test]
+    fn expression_applies_to_every_note_of_the_channel() {
+        let mut converter = lower_zone();
+        convert(&mut converter, 0x2091_3C40);
+        convert(&mut converter, 0x2091_4040);
+        assert_eq!(
+            convert(&mut converter, 0x20D1_7F00),
+            std::vec![
+                std::vec![0x40A0_3C00, 0xFFFF_FFFF],
+                std::vec![0x40A0_4000, 0xFFFF_FFFF],
+            ]
+        );
+        convert(&mut converter, 0x2081_3C40);
+        assert_eq!(
+            convert(&mut converter, 0x20D1_4000),
+            std::vec![std::vec![0x40A0_4000, 0x8000_0000]]
+        );
+    }
+
+    #[test]
+    fn manager_channel_messages_pass_through() {
+        let mut converter = lower_zone();
+        assert_eq!(
+            convert(&mut converter, 0x20E0_0040),
+            std::vec![std::vec![0x40E0_0000, 0x8000_0000]]
+        );
+        assert_eq!(
+            convert(&mut converter, 0x20B0_4040),
+            std::vec![std::vec![0x40B0_4000, 0x8000_0000]]
+        );
+    }
+
+    #[test]
+    fn channels_outside_the_zones_pass_through() {
+        let mut converter = lower_zone();
+        assert_eq!(
+            convert(&mut converter, 0x2095_3C40),
+            std::vec![std::vec![0x4095_3C00, 0x8000_0000]]
+        );
+        assert_eq!(
+            convert(&mut converter, 0x20E5_0040),
+            std::vec![std::vec![0x40E5_0000, 0x8000_0000]]
+        );
+    }
+
+    #[test]
+    fn other_parameters_become_registered_controllers() {
+        let mut converter = lower_zone();
+        convert(&mut converter, 0x20B0_6500);
+        convert(&mut converter, 0x20B0_6400);
+        assert_eq!(
+            convert(&mut converter, 0x20B0_060C),
+            std::vec![std::vec![0x4020_0000, 0x1800_0000]]
+        );
+    }
+
+    #[test]
+    fn all_notes_off_forgets_member_notes() {
+        let mut converter = lower_zone();
+        convert(&mut converter, 0x2091_3C40);
+        assert_eq!(
+            convert(&mut converter, 0x20B0_7B00),
+            std::vec![std::vec![0x40B0_7B00, 0x0000_0000]]
+        );
+        assert_eq!(convert(&mut converter, 0x20D1_4000), Vec::<Vec<u32>>::new());
+    }
+}
@@ -0,0 +1,649 @@
+use crate::{
+    channel_voice1::{self, ChannelVoice1, ControlFunction, ControllerEvent},
+    channel_voice2::{ChannelVoice2, Controller},
+    mpe::{Zone, Zones},
+    traits::{Channeled, Grouped},
+    translation::{scale_down, to_midi1},
+    ux::{u14, u4, u7},
+};
+
+const PITCH_BEND_SENSITIVITY: u16 = 0x0000;
+const MPE_CONFIGURATION: u16 = 0x0006;
+const TIMBRE_CONTROL: u8 = 74;
+const BRIGHTNESS_SOUND_CONTROLLER: u8 = 5;
+const DEFAULT_PITCH_BEND_RANGE: u8 = 48;
+const PITCH_BEND_CENTER: i64 = 0x8000_0000;
+const NEUTRAL_PITCH_BEND: u16 = 0x2000;
+const NEUTRAL_TIMBRE: u8 = 0x40;
+const NEUTRAL_PRESSURE: u8 = 0x0;
+const ENDED_NOTE_OFF_VELOCITY: u8 = 0x40;
+
+/// Which sounding note gives up its member channel
+/// when a note on arrives and every member channel is in use.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum VoiceStealing {
+    /// The note which has been sounding the longest.
+    #[default]
+    Oldest,
+    /// The note with the lowest velocity,
+    /// the longest sounding of those when several are equal.
+    Quietest,
+    /// No note is stolen and the new note is dropped,
+    /// along with its expression and note off.
+    Disabled,
+}
+
+/// Converts MIDI 2.0 notes and per-note expression into MPE over MIDI 1.0.
+///
+/// Every note is given a member channel of the zone.
+/// Free member channels are allocated in the order they were released,
+/// so that the release of a note has the longest possible time to ring out
+/// before its channel is reused.
+/// A note on for a note which is already sounding ends it and reuses its channel.
+/// When every member channel is in use the [VoiceStealing] policy decides
+/// which note is ended to make way for the new one.
+///
+/// * Per-note pitch bend becomes pitch bend on the member channel,
+///   rescaled from the [per-note pitch bend range](Midi2ToMpe::set_per_note_pitch_bend_range)
+///   of the source to the [member pitch bend range](Midi2ToMpe::set_member_pitch_bend_range).
+/// * The brightness registered per-note controller and assignable per-note
+///   controller 74 become timbre (CC74) on the member channel.
+/// * Key pressure becomes channel pressure on the member channel.
+///
+/// Before a note on, any expression left on the member channel by its previous
+/// note is returned to neutral.
+/// Other per-note messages have no MPE equivalent and yield nothing.
+/// Everything else is translated with [to_midi1](crate::translation::to_midi1)
+/// onto the manager channel.
+///
+/// The receiver is configured with the messages from [Midi2ToMpe::setup_messages],
+/// which should be sent before any converted messages.
+///
+/// ```rust
+/// use midi2::{prelude::*, mpe::{Midi2ToMpe, Zone}};
+///
+/// let mut converter = Midi2ToMpe::new(Zone::Lower, 2);
+///
+/// let first = channel_voice2::ChannelVoice2::try_from(&[0x4090_3C00, 0xFFFF_0000][..]).unwrap();
+/// let second = channel_voice2::ChannelVoice2::try_from(&[0x4090_4000, 0xFFFF_0000][..]).unwrap();
+/// let bend = channel_voice2::ChannelVoice2::try_from(&[0x4060_4000, 0x8000_0000][..]).unwrap();
+///
+/// assert_eq!(converter.convert(&first).next().unwrap().data(), &[0x2091_3C7F]);
+/// assert_eq!(converter.convert(&second).next().unwrap().data(), &[0x2092_407F]);
+/// assert_eq!(converter.convert(&bend).next().unwrap().data(), &[0x20E2_0040]);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Midi2ToMpe {
+    zone: Zone,
+    zones: Zones,
+    voice_stealing: VoiceStealing,
+    member_pitch_bend_range: u8,
+    per_note_pitch_bend_range: u8,
+    members: [Member; 15],
+    clock: u32,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+struct Member {
+    note: Option<(u4, u7)>,
+    velocity: u16,
+    // note on time while sounding, note off time while free
+    time: u32,
+    pitch_bend: u14,
+    timbre: u7,
+    pressure: u7,
+}
+
+impl core::default::Default for Member {
+    fn default() -> Self {
+        Self {
+            note: None,
+            velocity: 0,
+            time: 0,
+            pitch_bend: u14::new(NEUTRAL_PITCH_BEND),
+            timbre: u7::new(NEUTRAL_TIMBRE),
+            pressure: u7::new(NEUTRAL_PRESSURE),
+        }
+    }
+}
+
+impl Midi2ToMpe {
+    /// Convert into the zone with the given number of member channels.
+    ///
+    /// The member count is clamped to `1..=15`.
+    /// Both pitch bend ranges default to 48 semitones.
+    pub fn new(zone: Zone, member_count: u8) -> Self {
+        let mut zones = Zones::new();
+        zones.set_member_count(zone, member_count.max(1));
+        Self {
+            zone,
+            zones,
+            voice_stealing: VoiceStealing::default(),
+            member_pitch_bend_range: DEFAULT_PITCH_BEND_RANGE,
+            per_note_pitch_bend_range: DEFAULT_PITCH_BEND_RANGE,
+            members: Default::default(),
+            clock: 0,
+        }
+    }
+
+    pub fn zone(&self) -> Zone {
+        self.zone
+    }
+
+    pub fn member_count(&self) -> u8 {
+        self.zones.member_count(self.zone)
+    }
+
+    pub fn voice_stealing(&self) -> VoiceStealing {
+        self.voice_stealing
+    }
+
+    pub fn set_voice_stealing(&mut self, voice_stealing: VoiceStealing) {
+        self.voice_stealing = voice_stealing;
+    }
+
+    /// The pitch bend sensitivity in semitones configured on the member channels.
+    pub fn member_pitch_bend_range(&self) -> u8 {
+        self.member_pitch_bend_range
+    }
+
+    /// The range is clamped to `1..=127` semitones.
+    pub fn set_member_pitch_bend_range(&mut self, semitones: u8) {
+        self.member_pitch_bend_range = semitones.clamp(1, 0x7F);
+    }
+
+    /// The pitch bend range in semitones which the source of the
+    /// converted messages applies to per-note pitch bend.
+    pub fn per_note_pitch_bend_range(&self) -> u8 {
+        self.per_note_pitch_bend_range
+    }
+
+    /// The range is at least one semitone.
+    pub fn set_per_note_pitch_bend_range(&mut self, semitones: u8) {
+        self.per_note_pitch_bend_range = semitones.max(1);
+    }
+
+    /// The messages which configure the receiver for the zone:
+    /// the MPE configuration message on the manager channel followed by
+    /// the pitch bend sensitivity of every member channel.
+    pub fn setup_messages(&self, group: u4) -> impl Iterator<Item = ChannelVoice1<[u32; 4]>> {
+        let configuration = ControllerEvent::Rpn {
+            parameter: u14::new(MPE_CONFIGURATION),
+            value: u14::new(u16::from(self.member_count()) << 7),
+        };
+        let pitch_bend_sensitivity = ControllerEvent::Rpn {
+            parameter: u14::new(PITCH_BEND_SENSITIVITY),
+            value: u14::new(u16::from(self.member_pitch_bend_range) << 7),
+        };
+        core::iter::once((self.zone.manager_channel(), configuration))
+            .chain(
+                self.zones
+                    .member_channels(self.zone)
+                    .map(move |channel| (channel, pitch_bend_sensitivity)),
+            )
+            .flat_map(move |(channel, event)| {
+                event
+                    .control_functions()
+                    .map(move |function| control_change(group, channel, function))
+            })
+    }
+
+    /// Convert the next MIDI 2.0 message.
+    pub fn convert<B: crate::buffer::Ump>(&mut self, message: &ChannelVoice2<B>) -> MpeMessages {
+        let mut messages = MpeMessages::default();
+        let group = message.group();
+        let key = (message.channel(), note_number(message));
+        match message {
+            ChannelVoice2::NoteOn(m) => {
+                // a retriggered note ends the sounding one and keeps its channel
+                let retriggered = self.find(key);
+                if let Some(slot) = retriggered {
+                    self.end_note(group, slot, &mut messages);
+                }
+                let Some(slot) = retriggered.or_else(|| self.allocate(group, &mut messages)) else {
+                    return messages;
+                };
+                let member = &mut self.members[slot];
+                let channel = member_channel(self.zone, slot);
+                if member.pitch_bend != u14::new(NEUTRAL_PITCH_BEND) {
+                    member.pitch_bend = u14::new(NEUTRAL_PITCH_BEND);
+                    messages.push(pitch_bend(group, channel, member.pitch_bend));
+                }
+                if member.timbre != u7::new(NEUTRAL_TIMBRE) {
+                    member.timbre = u7::new(NEUTRAL_TIMBRE);
+                    messages.push(timbre(group, channel, member.timbre));
+                }
+                if member.pressure != u7::new(NEUTRAL_PRESSURE) {
+                    member.pressure = u7::new(NEUTRAL_PRESSURE);
+                    messages.push(pressure(group, channel, member.pressure));
+                }
+                self.clock = self.clock.wrapping_add(1);
+                member.note = key.1.map(|note| (key.0, note));
+                member.velocity = m.velocity();
+                member.time = self.clock;
+                messages.push_translated(message, channel);
+            }
+            ChannelVoice2::NoteOff(_) => {
+                let Some(slot) = self.find(key) else {
+                    return messages;
+                };
+                self.clock = self.clock.wrapping_add(1);
+                self.members[slot].note = None;
+                self.members[slot].time = self.clock;
+                messages.push_translated(message, member_channel(self.zone, slot));
+            }
+            ChannelVoice2::PerNotePitchBend(m) => {
+                let Some(slot) = self.find(key) else {
+                    return messages;
+                };
+                let bend = self.member_pitch_bend(m.pitch_bend_data());
+                self.members[slot].pitch_bend = bend;
+                messages.push(pitch_bend(group, member_channel(self.zone, slot), bend));
+            }
+            ChannelVoice2::KeyPressure(m) => {
+                let Some(slot) = self.find(key) else {
+                    return messages;
+                };
+                let value = u7::new(scale_down(m.key_pressure_data(), 32, 7) as u8);
+                self.members[slot].pressure = value;
+                messages.push(pressure(group, member_channel(self.zone, slot), value));
+            }
+            ChannelVoice2::RegisteredPerNoteController(m) => {
+                if let Controller::SoundController {
+                    index: BRIGHTNESS_SOUND_CONTROLLER,
+                    data,
+                } = m.controller()
+                {
+                    self.push_timbre(key, data, group, &mut messages);
+                }
+            }
+            ChannelVoice2::AssignablePerNoteController(m) => {
+                if m.index() == TIMBRE_CONTROL {
+                    self.push_timbre(key, m.controller_data(), group, &mut messages);
+                }
+            }
+            ChannelVoice2::PerNoteManagement(_) => {}
+            _ => messages.push_translated(message, self.zone.manager_channel()),
+        }
+        messages
+    }
+
+    fn push_timbre(
+        &mut self,
+        key: (u4, Option<u7>),
+        data: u32,
+        group: u4,
+        messages: &mut MpeMessages,
+    ) {
+        let Some(slot) = self.find(key) else {
+            return;
+        };
+        let value = u7::new(scale_down(data, 32, 7) as u8);
+        self.members[slot].timbre = value;
+        messages.push(timbre(group, member_channel(self.zone, slot), value));
+    }
+
+    fn find(&self, key: (u4, Option<u7>)) -> Option<usize> {
+        let (channel, note) = key;
+        let note = note?;
+        self.members[..self.member_count() as usize]
+            .iter()
+            .position(|m| m.note == Some((channel, note)))
+    }
+
+    fn allocate(&mut self, group: u4, messages: &mut MpeMessages) -> Option<usize> {
+        use core::cmp::Reverse;
+
+        let members = &self.members[..self.member_count() as usize];
+        let age = |m: &Member| self.clock.wrapping_sub(m.time);
+        let free = members
+            .iter()
+            .enumerate()
+            .filter(|(_, m)| m.note.is_none())
+            .min_by_key(|(_, m)| Reverse(age(m)))
+            .map(|(slot, _)| slot);
+        if free.is_some() {
+            return free;
+        }
+        let stolen = match self.voice_stealing {
+            VoiceStealing::Oldest => members
+                .iter()
+                .enumerate()
+                .min_by_key(|(_, m)| Reverse(age(m)))
+                .map(|(slot, _)| slot),
+            VoiceStealing::Quietest => members
+                .iter()
+                .enumerate()
+                .min_by_key(|(_, m)| (m.velocity, Reverse(age(m))))
+                .map(|(slot, _)| slot),
+            VoiceStealing::Disabled => None,
+        }?;
+        self.end_note(group, stolen, messages);
+        Some(stolen)
+    }
+
+    fn end_note(&mut self, group: u4, slot: usize, messages: &mut MpeMessages) {
+        let Some((_, note)) = self.members[slot].note.take() else {
+            return;
+        };
+        let mut note_off = channel_voice1::NoteOff::<[u32; 4]>::new();
+        note_off.set_group(group);
+        note_off.set_channel(member_channel(self.zone, slot));
+        note_off.set_note_number(note);
+        note_off.set_velocity(u7::new(ENDED_NOTE_OFF_VELOCITY));
+        messages.push(note_off.into());
+    }
+
+    fn member_pitch_bend(&self, data: u32) -> u14 {
+        let offset = data as i64 - PITCH_BEND_CENTER;
+        let scaled =
+            offset * self.per_note_pitch_bend_range as i64 / self.member_pitch_bend_range as i64;
+        let data = (PITCH_BEND_CENTER + scaled).clamp(0, u32::MAX as i64) as u32;
+        u14::new(scale_down(data, 32, 14) as u16)
+    }
+}
+
+fn member_channel(zone: Zone, slot: usize) -> u4 {
+    let offset = slot as u8 + 1;
+    match zone {
+        Zone::Lower => u4::new(u8::from(zone.manager_channel()) + offset),
+        Zone::Upper => u4::new(u8::from(zone.manager_channel()) - offset),
+    }
+}
+
+fn note_number<B: crate::buffer::Ump>(message: &ChannelVoice2<B>) -> Option<u7> {
+    match message {
+        ChannelVoice2::NoteOn(m) => Some(m.note_number()),
+        ChannelVoice2::NoteOff(m) => Some(m.note_number()),
+        ChannelVoice2::KeyPressure(m) => Some(m.note_number()),
+        ChannelVoice2::PerNotePitchBend(m) => Some(m.note_number()),
+        ChannelVoice2::RegisteredPerNoteController(m) => Some(m.note_number()),
+        ChannelVoice2::AssignablePerNoteController(m) => Some(m.note_number()),
+        ChannelVoice2::PerNoteManagement(m) => Some(m.note_number()),
+        _ => None,
+    }
+}
+
+fn control_change(group: u4, channel: u4, function: ControlFunction) -> ChannelVoice1<[u32; 4]> {
+    let mut message = channel_voice1::ControlChange::<[u32; 4]>::new();
+    message.set_group(group);
+    message.set_channel(channel);
+    message.set_control_function(function);
+    message.into()
+}
+
+fn pitch_bend(group: u4, channel: u4, bend: u14) -> ChannelVoice1<[u32; 4]> {
+    let mut message = channel_voice1::PitchBend::<[u32; 4]>::new();
+    message.set_group(group);
+    message.set_channel(channel);
+    message.set_bend(bend);
+    message.into()
+}
+
+fn timbre(group: u4, channel: u4, value: u7) -> ChannelVoice1<[u32; 4]> {
+    control_change(
+        group,
+        channel,
+        ControlFunction::Other {
+            control: u7::new(TIMBRE_CONTROL),
+            data: value,
+        },
+    )
+}
+
+fn pressure(group: u4, channel: u4, value: u7) -> ChannelVoice1<[u32; 4]> {
+    let mut message = channel_voice1::ChannelPressure::<[u32; 4]>::new();
+    message.set_group(group);
+    message.set_channel(channel);
+    message.set_pressure(value);
+    message.into()
+}
+
+/// The MIDI 1.0 messages produced by [Midi2ToMpe::convert].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MpeMessages {
+    messages: [Option<ChannelVoice1<[u32; 4]>>; 8],
+    len: usize,
+    index: usize,
+}
+
+impl MpeMessages {
+    fn push(&mut self, message: ChannelVoice1<[u32; 4]>) {
+        self.messages[self.len] = Some(message);
+        self.len += 1;
+    }
+
+    fn push_translated<B: crate::buffer::Ump>(&mut self, message: &ChannelVoice2<B>, channel: u4) {
+        for mut translated in to_midi1(message) {
+            translated.set_channel(channel);
+            self.push(translated);
+        }
+    }
+}
+
+impl core::iter::Iterator for MpeMessages {
+    type Item = ChannelVoice1<[u32; 4]>;
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.index == self.len {
+            return None;
+        }
+        let message = self.messages[self.index].take();
+        self.index += 1;
+        message
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::traits::Data;
+    use pretty_assertions::assert_eq;
+    use std::vec::Vec;
+
+    fn convert(converter: &mut Midi2ToMpe, ump: [u32; 2]) -> Vec<u32> {
+        let message = ChannelVoice2::try_from(&ump[..]).unwrap();
+        converter.convert(&message).map(|m| m.data()[0]).collect()
+    }
+
+    #[test]
+    fn setup_messages() {
+        let converter = Midi2ToMpe::new(Zone::Upper, 2);
+        assert_eq!(
+            converter
+                .setup_messages(u4::new(0x3))
+                .map(|m| m.data()[0])
+                .collect::<Vec<_>>(),
+            std::vec![
+                0x23BF_6500,
+                0x23BF_6406,
+                0x23BF_0602,
+                0x23BF_2600,
+                0x23BF_657F,
+                0x23BF_647F,
+                0x23BE_6500,
+                0x23BE_6400,
+                0x23BE_0630,
+                0x23BE_2600,
+                0x23BE_657F,
+                0x23BE_647F,
+                0x23BD_6500,
+                0x23BD_6400,
+                0x23BD_0630,
+                0x23BD_2600,
+                0x23BD_657F,
+                0x23BD_647F,
+            ]
+        );
+    }
+
+    #[test]
+    fn notes_are_given_their_own_channels() {
+        let mut converter = Midi2ToMpe::new(Zone::Lower, 3);
+        assert_eq!(
+            convert(&mut converter, [0x4090_3C00, 0x8000_0000]),
+            std::vec![0x2091_3C40]
+        );
+        assert_eq!(
+            convert(&mut converter, [0x4090_3E00, 0x8000_0000]),
+            std::vec![0x2092_3E40]
+        );
+        assert_eq!(
+            convert(&mut converter, [0x4080_3C00, 0x8000_0000]),
+            std::vec![0x2081_3C40]
+        );
+    }
+
+    #[test]
+    fn upper_zone_channels() {
+        let mut converter = Midi2ToMpe::new(Zone::Upper, 3);
+        assert_eq!(
+            convert(&mut converter, [0x4090_3C00, 0x8000_0000]),
+            std::vec![0x209E_3C40]
+        );
+    }
+
+    #[test]
+    fn least_recently_released_channel_is_reused() {
+        let mut converter = Midi2ToMpe::new(Zone::Lower, 3);
+        convert(&mut converter, [0x4090_3C00, 0x8000_0000]);
+        convert(&mut converter, [0x4090_3E00, 0x8000_0000]);
+        convert(&mut converter, [0x4080_3E00, 0x8000_0000]);
+        convert(&mut converter, [0x4080_3C00, 0x8000_0000]);
+        // channel 3 has never been used, then channel 2 was released first
+        assert_eq!(
+            convert(&mut converter, [0x4090_4000, 0x8000_0000]),
+            std::vec![0x2093_4040]
+        );
+        assert_eq!(
+            convert(&mut converter, [0x4090_4100, 0x8000_0000]),
+            std::vec![0x2092_4140]
+        );
+    }
+
+    #[test]
+    fn oldest_note_is_stolen() {
+        let mut converter = Midi2ToMpe::new(Zone::Lower, 2);
+        convert(&mut converter, [0x4090_3C00, 0x8000_0000]);
+        convert(&mut converter, [0x4090_3E00, 0x8000_0000]);
+        assert_eq!(
+            convert(&mut converter, [0x4090_4000, 0x8000_0000]),
+            std::vec![0x2081_3C40, 0x2091_4040]
+        );
+        // the stolen note's note off is dropped
+        assert_eq!(
+            convert(&mut converter, [0x4080_3C00, 0x8000_0000]),
+            Vec::<u32>::new()
+        );
+    }
+
+    #[test]
+    fn retriggered_note_keeps_its_channel() {
+        let mut converter = Midi2ToMpe::new(Zone::Lower, 3);
+        convert(&mut converter, [0x4090_3C00, 0x8000_0000]);
+        assert_eq!(
+            convert(&mut converter, [0x4090_3C00, 0xFFFF_0000]),
+            std::vec![0x2081_3C40, 0x2091_3C7F]
+        );
+        assert_eq!(
+            convert(&mut converter, [0x4080_3C00, 0x8000_0000]),
+            std::vec![0x2081_3C40]
+        );
+        // no other member channel is left sounding
+        assert_eq!(
+            convert(&mut converter, [0x4080_3C00, 0x8000_0000]),
+            Vec::<u32>::new()
+        );
+    }
+
+    #[test]
+    fn quietest_note_is_stolen() {
+        let mut converter = Midi2ToMpe::new(Zone::Lower, 2);
+        converter.set_voice_stealing(VoiceStealing::Quietest);
+        convert(&mut converter, [0x4090_3C00, 0x8000_0000]);
+        convert(&mut converter, [0x4090_3E00, 0x2000_0000]);
+        assert_eq!(
+            convert(&mut converter, [0x4090_4000, 0x8000_0000]),
+            std::vec![0x2082_3E40, 0x2092_4040]
+        );
+    }
+
+    #[test]
+    fn stealing_disabled() {
+        let mut converter = Midi2ToMpe::new(Zone::Lower, 1);
+        converter.set_voice_stealing(VoiceStealing::Disabled);
+        convert(&mut converter, [0x4090_3C00, 0x8000_0000]);
+        assert_eq!(
+            convert(&mut converter, [0x4090_4000, 0x8000_0000]),
+            Vec::<u32>::new()
+        );
+        assert_eq!(
+            convert(&mut converter, [0x40A0_4000, 0x8000_0000]),
+            Vec::<u32>::new()
+        );
+    }
+
+    #[test]
+    fn per_note_expression() {
+        let mut converter = Midi2ToMpe::new(Zone::Lower, 2);
+        convert(&mut converter, [0x4090_3C00, 0x8000_0000]);
+        convert(&mut converter, [0x4090_3E00, 0x8000_0000]);
+        assert_eq!(
+            convert(&mut converter, [0x4060_3E00, 0xFFFF_FFFF]),
+            std::vec![0x20E2_7F7F]
+        );
+        assert_eq!(
+            convert(&mut converter, [0x4000_3C4A, 0x0000_0000]),
+            std::vec![0x20B1_4A00]
+        );
+        assert_eq!(
+            convert(&mut converter, [0x4010_3E4A, 0xFFFF_FFFF]),
+            std::vec![0x20B2_4A7F]
+        );
+        assert_eq!(
+            convert(&mut converter, [0x40A0_3C00, 0x8000_0000]),
+            std::vec![0x20D1_4000]
+        );
+    }
+
+    #[test]
+    fn pitch_bend_range_is_rescaled() {
+        let mut converter = Midi2ToMpe::new(Zone::Lower, 2);
+        converter.set_member_pitch_bend_range(96);
+        convert(&mut converter, [0x4090_3C00, 0x8000_0000]);
+        assert_eq!(
+            convert(&mut converter, [0x4060_3C00, 0x0000_0000]),
+            std::vec![0x20E1_0020]
+        );
+    }
+
+    #[test]
+    fn expression_is_neutralised_before_reuse() {
+        let mut converter = Midi2ToMpe::new(Zone::Lower, 1);
+        convert(&mut converter, [0x4090_3C00, 0x8000_0000]);
+        convert(&mut converter, [0x4060_3C00, 0x0000_0000]);
+        convert(&mut converter, [0x40A0_3C00, 0x8000_0000]);
+        convert(&mut converter, [0x4080_3C00, 0x8000_0000]);
+        assert_eq!(
+            convert(&mut converter, [0x4090_3E00, 0x8000_0000]),
+            std::vec![0x20E1_0040, 0x20D1_0000, 0x2091_3E40]
+        );
+    }
+
+    #[test]
+    fn channel_messages_go_to_the_manager_channel() {
+        let mut converter = Midi2ToMpe::new(Zone::Upper, 2);
+        assert_eq!(
+            convert(&mut converter, [0x40E3_0000, 0x8000_0000]),
+            std::vec![0x20EF_0040]
+        );
+    }
+
+    #[test]
+    fn unknown_notes_are_ignored() {
+        let mut converter = Midi2ToMpe::new(Zone::Lower, 2);
+        assert_eq!(
+            convert(&mut converter, [0x4060_3C00, 0x0000_0000]),
+            Vec::<u32>::new()
+        );
+    }
+}
@@ -0,0 +1,201 @@
+use crate::ux::u4;
+
+const LOWER_MANAGER_CHANNEL: u8 = 0;
+const UPPER_MANAGER_CHANNEL: u8 = 15;
+const MAX_MEMBER_CHANNELS: u8 = 15;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Zone {
+    Lower,
+    Upper,
+}
+
+impl Zone {
+    /// The channel which carries the zone wide messages.
+    pub fn manager_channel(&self) -> u4 {
+        match self {
+            Zone::Lower => u4::new(LOWER_MANAGER_CHANNEL),
+            Zone::Upper => u4::new(UPPER_MANAGER_CHANNEL),
+        }
+    }
+
+    pub(crate) fn index(&self) -> usize {
+        match self {
+            Zone::Lower => 0,
+            Zone::Upper => 1,
+        }
+    }
+}
+
+/// The part a channel plays in a zone.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ChannelRole {
+    Manager(Zone),
+    Member(Zone),
+}
+
+/// The MPE zone layout of the channels of a group.
+///
+/// The lower zone takes its member channels upwards from the second channel
+/// and the upper zone takes its member channels downwards from the fifteenth.
+/// Configuring a zone shrinks the other zone when the two would overlap.
+///
+/// ```rust
+/// use midi2::{mpe::{ChannelRole, Zone, Zones}, ux::u4};
+///
+/// let mut zones = Zones::new();
+/// zones.set_member_count(Zone::Upper, 12);
+/// zones.set_member_count(Zone::Lower, 5);
+///
+/// assert_eq!(zones.member_count(Zone::Upper), 9);
+/// assert_eq!(zones.role(u4::new(5)), Some(ChannelRole::Member(Zone::Lower)));
+/// assert_eq!(zones.role(u4::new(6)), Some(ChannelRole::Member(Zone::Upper)));
+/// assert_eq!(zones.role(u4::new(15)), Some(ChannelRole::Manager(Zone::Upper)));
+/// ```
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Zones {
+    lower: u8,
+    upper: u8,
+}
+
+impl Zones {
+    /// No zones are configured.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The number of member channels of the zone.
+    /// Zero when the zone isn't configured.
+    pub fn member_count(&self, zone: Zone) -> u8 {
+        match zone {
+            Zone::Lower => self.lower,
+            Zone::Upper => self.upper,
+        }
+    }
+
+    /// Configure the number of member channels of the zone.
+    ///
+    /// A count of zero disables the zone and counts above fifteen are clamped.
+    pub fn set_member_count(&mut self, zone: Zone, count: u8) {
+        let count = count.min(MAX_MEMBER_CHANNELS);
+        let available = (MAX_MEMBER_CHANNELS - 1).saturating_sub(count);
+        match zone {
+            Zone::Lower => {
+                self.lower = count;
+                self.upper = self.upper.min(available);
+            }
+            Zone::Upper => {
+                self.upper = count;
+                self.lower = self.lower.min(available);
+            }
+        }
+    }
+
+    /// Whether the zone has any member channels.
+    pub fn is_enabled(&self, zone: Zone) -> bool {
+        self.member_count(zone) != 0
+    }
+
+    /// The member channels of the zone, in the order they are allocated.
+    pub fn member_channels(&self, zone: Zone) -> impl Iterator<Item = u4> {
+        let count = self.member_count(zone);
+        (1..=count).map(move |i| match zone {
+            Zone::Lower => u4::new(LOWER_MANAGER_CHANNEL + i),
+            Zone::Upper => u4::new(UPPER_MANAGER_CHANNEL - i),
+        })
+    }
+
+    /// The role of the channel, or None when the channel is outside of the zones.
+    pub fn role(&self, channel: u4) -> Option<ChannelRole> {
+        let channel = u8::from(channel);
+        if self.lower != 0 {
+            if channel == LOWER_MANAGER_CHANNEL {
+                return Some(ChannelRole::Manager(Zone::Lower));
+            }
+            if channel <= LOWER_MANAGER_CHANNEL + self.lower {
+                return Some(ChannelRole::Member(Zone::Lower));
+            }
+        }
+        if self.upper != 0 {
+            if channel == UPPER_MANAGER_CHANNEL {
+                return Some(ChannelRole::Manager(Zone::Upper));
+            }
+            if channel >= UPPER_MANAGER_CHANNEL - self.upper {
+                return Some(ChannelRole::Member(Zone::Upper));
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn no_zones() {
+        let zones = Zones::new();
+        assert_eq!(zones.role(u4::new(0)), None);
+        assert_eq!(zones.role(u4::new(15)), None);
+        assert_eq!(zones.member_channels(Zone::Lower).count(), 0);
+    }
+
+    #[test]
+    fn lower_zone_members() {
+        let mut zones = Zones::new();
+        zones.set_member_count(Zone::Lower, 3);
+        assert_eq!(
+            zones
+                .member_channels(Zone::Lower)
+                .collect::<std::vec::Vec<_>>(),
+            std::vec![u4::new(1), u4::new(2), u4::new(3)]
+        );
+        assert_eq!(zones.role(u4::new(4)), None);
+    }
+
+    #[test]
+    fn upper_zone_members() {
+        let mut zones = Zones::new();
+        zones.set_member_count(Zone::Upper, 2);
+        assert_eq!(
+            zones
+                .member_channels(Zone::Upper)
+                .collect::<std::vec::Vec<_>>(),
+            std::vec![u4::new(14), u4::new(13)]
+        );
+        assert_eq!(zones.role(u4::new(12)), None);
+        assert_eq!(zones.role(u4::new(0)), None);
+    }
+
+    #[test]
+    fn whole_group_zone() {
+        let mut zones = Zones::new();
+        zones.set_member_count(Zone::Upper, 4);
+        zones.set_member_count(Zone::Lower, 0x7F);
+        assert_eq!(zones.member_count(Zone::Lower), 15);
+        assert_eq!(zones.member_count(Zone::Upper), 0);
+        assert_eq!(
+            zones.role(u4::new(15)),
+            Some(ChannelRole::Member(Zone::Lower))
+        );
+    }
+
+    #[test]
+    fn latest_zone_takes_priority() {
+        let mut zones = Zones::new();
+        zones.set_member_count(Zone::Lower, 10);
+        zones.set_member_count(Zone::Upper, 7);
+        assert_eq!(zones.member_count(Zone::Lower), 7);
+        assert_eq!(zones.member_count(Zone::Upper), 7);
+    }
+
+    #[test]
+    fn disable_zone() {
+        let mut zones = Zones::new();
+        zones.set_member_count(Zone::Lower, 10);
+        zones.set_member_count(Zone::Lower, 0);
+        assert!(!zones.is_enabled(Zone::Lower));
+        assert_eq!(zones.role(u4::new(0)), None);
+    }
+}
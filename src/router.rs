@@ -0,0 +1,553 @@
+//! Filtering and routing of [UmpMessage]s by group, channel and type.
+//!
+//! Decisions are made from the first packet of a message,
+//! so multi-packet messages such as sysex and flex data are always routed whole.
+//!
+//! ```rust
+//! use midi2::{prelude::*, router::*};
+//!
+//! let mut router = Router::<4>::new();
+//! // channel voice messages on group 0 go to output 0
+//! router.add_route(Filter {
+//!     groups: 0b0000_0000_0000_0001,
+//!     message_types: MessageType::ChannelVoice2.mask(),
+//!     ..Default::default()
+//! }, 0).unwrap();
+//! // everything on channel 9 goes to output 1
+//! router.add_route(Filter {
+//!     channels: 1 << 9,
+//!     ..Default::default()
+//! }, 1).unwrap();
+//!
+//! let message = UmpMessage::try_from(&[0x4099_3C00, 0xFFFF_0000][..]).unwrap();
+//! assert_eq!(router.route(&message).collect::<Vec<_>>(), vec![0, 1]);
+//! ```
+
+use crate::{buffer::Ump, detail::BitOps, error::InvalidData, traits::Data, ux::u4, UmpMessage};
+
+const MAX_OUTPUTS: u8 = 32;
+const ALL: u16 = 0xFFFF;
+
+/// The UMP message types, as encoded in the first nibble of every message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MessageType {
+    Utility,
+    SystemCommon,
+    ChannelVoice1,
+    Sysex7,
+    ChannelVoice2,
+    Sysex8,
+    FlexData,
+    UmpStream,
+}
+
+impl MessageType {
+    pub fn from_u8(v: u8) -> Option<Self> {
+        use MessageType::*;
+        match v {
+            0x0 => Some(Utility),
+            0x1 => Some(SystemCommon),
+            0x2 => Some(ChannelVoice1),
+            0x3 => Some(Sysex7),
+            0x4 => Some(ChannelVoice2),
+            0x5 => Some(Sysex8),
+            0xD => Some(FlexData),
+            0xF => Some(UmpStream),
+            _ => None,
+        }
+    }
+
+    pub fn to_u8(self) -> u8 {
+        use MessageType::*;
+        match self {
+            Utility => 0x0,
+            SystemCommon => 0x1,
+            ChannelVoice1 => 0x2,
+            Sysex7 => 0x3,
+            ChannelVoice2 => 0x4,
+            Sysex8 => 0x5,
+            FlexData => 0xD,
+            UmpStream => 0xF,
+        }
+    }
+
+    /// The bit representing this type in [Filter::message_types].
+    pub fn mask(self) -> u16 {
+        1 << self.to_u8()
+    }
+
+    /// Whether messages of this type carry a group.
+    pub fn has_group(self) -> bool {
+        !matches!(self, MessageType::Utility | MessageType::UmpStream)
+    }
+}
+
+/// The type of a message.
+pub fn message_type<B: Ump>(message: &UmpMessage<B>) -> u4 {
+    message.data()[0].nibble(0)
+}
+
+/// The group of a message, or None for the utility and UMP stream messages
+/// which apply to the whole endpoint.
+pub fn group<B: Ump>(message: &UmpMessage<B>) -> Option<u4> {
+    let first = message.data()[0];
+    match MessageType::from_u8(first.nibble(0).into()) {
+        Some(ty) if !ty.has_group() => None,
+        _ => Some(first.nibble(1)),
+    }
+}
+
+/// The channel of a message, or None for messages which aren't addressed to a channel.
+///
+/// Flex data messages addressed to the whole group have no channel.
+pub fn channel<B: Ump>(message: &UmpMessage<B>) -> Option<u4> {
+    let first = message.data()[0];
+    match MessageType::from_u8(first.nibble(0).into()) {
+        Some(MessageType::ChannelVoice1 | MessageType::ChannelVoice2) => Some(first.nibble(3)),
+        Some(MessageType::FlexData) if first.crumb(5) == crate::ux::u2::new(0x0) => {
+            Some(first.nibble(3))
+        }
+        _ => None,
+    }
+}
+
+/// The status of a message.
+///
+/// This is the status nibble of utility and channel voice messages,
+/// and the status byte of system common messages.
+/// Other messages have no status.
+pub fn status<B: Ump>(message: &UmpMessage<B>) -> Option<u8> {
+    let first = message.data()[0];
+    match MessageType::from_u8(first.nibble(0).into()) {
+        Some(MessageType::Utility | MessageType::ChannelVoice1 | MessageType::ChannelVoice2) => {
+            Some(first.nibble(2).into())
+        }
+        Some(MessageType::SystemCommon) => Some(first.octet(1)),
+        _ => None,
+    }
+}
+
+/// A rule selecting a subset of messages.
+///
+/// A message passes when it passes every criterion.
+/// The default filter passes everything.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Filter {
+    /// Bit `n` passes messages on group `n`.
+    pub groups: u16,
+    /// Bit `n` passes messages on channel `n`.
+    /// Messages without a channel are not affected.
+    pub channels: u16,
+    /// Bit `n` passes messages of UMP message type `n`. See [MessageType::mask].
+    pub message_types: u16,
+    /// When set, only messages with this [status] pass.
+    pub status: Option<u8>,
+    /// Whether messages which have no group pass.
+    pub groupless: bool,
+}
+
+impl core::default::Default for Filter {
+    fn default() -> Self {
+        Filter {
+            groups: ALL,
+            channels: ALL,
+            message_types: ALL,
+            status: None,
+            groupless: true,
+        }
+    }
+}
+
+impl Filter {
+    pub fn matches<B: Ump>(&self, message: &UmpMessage<B>) -> bool {
+        if self.message_types & (1 << u8::from(message_type(message))) == 0 {
+            return false;
+        }
+        match group(message) {
+            Some(group) if self.groups & (1 << u8::from(group)) == 0 => return false,
+            None if !self.groupless => return false,
+            _ => {}
+        }
+        if let Some(channel) = channel(message) {
+            if self.channels & (1 << u8::from(channel)) == 0 {
+                return false;
+            }
+        }
+        match self.status {
+            Some(expected) => status(message) == Some(expected),
+            None => true,
+        }
+    }
+}
+
+/// A mapping of groups and channels applied to messages with [Remappable](crate::Remappable).
+///
+/// The default mapping leaves every group and channel unchanged.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Remap {
+    groups: [u4; 16],
+    channels: [u4; 16],
+}
+
+impl core::default::Default for Remap {
+    fn default() -> Self {
+        let mut identity = [u4::new(0); 16];
+        for (i, v) in identity.iter_mut().enumerate() {
+            *v = u4::new(i as u8);
+        }
+        Remap {
+            groups: identity,
+            channels: identity,
+        }
+    }
+}
+
+impl Remap {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Move messages on the group `from` onto the group `to`.
+    pub fn set_group(&mut self, from: u4, to: u4) {
+        self.groups[u8::from(from) as usize] = to;
+    }
+
+    /// Move messages on the channel `from` onto the channel `to`, whatever their group.
+    pub fn set_channel(&mut self, from: u4, to: u4) {
+        self.channels[u8::from(from) as usize] = to;
+    }
+
+    pub fn group(&self, group: u4) -> u4 {
+        self.groups[u8::from(group) as usize]
+    }
+
+    pub fn channel(&self, channel: u4) -> u4 {
+        self.channels[u8::from(channel) as usize]
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Route {
+    filter: Filter,
+    output: u8,
+}
+
+/// Sends messages to numbered outputs according to a list of [Filter]s.
+///
+/// A message is sent to the output of every route whose filter it passes,
+/// and to each output at most once.
+/// The router holds up to `N` routes and supports outputs `0..32`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Router<const N: usize = 16> {
+    routes: [Option<Route>; N],
+}
+
+impl<const N: usize> core::default::Default for Router<N> {
+    fn default() -> Self {
+        Router { routes: [None; N] }
+    }
+}
+
+impl<const N: usize> Router<N> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a route sending the messages passing the filter to the output.
+    pub fn add_route(&mut self, filter: Filter, output: u8) -> Result<(), InvalidData> {
+        if output >= MAX_OUTPUTS {
+            return Err(InvalidData(ERR_INVALID_OUTPUT));
+        }
+        let Some(slot) = self.routes.iter_mut().find(|slot| slot.is_none()) else {
+            return Err(InvalidData(ERR_TOO_MANY_ROUTES));
+        };
+        *slot = Some(Route { filter, output });
+        Ok(())
+    }
+
+    /// Remove every route to the output.
+    pub fn remove_routes(&mut self, output: u8) {
+        for slot in self.routes.iter_mut() {
+            if matches!(slot, Some(route) if route.output == output) {
+                *slot = None;
+            }
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.routes = [None; N];
+    }
+
+    /// The outputs the message should be sent to, in ascending order.
+    pub fn route<B: Ump>(&self, message: &UmpMessage<B>) -> Outputs {
+        let mut mask = 0;
+        for route in self.routes.iter().flatten() {
+            if route.filter.matches(message) {
+                mask |= 1 << route.output;
+            }
+        }
+        Outputs(mask)
+    }
+}
+
+/// The outputs selected by [Router::route].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Outputs(u32);
+
+impl Outputs {
+    pub fn contains(&self, output: u8) -> bool {
+        output < MAX_OUTPUTS && self.0 & (1 << output) != 0
+    }
+}
+
+impl core::iter::Iterator for Outputs {
+    type Item = u8;
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.0 == 0 {
+            return None;
+        }
+        let output = self.0.trailing_zeros() as u8;
+        self.0 &= self.0 - 1;
+        Some(output)
+    }
+}
+
+const ERR_INVALID_OUTPUT: &str = "Router outputs must be less than 32";
+const ERR_TOO_MANY_ROUTES: &str = "Router has no space for more routes";
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::RebufferInto;
+    use pretty_assertions::assert_eq;
+
+    fn message(data: &[u32]) -> UmpMessage<&[u32]> {
+        UmpMessage::try_from(data).unwrap()
+    }
+
+    #[test]
+    fn default_filter_passes_everything() {
+        assert!(Filter::default().matches(&message(&[0x4099_3C00, 0xFFFF_0000])));
+    }
+
+    #[test]
+    fn group_filter() {
+        let filter = Filter {
+            groups: 0b0000_0000_0000_0100,
+            ..Default::default()
+        };
+        assert!(filter.matches(&message(&[0x4299_3C00, 0xFFFF_0000])));
+        assert!(!filter.matches(&message(&[0x4399_3C00, 0xFFFF_0000])));
+    }
+
+    #[test]
+    fn channel_filter() {
+        let filter = Filter {
+            channels: 0b0000_0000_0000_0001,
+            ..Default::default()
+        };
+        assert!(filter.matches(&message(&[0x4090_3C00, 0xFFFF_0000])));
+        assert!(!filter.matches(&message(&[0x4091_3C00, 0xFFFF_0000])));
+    }
+
+    #[test]
+    fn status_filter() {
+        let filter = Filter {
+            status: Some(0x9),
+            ..Default::default()
+        };
+        assert!(filter.matches(&message(&[0x4090_3C00, 0xFFFF_0000])));
+        assert!(!filter.matches(&message(&[0x4080_3C00, 0xFFFF_0000])));
+    }
+
+    #[test]
+    fn message_type_filter() {
+        let filter = Filter {
+            message_types: MessageType::ChannelVoice1.mask(),
+            ..Default::default()
+        };
+        assert!(!filter.matches(&message(&[0x4090_3C00, 0xFFFF_0000])));
+    }
+
+    #[cfg(feature = "utility")]
+    #[test]
+    fn groupless_messages() {
+        let filter = Filter {
+            groups: 0b0000_0000_0000_0001,
+            groupless: false,
+            ..Default::default()
+        };
+        let no_op = message(&[0x0000_0000]);
+        assert_eq!(group(&no_op), None);
+        assert!(!filter.matches(&no_op));
+        assert!(Filter {
+            groupless: true,
+            ..filter
+        }
+        .matches(&no_op));
+    }
+
+    #[cfg(feature = "flex-data")]
+    #[test]
+    fn flex_data_channel() {
+        // set tempo is addressed to the whole group
+        let set_tempo = message(&[0xD710_0000, 0xF751_FE05, 0x0, 0x0]);
+        assert_eq!(channel(&set_tempo), None);
+        assert!(Filter {
+            channels: 0,
+            ..Default::default()
+        }
+        .matches(&set_tempo));
+    }
+
+    #[cfg(feature = "sysex7")]
+    #[test]
+    fn multi_packet_sysex_is_routed_whole() {
+        let mut router = Router::<2>::new();
+        router
+            .add_route(
+                Filter {
+                    groups: 0b0000_0000_0000_0010,
+                    ..Default::default()
+                },
+                3,
+            )
+            .unwrap();
+        let sysex = message(&[0x3116_0001, 0x0203_0405, 0x3131_0607, 0x0000_0000]);
+        assert_eq!(sysex.data().len(), 4);
+        assert_eq!(
+            router.route(&sysex).collect::<std::vec::Vec<_>>(),
+            std::vec![3]
+        );
+    }
+
+    #[test]
+    fn outputs_are_deduplicated() {
+        let mut router = Router::<4>::new();
+        router.add_route(Filter::default(), 2).unwrap();
+        router
+            .add_route(
+                Filter {
+                    channels: 0b0000_0000_0000_0001,
+                    ..Default::default()
+                },
+                2,
+            )
+            .unwrap();
+        router.add_route(Filter::default(), 0).unwrap();
+        assert_eq!(
+            router
+                .route(&message(&[0x4090_3C00, 0xFFFF_0000]))
+                .collect::<std::vec::Vec<_>>(),
+            std::vec![0, 2]
+        );
+    }
+
+    #[test]
+    fn remove_routes() {
+        let mut router = Router::<4>::new();
+        router.add_route(Filter::default(), 1).unwrap();
+        router.remove_routes(1);
+        assert_eq!(
+            router.route(&message(&[0x4090_3C00, 0xFFFF_0000])).count(),
+            0
+        );
+    }
+
+    #[test]
+    fn invalid_output() {
+        assert_eq!(
+            Router::<1>::new().add_route(Filter::default(), 32),
+            Err(InvalidData(ERR_INVALID_OUTPUT))
+        );
+    }
+
+    #[test]
+    fn too_many_routes() {
+        let mut router = Router::<1>::new();
+        router.add_route(Filter::default(), 0).unwrap();
+        assert_eq!(
+            router.add_route(Filter::default(), 1),
+            Err(InvalidData(ERR_TOO_MANY_ROUTES))
+        );
+    }
+
+    fn remap() -> Remap {
+        let mut remap = Remap::new();
+        remap.set_group(u4::new(0x0), u4::new(0x3));
+        remap.set_channel(u4::new(0x2), u4::new(0x7));
+        remap
+    }
+
+    #[test]
+    fn default_remap_is_identity() {
+        let remap = Remap::default();
+        assert_eq!(remap.group(u4::new(0x5)), u4::new(0x5));
+        assert_eq!(remap.channel(u4::new(0xF)), u4::new(0xF));
+    }
+
+    #[test]
+    fn remap_ump_message() {
+        use crate::Remappable;
+
+        let mut message: UmpMessage<std::vec::Vec<u32>> =
+            message(&[0x4092_3C00, 0xFFFF_0000]).rebuffer_into();
+        message.remap(&remap());
+        assert_eq!(message.data(), &[0x4397_3C00, 0xFFFF_0000]);
+    }
+
+    #[test]
+    fn remap_unmapped_group_and_channel() {
+        use crate::{Channeled, Grouped, Remappable};
+
+        let mut message = crate::channel_voice2::NoteOn::<[u32; 4]>::new();
+        message.set_group(u4::new(0x1));
+        message.set_channel(u4::new(0x1));
+        message.remap(&remap());
+        assert_eq!(message.data(), &[0x4191_0000, 0x0]);
+    }
+
+    #[cfg(feature = "sysex7")]
+    #[test]
+    fn remap_every_sysex_packet() {
+        use crate::Remappable;
+
+        let mut message: crate::sysex7::Sysex7<std::vec::Vec<u32>> =
+            crate::sysex7::Sysex7::try_from(
+                &[0x3016_0001, 0x0203_0405, 0x3035_0607, 0x0809_0A00][..],
+            )
+            .unwrap()
+            .rebuffer_into();
+        message.remap(&remap());
+        assert_eq!(
+            message.data(),
+            &[0x3316_0001, 0x0203_0405, 0x3335_0607, 0x0809_0A00]
+        );
+    }
+
+    #[cfg(feature = "flex-data")]
+    #[test]
+    fn remap_every_flex_data_packet() {
+        use crate::Remappable;
+
+        let mut message = crate::flex_data::ProjectName::<std::vec::Vec<u32>>::new();
+        message.set_optional_channel(Some(u4::new(0x2)));
+        message.set_text("A project name which spans packets");
+        message.remap(&remap());
+        let data = message.data();
+        assert_eq!(data.len(), 12);
+        for packet in data.chunks_exact(4) {
+            assert_eq!(packet[0] & 0x0F3F_0000, 0x0307_0000);
+        }
+    }
+
+    #[cfg(feature = "utility")]
+    #[test]
+    fn remap_leaves_groupless_messages() {
+        use crate::Remappable;
+
+        let mut message: UmpMessage<std::vec::Vec<u32>> = message(&[0x0010_1234]).rebuffer_into();
+        message.remap(&remap());
+        assert_eq!(message.data(), &[0x0010_1234]);
+    }
+}
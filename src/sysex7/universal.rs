@@ -0,0 +1,379 @@
+//! Universal System Exclusive messages.
+//!
+//! Universal sysex payloads start with a header of four bytes:
+//! `0x7E` for non-real time or `0x7F` for real time messages,
+//! the target device id, and two sub-ids which identify the message.
+//!
+//! Messages are read from any [Sysex7](crate::sysex7::Sysex7),
+//! backed by either bytes or UMP.
+//! Each message type provides its payload, to be written with
+//! [set_payload](crate::Sysex::set_payload) or
+//! [try_set_payload](crate::Sysex::try_set_payload).
+//!
+//! ```rust
+//! use midi2::{prelude::*, sysex7::universal::{IdentityRequest, UniversalMessage}};
+//!
+//! let mut message = sysex7::Sysex7::<Vec<u8>>::new();
+//! message.set_payload(IdentityRequest { device_id: u7::new(0x7F) }.payload());
+//! assert_eq!(message.data(), &[0xF0, 0x7E, 0x7F, 0x06, 0x01, 0xF7]);
+//!
+//! let mut message = sysex7::Sysex7::<Vec<u32>>::new();
+//! message.set_payload(IdentityRequest { device_id: u7::new(0x7F) }.payload());
+//! assert_eq!(message.data(), &[0x3004_7E7F, 0x0601_0000]);
+//!
+//! assert_eq!(
+//!     UniversalMessage::try_from(&message),
+//!     Ok(UniversalMessage::IdentityRequest(IdentityRequest { device_id: u7::new(0x7F) })),
+//! );
+//! ```
+
+use crate::{error::InvalidData, sysex7::Sysex7, traits::Sysex, ux::u7};
+
+mod device_control;
+mod identity;
+#[cfg(feature = "system-common")]
+mod mmc;
+mod tuning;
+
+pub use device_control::*;
+pub use identity::*;
+#[cfg(feature = "system-common")]
+pub use mmc::*;
+pub use tuning::*;
+
+const NON_REAL_TIME: u8 = 0x7E;
+const REAL_TIME: u8 = 0x7F;
+
+/// The device id which addresses every device.
+pub const ALL_CALL: u8 = 0x7F;
+
+const ERR_NOT_UNIVERSAL: &str = "Expected universal sysex id 0x7E or 0x7F";
+const ERR_PAYLOAD_TOO_SHORT: &str = "Universal sysex payload is too short";
+const ERR_UNEXPECTED_MESSAGE: &str = "Unexpected universal sysex sub-ids";
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Category {
+    NonRealTime,
+    RealTime,
+}
+
+/// The routing information at the start of every universal sysex payload.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Header {
+    pub category: Category,
+    pub device_id: u7,
+    pub sub_id1: u7,
+    pub sub_id2: u7,
+}
+
+impl Header {
+    pub fn payload(&self) -> [u7; 4] {
+        [
+            u7::new(match self.category {
+                Category::NonRealTime => NON_REAL_TIME,
+                Category::RealTime => REAL_TIME,
+            }),
+            self.device_id,
+            self.sub_id1,
+            self.sub_id2,
+        ]
+    }
+
+    /// Whether a device with the given id should respond to the message:
+    /// either the message targets the id, or it is sent to [ALL_CALL].
+    pub fn is_addressed_to(&self, device_id: u7) -> bool {
+        self.device_id == device_id || u8::from(self.device_id) == ALL_CALL
+    }
+
+    fn read<I: Iterator<Item = u7>>(payload: &mut I) -> Result<Self, InvalidData> {
+        let category = match u8::from(next(payload)?) {
+            NON_REAL_TIME => Category::NonRealTime,
+            REAL_TIME => Category::RealTime,
+            _ => return Err(InvalidData(ERR_NOT_UNIVERSAL)),
+        };
+        Ok(Header {
+            category,
+            device_id: next(payload)?,
+            sub_id1: next(payload)?,
+            sub_id2: next(payload)?,
+        })
+    }
+
+    fn sub_ids(&self) -> (Category, u8, u8) {
+        (
+            self.category,
+            u8::from(self.sub_id1),
+            u8::from(self.sub_id2),
+        )
+    }
+}
+
+impl<'a, B: crate::buffer::Buffer> TryFrom<&'a Sysex7<B>> for Header {
+    type Error = InvalidData;
+    fn try_from(message: &'a Sysex7<B>) -> Result<Self, Self::Error> {
+        Header::read(&mut message.payload())
+    }
+}
+
+/// The universal sysex messages, routed by their sub-ids.
+///
+/// Messages which aren't covered are read as [Unknown](UniversalMessage::Unknown),
+/// keeping the header.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum UniversalMessage {
+    IdentityRequest(IdentityRequest),
+    IdentityReply(IdentityReply),
+    /// MIDI Machine Control commands addressed to the device id,
+    /// read with [MmcCommands].
+    #[cfg(feature = "system-common")]
+    MmcCommands(u7),
+    /// MIDI Machine Control responses from the device id,
+    /// read with [MmcResponses].
+    #[cfg(feature = "system-common")]
+    MmcResponses(u7),
+    BulkTuningDumpRequest(BulkTuningDumpRequest),
+    BulkTuningDump(BulkTuningDump),
+    SingleNoteTuningChange(SingleNoteTuningChange),
+    ScaleOctaveTuning1Byte(ScaleOctaveTuning1Byte),
+    ScaleOctaveTuning2Byte(ScaleOctaveTuning2Byte),
+    GeneralMidiSystem(GeneralMidiSystem),
+    MasterVolume(MasterVolume),
+    MasterBalance(MasterBalance),
+    MasterFineTuning(MasterFineTuning),
+    MasterCoarseTuning(MasterCoarseTuning),
+    GlobalParameterControl(GlobalParameterControl),
+    Unknown(Header),
+}
+
+impl<'a, B: crate::buffer::Buffer> TryFrom<&'a Sysex7<B>> for UniversalMessage {
+    type Error = InvalidData;
+    fn try_from(message: &'a Sysex7<B>) -> Result<Self, Self::Error> {
+        let mut payload = message.payload();
+        let header = Header::read(&mut payload)?;
+        Ok(match header.sub_ids() {
+            (Category::NonRealTime, GENERAL_INFORMATION, IDENTITY_REQUEST) => {
+                UniversalMessage::IdentityRequest(IdentityRequest {
+                    device_id: header.device_id,
+                })
+            }
+            (Category::NonRealTime, GENERAL_INFORMATION, IDENTITY_REPLY) => {
+                UniversalMessage::IdentityReply(IdentityReply::read(&header, &mut payload)?)
+            }
+            #[cfg(feature = "system-common")]
+            (Category::RealTime, MMC_COMMAND, _) => UniversalMessage::MmcCommands(header.device_id),
+            #[cfg(feature = "system-common")]
+            (Category::RealTime, MMC_RESPONSE, _) => {
+                UniversalMessage::MmcResponses(header.device_id)
+            }
+            (
+                Category::NonRealTime,
+                MIDI_TUNING_STANDARD,
+                BULK_TUNING_DUMP_REQUEST | BULK_TUNING_DUMP_REQUEST_BANK,
+            ) => UniversalMessage::BulkTuningDumpRequest(BulkTuningDumpRequest::read(
+                &header,
+                &mut payload,
+            )?),
+            (
+                Category::NonRealTime,
+                MIDI_TUNING_STANDARD,
+                BULK_TUNING_DUMP | BULK_TUNING_DUMP_BANK,
+            ) => UniversalMessage::BulkTuningDump(BulkTuningDump::read(&header, &mut payload)?),
+            (Category::RealTime, MIDI_TUNING_STANDARD, SINGLE_NOTE_TUNING_CHANGE)
+            | (_, MIDI_TUNING_STANDARD, SINGLE_NOTE_TUNING_CHANGE_BANK) => {
+                UniversalMessage::SingleNoteTuningChange(SingleNoteTuningChange::read(
+                    &header,
+                    &mut payload,
+                )?)
+            }
+            (_, MIDI_TUNING_STANDARD, SCALE_OCTAVE_TUNING_1_BYTE) => {
+                UniversalMessage::ScaleOctaveTuning1Byte(ScaleOctaveTuning1Byte::read(
+                    &header,
+                    &mut payload,
+                )?)
+            }
+            (_, MIDI_TUNING_STANDARD, SCALE_OCTAVE_TUNING_2_BYTE) => {
+                UniversalMessage::ScaleOctaveTuning2Byte(ScaleOctaveTuning2Byte::read(
+                    &header,
+                    &mut payload,
+                )?)
+            }
+            (
+                Category::NonRealTime,
+                GENERAL_MIDI,
+                sub_id2 @ GENERAL_MIDI_1_ON..=GENERAL_MIDI_2_ON,
+            ) => UniversalMessage::GeneralMidiSystem(GeneralMidiSystem::read(
+                &header,
+                match sub_id2 {
+                    GENERAL_MIDI_1_ON => GeneralMidiMode::GeneralMidi1On,
+                    GENERAL_MIDI_OFF => GeneralMidiMode::GeneralMidiOff,
+                    _ => GeneralMidiMode::GeneralMidi2On,
+                },
+            )),
+            (Category::RealTime, DEVICE_CONTROL, MASTER_VOLUME) => {
+                UniversalMessage::MasterVolume(MasterVolume::read(&header, &mut payload)?)
+            }
+            (Category::RealTime, DEVICE_CONTROL, MASTER_BALANCE) => {
+                UniversalMessage::MasterBalance(MasterBalance::read(&header, &mut payload)?)
+            }
+            (Category::RealTime, DEVICE_CONTROL, MASTER_FINE_TUNING) => {
+                UniversalMessage::MasterFineTuning(MasterFineTuning::read(&header, &mut payload)?)
+            }
+            (Category::RealTime, DEVICE_CONTROL, MASTER_COARSE_TUNING) => {
+                UniversalMessage::MasterCoarseTuning(MasterCoarseTuning::read(
+                    &header,
+                    &mut payload,
+                )?)
+            }
+            (Category::RealTime, DEVICE_CONTROL, GLOBAL_PARAMETER_CONTROL) => {
+                match GlobalParameterControl::read(&header, &mut payload)? {
+                    Some(control) => UniversalMessage::GlobalParameterControl(control),
+                    None => UniversalMessage::Unknown(header),
+                }
+            }
+            _ => UniversalMessage::Unknown(header),
+        })
+    }
+}
+
+fn next<I: Iterator<Item = u7>>(payload: &mut I) -> Result<u7, InvalidData> {
+    payload.next().ok_or(InvalidData(ERR_PAYLOAD_TOO_SHORT))
+}
+
+/// Payload bytes of a universal sysex message, held in a fixed size array.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Payload<const SIZE: usize> {
+    data: [u7; SIZE],
+    len: usize,
+    index: usize,
+}
+
+impl<const SIZE: usize> Default for Payload<SIZE> {
+    fn default() -> Self {
+        Self {
+            data: [u7::new(0x0); SIZE],
+            len: 0,
+            index: 0,
+        }
+    }
+}
+
+impl<const SIZE: usize> Payload<SIZE> {
+    fn push(&mut self, byte: u7) {
+        self.data[self.len] = byte;
+        self.len += 1;
+    }
+
+    fn extend<I: IntoIterator<Item = u7>>(&mut self, bytes: I) {
+        for byte in bytes {
+            self.push(byte);
+        }
+    }
+}
+
+impl<const SIZE: usize> core::iter::Iterator for Payload<SIZE> {
+    type Item = u7;
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.index == self.len {
+            return None;
+        }
+        self.index += 1;
+        Some(self.data[self.index - 1])
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.len - self.index;
+        (remaining, Some(remaining))
+    }
+}
+
+impl<const SIZE: usize> core::iter::FusedIterator for Payload<SIZE> {}
+
+impl<const SIZE: usize> core::iter::ExactSizeIterator for Payload<SIZE> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn header_from_bytes() {
+        let message = Sysex7::try_from(&[0xF0_u8, 0x7F, 0x10, 0x06, 0x02, 0xF7][..]).unwrap();
+        assert_eq!(
+            Header::try_from(&message),
+            Ok(Header {
+                category: Category::RealTime,
+                device_id: u7::new(0x10),
+                sub_id1: u7::new(0x06),
+                sub_id2: u7::new(0x02),
+            })
+        );
+    }
+
+    #[test]
+    fn not_universal() {
+        let message = Sysex7::try_from(&[0xF0_u8, 0x43, 0x10, 0x06, 0x02, 0xF7][..]).unwrap();
+        assert_eq!(
+            UniversalMessage::try_from(&message),
+            Err(InvalidData(ERR_NOT_UNIVERSAL))
+        );
+    }
+
+    #[test]
+    fn short_header() {
+        let message = Sysex7::try_from(&[0xF0_u8, 0x7E, 0x10, 0xF7][..]).unwrap();
+        assert_eq!(
+            UniversalMessage::try_from(&message),
+            Err(InvalidData(ERR_PAYLOAD_TOO_SHORT))
+        );
+    }
+
+    #[test]
+    fn unknown_message() {
+        let message =
+            Sysex7::try_from(&[0xF0_u8, 0x7F, 0x7F, 0x04, 0x7F, 0x00, 0x40, 0xF7][..]).unwrap();
+        assert_eq!(
+            UniversalMessage::try_from(&message),
+            Ok(UniversalMessage::Unknown(Header {
+                category: Category::RealTime,
+                device_id: u7::new(0x7F),
+                sub_id1: u7::new(0x04),
+                sub_id2: u7::new(0x7F),
+            }))
+        );
+    }
+
+    #[test]
+    fn addressed_to_device() {
+        let header = Header {
+            category: Category::RealTime,
+            device_id: u7::new(0x10),
+            sub_id1: u7::new(0x04),
+            sub_id2: u7::new(0x01),
+        };
+        assert!(header.is_addressed_to(u7::new(0x10)));
+        assert!(!header.is_addressed_to(u7::new(0x11)));
+    }
+
+    #[test]
+    fn all_call_addresses_every_device() {
+        let header = Header {
+            category: Category::NonRealTime,
+            device_id: u7::new(ALL_CALL),
+            sub_id1: u7::new(0x09),
+            sub_id2: u7::new(0x01),
+        };
+        assert!(header.is_addressed_to(u7::new(0x00)));
+        assert!(header.is_addressed_to(u7::new(0x10)));
+    }
+
+    #[cfg(feature = "system-common")]
+    #[test]
+    fn mmc_commands() {
+        let message = Sysex7::try_from(&[0xF0_u8, 0x7F, 0x10, 0x06, 0x02, 0xF7][..]).unwrap();
+        assert_eq!(
+            UniversalMessage::try_from(&message),
+            Ok(UniversalMessage::MmcCommands(u7::new(0x10)))
+        );
+    }
+}
@@ -0,0 +1,719 @@
+use crate::{
+    error::InvalidData,
+    sysex7::{
+        universal::{
+            identity::{read_u14, u14_lsb_first},
+            next, Category, Header, Payload, UniversalMessage, ERR_UNEXPECTED_MESSAGE,
+        },
+        Sysex7,
+    },
+    ux::{u14, u7},
+};
+
+pub(crate) const GENERAL_MIDI: u8 = 0x09;
+pub(crate) const GENERAL_MIDI_1_ON: u8 = 0x01;
+pub(crate) const GENERAL_MIDI_OFF: u8 = 0x02;
+pub(crate) const GENERAL_MIDI_2_ON: u8 = 0x03;
+
+pub(crate) const DEVICE_CONTROL: u8 = 0x04;
+pub(crate) const MASTER_VOLUME: u8 = 0x01;
+pub(crate) const MASTER_BALANCE: u8 = 0x02;
+pub(crate) const MASTER_FINE_TUNING: u8 = 0x03;
+pub(crate) const MASTER_COARSE_TUNING: u8 = 0x04;
+pub(crate) const GLOBAL_PARAMETER_CONTROL: u8 = 0x05;
+
+// General MIDI 2 effects sit at slot path 01 0x
+const EFFECT_SLOT_MSB: u8 = 0x01;
+const REVERB_SLOT: u8 = 0x01;
+const CHORUS_SLOT: u8 = 0x02;
+
+const COARSE_TUNING_CENTRE: i8 = 0x40;
+const FINE_TUNING_CENTRE: u16 = 0x2000;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum GeneralMidiMode {
+    GeneralMidi1On,
+    GeneralMidiOff,
+    GeneralMidi2On,
+}
+
+/// General MIDI System On / Off.
+///
+/// Resets the addressed device into, or out of, General MIDI 1 or 2 mode.
+///
+/// ```rust
+/// use midi2::{prelude::*, sysex7::universal::{GeneralMidiMode, GeneralMidiSystem, ALL_CALL}};
+///
+/// let reset = GeneralMidiSystem {
+///     device_id: u7::new(ALL_CALL),
+///     mode: GeneralMidiMode::GeneralMidi2On,
+/// };
+///
+/// let mut message = sysex7::Sysex7::<Vec<u8>>::new();
+/// message.set_payload(reset.payload());
+/// assert_eq!(message.data(), &[0xF0, 0x7E, 0x7F, 0x09, 0x03, 0xF7]);
+/// assert_eq!(GeneralMidiSystem::try_from(&message), Ok(reset));
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct GeneralMidiSystem {
+    pub device_id: u7,
+    pub mode: GeneralMidiMode,
+}
+
+impl GeneralMidiSystem {
+    pub fn header(&self) -> Header {
+        Header {
+            category: Category::NonRealTime,
+            device_id: self.device_id,
+            sub_id1: u7::new(GENERAL_MIDI),
+            sub_id2: u7::new(match self.mode {
+                GeneralMidiMode::GeneralMidi1On => GENERAL_MIDI_1_ON,
+                GeneralMidiMode::GeneralMidiOff => GENERAL_MIDI_OFF,
+                GeneralMidiMode::GeneralMidi2On => GENERAL_MIDI_2_ON,
+            }),
+        }
+    }
+
+    pub fn payload(&self) -> Payload<4> {
+        let mut payload = Payload::default();
+        payload.extend(self.header().payload());
+        payload
+    }
+
+    pub(crate) fn read(header: &Header, mode: GeneralMidiMode) -> Self {
+        GeneralMidiSystem {
+            device_id: header.device_id,
+            mode,
+        }
+    }
+}
+
+impl<'a, B: crate::buffer::Buffer> TryFrom<&'a Sysex7<B>> for GeneralMidiSystem {
+    type Error = InvalidData;
+    fn try_from(message: &'a Sysex7<B>) -> Result<Self, Self::Error> {
+        match UniversalMessage::try_from(message)? {
+            UniversalMessage::GeneralMidiSystem(system) => Ok(system),
+            _ => Err(InvalidData(ERR_UNEXPECTED_MESSAGE)),
+        }
+    }
+}
+
+fn device_control_header(device_id: u7, sub_id2: u8) -> Header {
+    Header {
+        category: Category::RealTime,
+        device_id,
+        sub_id1: u7::new(DEVICE_CONTROL),
+        sub_id2: u7::new(sub_id2),
+    }
+}
+
+/// Device Control Master Volume.
+///
+/// The volume is a 14 bit value, sent least significant byte first.
+///
+/// ```rust
+/// use midi2::{prelude::*, sysex7::universal::MasterVolume};
+///
+/// let volume = MasterVolume {
+///     device_id: u7::new(0x10),
+///     volume: u14::new(0x3FFF),
+/// };
+///
+/// let mut message = sysex7::Sysex7::<Vec<u8>>::new();
+/// message.set_payload(volume.payload());
+/// assert_eq!(message.data(), &[0xF0, 0x7F, 0x10, 0x04, 0x01, 0x7F, 0x7F, 0xF7]);
+/// assert_eq!(MasterVolume::try_from(&message), Ok(volume));
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct MasterVolume {
+    pub device_id: u7,
+    pub volume: u14,
+}
+
+impl MasterVolume {
+    pub fn header(&self) -> Header {
+        device_control_header(self.device_id, MASTER_VOLUME)
+    }
+
+    pub fn payload(&self) -> Payload<6> {
+        let mut payload = Payload::default();
+        payload.extend(self.header().payload());
+        payload.extend(u14_lsb_first(self.volume));
+        payload
+    }
+
+    pub(crate) fn read<I: Iterator<Item = u7>>(
+        header: &Header,
+        payload: &mut I,
+    ) -> Result<Self, InvalidData> {
+        Ok(MasterVolume {
+            device_id: header.device_id,
+            volume: read_u14(payload)?,
+        })
+    }
+}
+
+impl<'a, B: crate::buffer::Buffer> TryFrom<&'a Sysex7<B>> for MasterVolume {
+    type Error = InvalidData;
+    fn try_from(message: &'a Sysex7<B>) -> Result<Self, Self::Error> {
+        match UniversalMessage::try_from(message)? {
+            UniversalMessage::MasterVolume(volume) => Ok(volume),
+            _ => Err(InvalidData(ERR_UNEXPECTED_MESSAGE)),
+        }
+    }
+}
+
+/// Device Control Master Balance.
+///
+/// The balance is a 14 bit value, sent least significant byte first,
+/// with `0x0` hard left, `0x2000` centre and `0x3FFF` hard right.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct MasterBalance {
+    pub device_id: u7,
+    pub balance: u14,
+}
+
+impl MasterBalance {
+    pub fn header(&self) -> Header {
+        device_control_header(self.device_id, MASTER_BALANCE)
+    }
+
+    pub fn payload(&self) -> Payload<6> {
+        let mut payload = Payload::default();
+        payload.extend(self.header().payload());
+        payload.extend(u14_lsb_first(self.balance));
+        payload
+    }
+
+    pub(crate) fn read<I: Iterator<Item = u7>>(
+        header: &Header,
+        payload: &mut I,
+    ) -> Result<Self, InvalidData> {
+        Ok(MasterBalance {
+            device_id: header.device_id,
+            balance: read_u14(payload)?,
+        })
+    }
+}
+
+impl<'a, B: crate::buffer::Buffer> TryFrom<&'a Sysex7<B>> for MasterBalance {
+    type Error = InvalidData;
+    fn try_from(message: &'a Sysex7<B>) -> Result<Self, Self::Error> {
+        match UniversalMessage::try_from(message)? {
+            UniversalMessage::MasterBalance(balance) => Ok(balance),
+            _ => Err(InvalidData(ERR_UNEXPECTED_MESSAGE)),
+        }
+    }
+}
+
+/// Device Control Master Fine Tuning.
+///
+/// The tuning is a 14 bit value, sent least significant byte first,
+/// spanning -100 to +100 cents with `0x2000` at A440.
+///
+/// ```rust
+/// use midi2::{prelude::*, sysex7::universal::MasterFineTuning};
+///
+/// let tuning = MasterFineTuning::from_cents(u7::new(0x7F), -50.0);
+/// assert_eq!(tuning.tuning, u14::new(0x1000));
+/// assert_eq!(tuning.cents(), -50.0);
+///
+/// let mut message = sysex7::Sysex7::<Vec<u8>>::new();
+/// message.set_payload(tuning.payload());
+/// assert_eq!(message.data(), &[0xF0, 0x7F, 0x7F, 0x04, 0x03, 0x00, 0x20, 0xF7]);
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct MasterFineTuning {
+    pub device_id: u7,
+    pub tuning: u14,
+}
+
+impl MasterFineTuning {
+    /// Cents are clamped to the range of the message.
+    pub fn from_cents(device_id: u7, cents: f32) -> Self {
+        let step = 100.0 / FINE_TUNING_CENTRE as f32;
+        let value = (cents / step + FINE_TUNING_CENTRE as f32 + 0.5).clamp(0.0, 0x3FFF as f32);
+        MasterFineTuning {
+            device_id,
+            tuning: u14::new(value as u16),
+        }
+    }
+
+    pub fn cents(&self) -> f32 {
+        (u16::from(self.tuning) as f32 - FINE_TUNING_CENTRE as f32) * 100.0
+            / FINE_TUNING_CENTRE as f32
+    }
+
+    pub fn header(&self) -> Header {
+        device_control_header(self.device_id, MASTER_FINE_TUNING)
+    }
+
+    pub fn payload(&self) -> Payload<6> {
+        let mut payload = Payload::default();
+        payload.extend(self.header().payload());
+        payload.extend(u14_lsb_first(self.tuning));
+        payload
+    }
+
+    pub(crate) fn read<I: Iterator<Item = u7>>(
+        header: &Header,
+        payload: &mut I,
+    ) -> Result<Self, InvalidData> {
+        Ok(MasterFineTuning {
+            device_id: header.device_id,
+            tuning: read_u14(payload)?,
+        })
+    }
+}
+
+impl<'a, B: crate::buffer::Buffer> TryFrom<&'a Sysex7<B>> for MasterFineTuning {
+    type Error = InvalidData;
+    fn try_from(message: &'a Sysex7<B>) -> Result<Self, Self::Error> {
+        match UniversalMessage::try_from(message)? {
+            UniversalMessage::MasterFineTuning(tuning) => Ok(tuning),
+            _ => Err(InvalidData(ERR_UNEXPECTED_MESSAGE)),
+        }
+    }
+}
+
+/// Device Control Master Coarse Tuning.
+///
+/// Only the most significant byte carries the tuning, in semitones from A440.
+/// Values outside of -64 to +63 are clamped when written.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct MasterCoarseTuning {
+    pub device_id: u7,
+    pub semitones: i8,
+}
+
+impl MasterCoarseTuning {
+    pub fn header(&self) -> Header {
+        device_control_header(self.device_id, MASTER_COARSE_TUNING)
+    }
+
+    pub fn payload(&self) -> Payload<6> {
+        let mut payload = Payload::default();
+        payload.extend(self.header().payload());
+        payload.push(u7::new(0x0));
+        payload.push(u7::new(
+            (self
+                .semitones
+                .clamp(-COARSE_TUNING_CENTRE, COARSE_TUNING_CENTRE - 1)
+                + COARSE_TUNING_CENTRE) as u8,
+        ));
+        payload
+    }
+
+    pub(crate) fn read<I: Iterator<Item = u7>>(
+        header: &Header,
+        payload: &mut I,
+    ) -> Result<Self, InvalidData> {
+        next(payload)?;
+        let msb = u8::from(next(payload)?) as i8;
+        Ok(MasterCoarseTuning {
+            device_id: header.device_id,
+            semitones: msb - COARSE_TUNING_CENTRE,
+        })
+    }
+}
+
+impl<'a, B: crate::buffer::Buffer> TryFrom<&'a Sysex7<B>> for MasterCoarseTuning {
+    type Error = InvalidData;
+    fn try_from(message: &'a Sysex7<B>) -> Result<Self, Self::Error> {
+        match UniversalMessage::try_from(message)? {
+            UniversalMessage::MasterCoarseTuning(tuning) => Ok(tuning),
+            _ => Err(InvalidData(ERR_UNEXPECTED_MESSAGE)),
+        }
+    }
+}
+
+/// The General MIDI 2 reverb parameters.
+///
+/// Parameters left as `None` aren't sent.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct ReverbParameters {
+    pub reverb_type: Option<u7>,
+    pub time: Option<u7>,
+}
+
+/// The General MIDI 2 chorus parameters.
+///
+/// Parameters left as `None` aren't sent.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct ChorusParameters {
+    pub chorus_type: Option<u7>,
+    pub mod_rate: Option<u7>,
+    pub mod_depth: Option<u7>,
+    pub feedback: Option<u7>,
+    pub send_to_reverb: Option<u7>,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum EffectParameters {
+    Reverb(ReverbParameters),
+    Chorus(ChorusParameters),
+}
+
+impl EffectParameters {
+    fn slot(&self) -> u8 {
+        match self {
+            EffectParameters::Reverb(_) => REVERB_SLOT,
+            EffectParameters::Chorus(_) => CHORUS_SLOT,
+        }
+    }
+
+    fn values(&self) -> [Option<u7>; 5] {
+        match *self {
+            EffectParameters::Reverb(reverb) => [reverb.reverb_type, reverb.time, None, None, None],
+            EffectParameters::Chorus(chorus) => [
+                chorus.chorus_type,
+                chorus.mod_rate,
+                chorus.mod_depth,
+                chorus.feedback,
+                chorus.send_to_reverb,
+            ],
+        }
+    }
+
+    /// Returns `false` for parameter ids the effect doesn't define.
+    fn set(&mut self, parameter: u8, value: u7) -> bool {
+        let field = match (self, parameter) {
+            (EffectParameters::Reverb(reverb), 0x0) => &mut reverb.reverb_type,
+            (EffectParameters::Reverb(reverb), 0x1) => &mut reverb.time,
+            (EffectParameters::Chorus(chorus), 0x0) => &mut chorus.chorus_type,
+            (EffectParameters::Chorus(chorus), 0x1) => &mut chorus.mod_rate,
+            (EffectParameters::Chorus(chorus), 0x2) => &mut chorus.mod_depth,
+            (EffectParameters::Chorus(chorus), 0x3) => &mut chorus.feedback,
+            (EffectParameters::Chorus(chorus), 0x4) => &mut chorus.send_to_reverb,
+            _ => return false,
+        };
+        *field = Some(value);
+        true
+    }
+}
+
+/// Device Control Global Parameter Control for the General MIDI 2
+/// reverb and chorus effects.
+///
+/// Global parameter messages addressing other slots are read as
+/// [Unknown](UniversalMessage::Unknown).
+///
+/// ```rust
+/// use midi2::{prelude::*, sysex7::universal::*};
+///
+/// let control = GlobalParameterControl {
+///     device_id: u7::new(ALL_CALL),
+///     parameters: EffectParameters::Reverb(ReverbParameters {
+///         reverb_type: Some(u7::new(0x4)),
+///         time: None,
+///     }),
+/// };
+///
+/// let mut message = sysex7::Sysex7::<Vec<u8>>::new();
+/// message.set_payload(control.payload());
+/// assert_eq!(
+///     message.data(),
+///     &[0xF0, 0x7F, 0x7F, 0x04, 0x05, 0x01, 0x01, 0x01, 0x01, 0x01, 0x00, 0x04, 0xF7],
+/// );
+/// assert_eq!(GlobalParameterControl::try_from(&message), Ok(control));
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct GlobalParameterControl {
+    pub device_id: u7,
+    pub parameters: EffectParameters,
+}
+
+impl GlobalParameterControl {
+    pub fn header(&self) -> Header {
+        device_control_header(self.device_id, GLOBAL_PARAMETER_CONTROL)
+    }
+
+    pub fn payload(&self) -> Payload<19> {
+        let mut payload = Payload::default();
+        payload.extend(self.header().payload());
+        // one slot path, one byte parameter ids and one byte values
+        payload.extend([u7::new(0x1); 3]);
+        payload.push(u7::new(EFFECT_SLOT_MSB));
+        payload.push(u7::new(self.parameters.slot()));
+        for (parameter, value) in self.parameters.values().iter().enumerate() {
+            if let Some(value) = value {
+                payload.push(u7::new(parameter as u8));
+                payload.push(*value);
+            }
+        }
+        payload
+    }
+
+    /// Returns `None` for global parameters other than the
+    /// General MIDI 2 effects, including unknown effect parameter ids.
+    pub(crate) fn read<I: Iterator<Item = u7>>(
+        header: &Header,
+        payload: &mut I,
+    ) -> Result<Option<Self>, InvalidData> {
+        let widths = [next(payload)?, next(payload)?, next(payload)?].map(u8::from);
+        if widths != [0x1; 3] {
+            return Ok(None);
+        }
+        let mut parameters = match [next(payload)?, next(payload)?].map(u8::from) {
+            [EFFECT_SLOT_MSB, REVERB_SLOT] => EffectParameters::Reverb(Default::default()),
+            [EFFECT_SLOT_MSB, CHORUS_SLOT] => EffectParameters::Chorus(Default::default()),
+            _ => return Ok(None),
+        };
+        while let Some(parameter) = payload.next() {
+            if !parameters.set(parameter.into(), next(payload)?) {
+                return Ok(None);
+            }
+        }
+        Ok(Some(GlobalParameterControl {
+            device_id: header.device_id,
+            parameters,
+        }))
+    }
+}
+
+impl<'a, B: crate::buffer::Buffer> TryFrom<&'a Sysex7<B>> for GlobalParameterControl {
+    type Error = InvalidData;
+    fn try_from(message: &'a Sysex7<B>) -> Result<Self, Self::Error> {
+        match UniversalMessage::try_from(message)? {
+            UniversalMessage::GlobalParameterControl(control) => Ok(control),
+            _ => Err(InvalidData(ERR_UNEXPECTED_MESSAGE)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        sysex7::universal::{ALL_CALL, ERR_PAYLOAD_TOO_SHORT},
+        traits::{Data, Sysex},
+    };
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn general_midi_off_bytes() {
+        let message = Sysex7::try_from(&[0xF0_u8, 0x7E, 0x05, 0x09, 0x02, 0xF7][..]).unwrap();
+        assert_eq!(
+            GeneralMidiSystem::try_from(&message),
+            Ok(GeneralMidiSystem {
+                device_id: u7::new(0x05),
+                mode: GeneralMidiMode::GeneralMidiOff,
+            })
+        );
+    }
+
+    #[test]
+    fn general_midi_1_on_ump() {
+        let mut message = Sysex7::<std::vec::Vec<u32>>::new();
+        message.set_payload(
+            GeneralMidiSystem {
+                device_id: u7::new(ALL_CALL),
+                mode: GeneralMidiMode::GeneralMidi1On,
+            }
+            .payload(),
+        );
+        assert_eq!(message.data(), &[0x3004_7E7F, 0x0901_0000]);
+    }
+
+    #[test]
+    fn real_time_general_midi_is_unknown() {
+        let message = Sysex7::try_from(&[0xF0_u8, 0x7F, 0x05, 0x09, 0x01, 0xF7][..]).unwrap();
+        assert_eq!(
+            GeneralMidiSystem::try_from(&message),
+            Err(InvalidData(ERR_UNEXPECTED_MESSAGE))
+        );
+    }
+
+    #[test]
+    fn master_balance_ump() {
+        let mut message = Sysex7::<std::vec::Vec<u32>>::new();
+        message.set_payload(
+            MasterBalance {
+                device_id: u7::new(0x10),
+                balance: u14::new(0x2000),
+            }
+            .payload(),
+        );
+        assert_eq!(message.data(), &[0x3006_7F10, 0x0402_0040]);
+        assert_eq!(
+            MasterBalance::try_from(&message),
+            Ok(MasterBalance {
+                device_id: u7::new(0x10),
+                balance: u14::new(0x2000),
+            })
+        );
+    }
+
+    #[test]
+    fn master_volume_is_not_balance() {
+        let message =
+            Sysex7::try_from(&[0xF0_u8, 0x7F, 0x7F, 0x04, 0x01, 0x00, 0x40, 0xF7][..]).unwrap();
+        assert_eq!(
+            MasterBalance::try_from(&message),
+            Err(InvalidData(ERR_UNEXPECTED_MESSAGE))
+        );
+        assert_eq!(
+            MasterVolume::try_from(&message),
+            Ok(MasterVolume {
+                device_id: u7::new(0x7F),
+                volume: u14::new(0x2000),
+            })
+        );
+    }
+
+    #[test]
+    fn master_volume_too_short() {
+        let message = Sysex7::try_from(&[0xF0_u8, 0x7F, 0x7F, 0x04, 0x01, 0x00, 0xF7][..]).unwrap();
+        assert_eq!(
+            MasterVolume::try_from(&message),
+            Err(InvalidData(ERR_PAYLOAD_TOO_SHORT))
+        );
+    }
+
+    #[test]
+    fn fine_tuning_limits() {
+        assert_eq!(
+            MasterFineTuning::from_cents(u7::new(0x0), -200.0).tuning,
+            u14::new(0x0)
+        );
+        assert_eq!(
+            MasterFineTuning::from_cents(u7::new(0x0), 200.0).tuning,
+            u14::new(0x3FFF)
+        );
+        assert_eq!(
+            MasterFineTuning::from_cents(u7::new(0x0), 0.0).tuning,
+            u14::new(0x2000)
+        );
+    }
+
+    #[test]
+    fn coarse_tuning_bytes() {
+        let mut message = Sysex7::<std::vec::Vec<u8>>::new();
+        message.set_payload(
+            MasterCoarseTuning {
+                device_id: u7::new(0x7F),
+                semitones: -12,
+            }
+            .payload(),
+        );
+        assert_eq!(
+            message.data(),
+            &[0xF0, 0x7F, 0x7F, 0x04, 0x04, 0x00, 0x34, 0xF7]
+        );
+        assert_eq!(
+            MasterCoarseTuning::try_from(&message),
+            Ok(MasterCoarseTuning {
+                device_id: u7::new(0x7F),
+                semitones: -12,
+            })
+        );
+    }
+
+    #[test]
+    fn coarse_tuning_is_clamped() {
+        let mut message = Sysex7::<std::vec::Vec<u8>>::new();
+        message.set_payload(
+            MasterCoarseTuning {
+                device_id: u7::new(0x7F),
+                semitones: 100,
+            }
+            .payload(),
+        );
+        assert_eq!(
+            message.data(),
+            &[0xF0, 0x7F, 0x7F, 0x04, 0x04, 0x00, 0x7F, 0xF7]
+        );
+    }
+
+    #[test]
+    fn chorus_parameters() {
+        let message = Sysex7::try_from(
+            &[
+                0xF0_u8, 0x7F, 0x7F, 0x04, 0x05, 0x01, 0x01, 0x01, 0x01, 0x02, 0x00, 0x03, 0x04,
+                0x40, 0xF7,
+            ][..],
+        )
+        .unwrap();
+        assert_eq!(
+            GlobalParameterControl::try_from(&message),
+            Ok(GlobalParameterControl {
+                device_id: u7::new(0x7F),
+                parameters: EffectParameters::Chorus(ChorusParameters {
+                    chorus_type: Some(u7::new(0x3)),
+                    send_to_reverb: Some(u7::new(0x40)),
+                    ..Default::default()
+                }),
+            })
+        );
+    }
+
+    #[test]
+    fn chorus_parameters_round_trip() {
+        let control = GlobalParameterControl {
+            device_id: u7::new(0x10),
+            parameters: EffectParameters::Chorus(ChorusParameters {
+                chorus_type: Some(u7::new(0x1)),
+                mod_rate: Some(u7::new(0x2)),
+                mod_depth: Some(u7::new(0x3)),
+                feedback: Some(u7::new(0x4)),
+                send_to_reverb: Some(u7::new(0x5)),
+            }),
+        };
+        let mut message = Sysex7::<std::vec::Vec<u32>>::new();
+        message.set_payload(control.payload());
+        assert_eq!(GlobalParameterControl::try_from(&message), Ok(control));
+    }
+
+    #[test]
+    fn unknown_reverb_parameter() {
+        let message = Sysex7::try_from(
+            &[
+                0xF0_u8, 0x7F, 0x7F, 0x04, 0x05, 0x01, 0x01, 0x01, 0x01, 0x01, 0x02, 0x03, 0xF7,
+            ][..],
+        )
+        .unwrap();
+        assert_eq!(
+            UniversalMessage::try_from(&message),
+            Ok(UniversalMessage::Unknown(Header {
+                category: Category::RealTime,
+                device_id: u7::new(0x7F),
+                sub_id1: u7::new(DEVICE_CONTROL),
+                sub_id2: u7::new(GLOBAL_PARAMETER_CONTROL),
+            }))
+        );
+    }
+
+    #[test]
+    fn parameter_missing_value() {
+        let message = Sysex7::try_from(
+            &[
+                0xF0_u8, 0x7F, 0x7F, 0x04, 0x05, 0x01, 0x01, 0x01, 0x01, 0x01, 0x00, 0xF7,
+            ][..],
+        )
+        .unwrap();
+        assert_eq!(
+            GlobalParameterControl::try_from(&message),
+            Err(InvalidData(ERR_PAYLOAD_TOO_SHORT))
+        );
+    }
+
+    #[test]
+    fn other_global_parameters_are_unknown() {
+        let message = Sysex7::try_from(
+            &[
+                0xF0_u8, 0x7F, 0x7F, 0x04, 0x05, 0x01, 0x01, 0x02, 0x01, 0x01, 0x00, 0x00, 0x10,
+                0xF7,
+            ][..],
+        )
+        .unwrap();
+        assert_eq!(
+            UniversalMessage::try_from(&message),
+            Ok(UniversalMessage::Unknown(Header {
+                category: Category::RealTime,
+                device_id: u7::new(0x7F),
+                sub_id1: u7::new(DEVICE_CONTROL),
+                sub_id2: u7::new(GLOBAL_PARAMETER_CONTROL),
+            }))
+        );
+    }
+}
@@ -0,0 +1,262 @@
+use crate::{
+    error::InvalidData,
+    sysex7::{
+        universal::{next, Category, Header, Payload, UniversalMessage, ERR_UNEXPECTED_MESSAGE},
+        Sysex7,
+    },
+    ux::{u14, u7},
+    ManufacturerId,
+};
+
+pub(crate) const GENERAL_INFORMATION: u8 = 0x06;
+pub(crate) const IDENTITY_REQUEST: u8 = 0x01;
+pub(crate) const IDENTITY_REPLY: u8 = 0x02;
+
+/// General Information Identity Request.
+///
+/// Asks the addressed device, or every device with the
+/// [all call](crate::sysex7::universal::ALL_CALL) id, to send an [IdentityReply].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct IdentityRequest {
+    pub device_id: u7,
+}
+
+impl IdentityRequest {
+    pub fn header(&self) -> Header {
+        Header {
+            category: Category::NonRealTime,
+            device_id: self.device_id,
+            sub_id1: u7::new(GENERAL_INFORMATION),
+            sub_id2: u7::new(IDENTITY_REQUEST),
+        }
+    }
+
+    pub fn payload(&self) -> Payload<4> {
+        let mut payload = Payload::default();
+        payload.extend(self.header().payload());
+        payload
+    }
+}
+
+impl<'a, B: crate::buffer::Buffer> TryFrom<&'a Sysex7<B>> for IdentityRequest {
+    type Error = InvalidData;
+    fn try_from(message: &'a Sysex7<B>) -> Result<Self, Self::Error> {
+        match UniversalMessage::try_from(message)? {
+            UniversalMessage::IdentityRequest(request) => Ok(request),
+            _ => Err(InvalidData(ERR_UNEXPECTED_MESSAGE)),
+        }
+    }
+}
+
+/// General Information Identity Reply.
+///
+/// ```rust
+/// use midi2::{prelude::*, sysex7::universal::IdentityReply, ManufacturerId};
+///
+/// let reply = IdentityReply {
+///     device_id: u7::new(0x10),
+///     manufacturer: ManufacturerId::Standard(u7::new(0x43)),
+///     family: u14::new(0x0141),
+///     model: u14::new(0x0002),
+///     version: [u7::new(0x1), u7::new(0x2), u7::new(0x3), u7::new(0x4)],
+/// };
+///
+/// let mut message = sysex7::Sysex7::<Vec<u8>>::new();
+/// message.set_payload(reply.payload());
+/// assert_eq!(
+///     message.data(),
+///     &[0xF0, 0x7E, 0x10, 0x06, 0x02, 0x43, 0x41, 0x02, 0x02, 0x00, 0x01, 0x02, 0x03, 0x04, 0xF7],
+/// );
+/// assert_eq!(IdentityReply::try_from(&message), Ok(reply));
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct IdentityReply {
+    pub device_id: u7,
+    pub manufacturer: ManufacturerId,
+    pub family: u14,
+    pub model: u14,
+    pub version: [u7; 4],
+}
+
+impl IdentityReply {
+    pub fn header(&self) -> Header {
+        Header {
+            category: Category::NonRealTime,
+            device_id: self.device_id,
+            sub_id1: u7::new(GENERAL_INFORMATION),
+            sub_id2: u7::new(IDENTITY_REPLY),
+        }
+    }
+
+    pub fn payload(&self) -> Payload<15> {
+        let mut payload = Payload::default();
+        payload.extend(self.header().payload());
+        payload.extend(self.manufacturer.payload());
+        payload.extend(u14_lsb_first(self.family));
+        payload.extend(u14_lsb_first(self.model));
+        payload.extend(self.version);
+        payload
+    }
+
+    pub(crate) fn read<I: Iterator<Item = u7>>(
+        header: &Header,
+        payload: &mut I,
+    ) -> Result<Self, InvalidData> {
+        let manufacturer = ManufacturerId::read(payload)?;
+        let family = read_u14(payload)?;
+        let model = read_u14(payload)?;
+        let version = [
+            next(payload)?,
+            next(payload)?,
+            next(payload)?,
+            next(payload)?,
+        ];
+        Ok(IdentityReply {
+            device_id: header.device_id,
+            manufacturer,
+            family,
+            model,
+            version,
+        })
+    }
+}
+
+impl<'a, B: crate::buffer::Buffer> TryFrom<&'a Sysex7<B>> for IdentityReply {
+    type Error = InvalidData;
+    fn try_from(message: &'a Sysex7<B>) -> Result<Self, Self::Error> {
+        match UniversalMessage::try_from(message)? {
+            UniversalMessage::IdentityReply(reply) => Ok(reply),
+            _ => Err(InvalidData(ERR_UNEXPECTED_MESSAGE)),
+        }
+    }
+}
+
+pub(super) fn u14_lsb_first(value: u14) -> [u7; 2] {
+    let value = u16::from(value);
+    [u7::new((value & 0x7F) as u8), u7::new((value >> 7) as u8)]
+}
+
+pub(super) fn read_u14<I: Iterator<Item = u7>>(payload: &mut I) -> Result<u14, InvalidData> {
+    let lsb = u16::from(u8::from(next(payload)?));
+    let msb = u16::from(u8::from(next(payload)?));
+    Ok(u14::new(msb << 7 | lsb))
+}
+
+#[cfg(feature = "ump-stream")]
+impl core::convert::From<IdentityReply> for crate::ump_stream::DeviceIdentity<[u32; 4]> {
+    fn from(reply: IdentityReply) -> Self {
+        let mut message = crate::ump_stream::DeviceIdentity::<[u32; 4]>::new();
+        message.set_device_manufacturer(reply.manufacturer);
+        message.set_device_family(reply.family);
+        message.set_device_family_model_number(reply.model);
+        message.set_software_version(reply.version);
+        message
+    }
+}
+
+#[cfg(feature = "ump-stream")]
+impl<'a, B: crate::buffer::Ump> core::convert::From<&'a crate::ump_stream::DeviceIdentity<B>>
+    for IdentityReply
+{
+    /// The reply has the [all call](crate::sysex7::universal::ALL_CALL) device id,
+    /// UMP endpoints not having one.
+    fn from(message: &'a crate::ump_stream::DeviceIdentity<B>) -> Self {
+        IdentityReply {
+            device_id: u7::new(crate::sysex7::universal::ALL_CALL),
+            manufacturer: message.device_manufacturer(),
+            family: message.device_family(),
+            model: message.device_family_model_number(),
+            version: message.software_version(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::traits::{Data, Sysex};
+    use pretty_assertions::assert_eq;
+
+    fn reply() -> IdentityReply {
+        IdentityReply {
+            device_id: u7::new(0x7F),
+            manufacturer: ManufacturerId::Extended(u7::new(0x21), u7::new(0x09)),
+            family: u14::new(0x1234),
+            model: u14::new(0x0056),
+            version: [u7::new(0x1), u7::new(0x0), u7::new(0x2), u7::new(0x0)],
+        }
+    }
+
+    #[test]
+    fn request_bytes() {
+        let message = Sysex7::try_from(&[0xF0_u8, 0x7E, 0x05, 0x06, 0x01, 0xF7][..]).unwrap();
+        assert_eq!(
+            IdentityRequest::try_from(&message),
+            Ok(IdentityRequest {
+                device_id: u7::new(0x05)
+            })
+        );
+    }
+
+    #[test]
+    fn request_is_not_a_reply() {
+        let message = Sysex7::try_from(&[0xF0_u8, 0x7E, 0x05, 0x06, 0x01, 0xF7][..]).unwrap();
+        assert_eq!(
+            IdentityReply::try_from(&message),
+            Err(InvalidData(ERR_UNEXPECTED_MESSAGE))
+        );
+    }
+
+    #[test]
+    fn three_byte_manufacturer_bytes() {
+        let mut message = Sysex7::<std::vec::Vec<u8>>::new();
+        message.set_payload(reply().payload());
+        assert_eq!(
+            message.data(),
+            &[
+                0xF0, 0x7E, 0x7F, 0x06, 0x02, 0x00, 0x21, 0x09, 0x34, 0x24, 0x56, 0x00, 0x01, 0x00,
+                0x02, 0x00, 0xF7
+            ]
+        );
+        assert_eq!(IdentityReply::try_from(&message), Ok(reply()));
+    }
+
+    #[test]
+    fn reply_ump() {
+        let mut message = Sysex7::<[u32; 8]>::new();
+        message.try_set_payload(reply().payload()).unwrap();
+        assert_eq!(
+            message.data(),
+            &[
+                0x3016_7E7F,
+                0x0602_0021,
+                0x3026_0934,
+                0x2456_0001,
+                0x3033_0002,
+                0x0000_0000,
+            ]
+        );
+        assert_eq!(IdentityReply::try_from(&message), Ok(reply()));
+    }
+
+    #[test]
+    fn truncated_reply() {
+        let message =
+            Sysex7::try_from(&[0xF0_u8, 0x7E, 0x7F, 0x06, 0x02, 0x43, 0x01, 0xF7][..]).unwrap();
+        assert_eq!(
+            IdentityReply::try_from(&message),
+            Err(InvalidData(super::super::ERR_PAYLOAD_TOO_SHORT))
+        );
+    }
+
+    #[cfg(feature = "ump-stream")]
+    #[test]
+    fn device_identity_round_trip() {
+        let message = crate::ump_stream::DeviceIdentity::<[u32; 4]>::from(reply());
+        assert_eq!(
+            message.data(),
+            &[0xF002_0000, 0x0000_2109, 0x3424_5600, 0x0100_0200]
+        );
+        assert_eq!(IdentityReply::from(&message), reply());
+    }
+}
@@ -0,0 +1,886 @@
+use crate::{
+    error::InvalidData,
+    sysex7::{
+        universal::{next, Payload, ERR_NOT_UNIVERSAL, ERR_UNEXPECTED_MESSAGE, REAL_TIME},
+        PayloadIterator, Sysex7,
+    },
+    system_common::{FrameRate, Smpte},
+    traits::Sysex,
+    ux::u7,
+};
+
+pub(crate) const MMC_COMMAND: u8 = 0x06;
+pub(crate) const MMC_RESPONSE: u8 = 0x07;
+
+const STOP: u8 = 0x01;
+const PLAY: u8 = 0x02;
+const DEFERRED_PLAY: u8 = 0x03;
+const FAST_FORWARD: u8 = 0x04;
+const REWIND: u8 = 0x05;
+const RECORD_STROBE: u8 = 0x06;
+const RECORD_EXIT: u8 = 0x07;
+const RECORD_PAUSE: u8 = 0x08;
+const PAUSE: u8 = 0x09;
+const WRITE: u8 = 0x40;
+const LOCATE: u8 = 0x44;
+const SHUTTLE: u8 = 0x47;
+
+const LOCATE_FIELD: u8 = 0x00;
+const LOCATE_TARGET: u8 = 0x01;
+
+const SELECTED_TIME_CODE: u8 = 0x01;
+const RECORD_READY: u8 = 0x4F;
+
+/// The field written to arm tracks for recording.
+pub const TRACK_RECORD_READY: u8 = RECORD_READY;
+
+// the largest track bitmap which fits in a u128
+const TRACK_BITMAP_SIZE: usize = 18;
+// enough for the write command with the largest track bitmap
+const MAX_DATA_SIZE: usize = TRACK_BITMAP_SIZE + 1;
+
+const ERR_DATA_TOO_LONG: &str = "MMC data is longer than supported";
+const ERR_INVALID_LOCATE: &str =
+    "Expected MMC locate field with two data bytes or target with six data bytes";
+const ERR_INVALID_SHUTTLE: &str = "Expected MMC shuttle with three data bytes";
+
+/// A MIDI Machine Control command.
+///
+/// Several commands can be packed into one message.
+///
+/// ```rust
+/// use midi2::{
+///     prelude::*,
+///     sysex7::universal::{MmcCommand, MmcCommands},
+///     system_common::{FrameRate, Smpte},
+/// };
+///
+/// let target = Smpte::new(1, 0, 0, 0, FrameRate::Fps25).unwrap();
+///
+/// let mut message = sysex7::Sysex7::<Vec<u8>>::new();
+/// message.set_payload(MmcCommand::payload(
+///     u7::new(0x7F),
+///     [MmcCommand::Locate(target), MmcCommand::Play],
+/// ));
+/// assert_eq!(
+///     message.data(),
+///     &[0xF0, 0x7F, 0x7F, 0x06, 0x44, 0x06, 0x01, 0x21, 0x00, 0x00, 0x00, 0x00, 0x02, 0xF7],
+/// );
+///
+/// let mut commands = MmcCommands::try_from(&message).unwrap();
+/// assert_eq!(commands.next(), Some(Ok(MmcCommand::Locate(target))));
+/// assert_eq!(commands.next(), Some(Ok(MmcCommand::Play)));
+/// assert_eq!(commands.next(), None);
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[non_exhaustive]
+pub enum MmcCommand {
+    Stop,
+    Play,
+    DeferredPlay,
+    FastForward,
+    Rewind,
+    RecordStrobe,
+    RecordExit,
+    RecordPause,
+    Pause,
+    /// Locates to the target time code. Subframes are not kept.
+    Locate(Smpte),
+    /// Locates to the time code held in an information field.
+    LocateField(u7),
+    Shuttle(Speed),
+    /// Writes the tracks of a track bitmap field,
+    /// e.g. [TRACK_RECORD_READY] to arm tracks for recording.
+    Write {
+        field: u7,
+        tracks: TrackBitmap,
+    },
+    /// A command which isn't covered. Its data is skipped.
+    Other(u7),
+}
+
+impl MmcCommand {
+    /// The payload of a message carrying the commands.
+    pub fn payload<I: IntoIterator<Item = MmcCommand>>(
+        device_id: u7,
+        commands: I,
+    ) -> impl Iterator<Item = u7> {
+        header(device_id, MMC_COMMAND).chain(commands.into_iter().flat_map(|c| c.data()))
+    }
+
+    /// The bytes of the command alone.
+    ///
+    /// Other commands write only their command byte.
+    pub fn data(&self) -> Payload<{ MAX_DATA_SIZE + 2 }> {
+        use MmcCommand::*;
+        let mut data = Payload::default();
+        match self {
+            Stop => data.push(u7::new(STOP)),
+            Play => data.push(u7::new(PLAY)),
+            DeferredPlay => data.push(u7::new(DEFERRED_PLAY)),
+            FastForward => data.push(u7::new(FAST_FORWARD)),
+            Rewind => data.push(u7::new(REWIND)),
+            RecordStrobe => data.push(u7::new(RECORD_STROBE)),
+            RecordExit => data.push(u7::new(RECORD_EXIT)),
+            RecordPause => data.push(u7::new(RECORD_PAUSE)),
+            Pause => data.push(u7::new(PAUSE)),
+            Locate(time) => {
+                data.extend([u7::new(LOCATE), u7::new(6), u7::new(LOCATE_TARGET)]);
+                data.extend(time_code(time));
+                data.push(u7::new(0x0));
+            }
+            LocateField(field) => {
+                data.extend([u7::new(LOCATE), u7::new(2), u7::new(LOCATE_FIELD), *field]);
+            }
+            Shuttle(speed) => {
+                data.extend([u7::new(SHUTTLE), u7::new(3)]);
+                data.extend(speed.data());
+            }
+            Write { field, tracks } => {
+                let bitmap = tracks.data();
+                data.extend([u7::new(WRITE), u7::new(bitmap.len() as u8 + 1), *field]);
+                data.extend(bitmap);
+            }
+            Other(command) => data.push(*command),
+        }
+        data
+    }
+}
+
+/// Reads the commands packed into an MMC command message.
+///
+/// Reading stops after the first error.
+#[derive(Clone, Debug)]
+pub struct MmcCommands<I> {
+    device_id: u7,
+    payload: I,
+    done: bool,
+}
+
+impl<I> MmcCommands<I> {
+    pub fn device_id(&self) -> u7 {
+        self.device_id
+    }
+}
+
+impl<'a, B: crate::buffer::Buffer> TryFrom<&'a Sysex7<B>>
+    for MmcCommands<PayloadIterator<'a, B::Unit>>
+{
+    type Error = InvalidData;
+    fn try_from(message: &'a Sysex7<B>) -> Result<Self, Self::Error> {
+        let mut payload = message.payload();
+        Ok(MmcCommands {
+            device_id: read_header(&mut payload, MMC_COMMAND)?,
+            payload,
+            done: false,
+        })
+    }
+}
+
+impl<I: Iterator<Item = u7>> MmcCommands<I> {
+    fn read(&mut self, command: u8) -> Result<MmcCommand, InvalidData> {
+        use MmcCommand::*;
+        Ok(match command {
+            STOP => Stop,
+            PLAY => Play,
+            DEFERRED_PLAY => DeferredPlay,
+            FAST_FORWARD => FastForward,
+            REWIND => Rewind,
+            RECORD_STROBE => RecordStrobe,
+            RECORD_EXIT => RecordExit,
+            RECORD_PAUSE => RecordPause,
+            PAUSE => Pause,
+            LOCATE => {
+                let data = Data::read(&mut self.payload)?;
+                match data.bytes() {
+                    [target, time @ ..]
+                        if u8::from(*target) == LOCATE_TARGET && time.len() == 5 =>
+                    {
+                        Locate(read_time_code(time)?)
+                    }
+                    [sub_command, field] if u8::from(*sub_command) == LOCATE_FIELD => {
+                        LocateField(*field)
+                    }
+                    _ => return Err(InvalidData(ERR_INVALID_LOCATE)),
+                }
+            }
+            SHUTTLE => {
+                let data = Data::read(&mut self.payload)?;
+                match data.bytes() {
+                    [sh, sm, sl] => Shuttle(Speed::from([*sh, *sm, *sl])),
+                    _ => return Err(InvalidData(ERR_INVALID_SHUTTLE)),
+                }
+            }
+            WRITE => {
+                let data = Data::read(&mut self.payload)?;
+                match data.bytes() {
+                    [field, bitmap @ ..] => Write {
+                        field: *field,
+                        tracks: TrackBitmap::read(bitmap)?,
+                    },
+                    [] => Other(u7::new(WRITE)),
+                }
+            }
+            _ => {
+                if has_count(command) {
+                    Data::skip(&mut self.payload)?;
+                }
+                Other(u7::new(command))
+            }
+        })
+    }
+}
+
+impl<I: Iterator<Item = u7>> core::iter::Iterator for MmcCommands<I> {
+    type Item = Result<MmcCommand, InvalidData>;
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let Some(command) = self.payload.next() else {
+            self.done = true;
+            return None;
+        };
+        let command = self.read(command.into());
+        self.done = command.is_err();
+        Some(command)
+    }
+}
+
+impl<I: Iterator<Item = u7>> core::iter::FusedIterator for MmcCommands<I> {}
+
+/// A MIDI Machine Control response, reporting the value of a field.
+///
+/// ```rust
+/// use midi2::{
+///     prelude::*,
+///     sysex7::universal::{MmcResponse, MmcResponses, Track, TrackBitmap},
+/// };
+///
+/// let mut tracks = TrackBitmap::default();
+/// tracks.set(Track::Audio(1), true);
+///
+/// let mut message = sysex7::Sysex7::<[u32; 4]>::new();
+/// message
+///     .try_set_payload(MmcResponse::payload(u7::new(0x10), [MmcResponse::RecordReady(tracks)]))
+///     .unwrap();
+/// assert_eq!(message.data(), &[0x3006_7F10, 0x074F_0120]);
+///
+/// let mut responses = MmcResponses::try_from(&message).unwrap();
+/// assert_eq!(responses.next(), Some(Ok(MmcResponse::RecordReady(tracks))));
+/// assert_eq!(responses.next(), None);
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[non_exhaustive]
+pub enum MmcResponse {
+    /// The current position. Subframes and status are not kept.
+    SelectedTimeCode(Smpte),
+    /// The tracks which are armed for recording.
+    RecordReady(TrackBitmap),
+    /// A field which isn't covered. Its data is skipped.
+    Other(u7),
+}
+
+impl MmcResponse {
+    /// The payload of a message carrying the responses.
+    pub fn payload<I: IntoIterator<Item = MmcResponse>>(
+        device_id: u7,
+        responses: I,
+    ) -> impl Iterator<Item = u7> {
+        header(device_id, MMC_RESPONSE).chain(responses.into_iter().flat_map(|r| r.data()))
+    }
+
+    /// The bytes of the response alone.
+    ///
+    /// Other responses write only their field byte.
+    pub fn data(&self) -> Payload<{ MAX_DATA_SIZE + 1 }> {
+        let mut data = Payload::default();
+        match self {
+            MmcResponse::SelectedTimeCode(time) => {
+                data.push(u7::new(SELECTED_TIME_CODE));
+                data.extend(time_code(time));
+                data.push(u7::new(0x0));
+            }
+            MmcResponse::RecordReady(tracks) => {
+                let bitmap = tracks.data();
+                data.extend([u7::new(RECORD_READY), u7::new(bitmap.len() as u8)]);
+                data.extend(bitmap);
+            }
+            MmcResponse::Other(field) => data.push(*field),
+        }
+        data
+    }
+}
+
+/// Reads the responses packed into an MMC response message.
+///
+/// Reading stops after the first error.
+#[derive(Clone, Debug)]
+pub struct MmcResponses<I> {
+    device_id: u7,
+    payload: I,
+    done: bool,
+}
+
+impl<I> MmcResponses<I> {
+    pub fn device_id(&self) -> u7 {
+        self.device_id
+    }
+}
+
+impl<'a, B: crate::buffer::Buffer> TryFrom<&'a Sysex7<B>>
+    for MmcResponses<PayloadIterator<'a, B::Unit>>
+{
+    type Error = InvalidData;
+    fn try_from(message: &'a Sysex7<B>) -> Result<Self, Self::Error> {
+        let mut payload = message.payload();
+        Ok(MmcResponses {
+            device_id: read_header(&mut payload, MMC_RESPONSE)?,
+            payload,
+            done: false,
+        })
+    }
+}
+
+impl<I: Iterator<Item = u7>> MmcResponses<I> {
+    fn read(&mut self, field: u8) -> Result<MmcResponse, InvalidData> {
+        Ok(match field {
+            SELECTED_TIME_CODE => {
+                let time = [
+                    next(&mut self.payload)?,
+                    next(&mut self.payload)?,
+                    next(&mut self.payload)?,
+                    next(&mut self.payload)?,
+                    next(&mut self.payload)?,
+                ];
+                MmcResponse::SelectedTimeCode(read_time_code(&time)?)
+            }
+            RECORD_READY => {
+                let data = Data::read(&mut self.payload)?;
+                MmcResponse::RecordReady(TrackBitmap::read(data.bytes())?)
+            }
+            // standard time code fields
+            0x02..=0x1F => {
+                for _ in 0..5 {
+                    next(&mut self.payload)?;
+                }
+                MmcResponse::Other(u7::new(field))
+            }
+            // short time code fields
+            0x20..=0x3F => {
+                for _ in 0..2 {
+                    next(&mut self.payload)?;
+                }
+                MmcResponse::Other(u7::new(field))
+            }
+            _ => {
+                if has_count(field) {
+                    Data::skip(&mut self.payload)?;
+                }
+                MmcResponse::Other(u7::new(field))
+            }
+        })
+    }
+}
+
+impl<I: Iterator<Item = u7>> core::iter::Iterator for MmcResponses<I> {
+    type Item = Result<MmcResponse, InvalidData>;
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let Some(field) = self.payload.next() else {
+            self.done = true;
+            return None;
+        };
+        let response = self.read(field.into());
+        self.done = response.is_err();
+        Some(response)
+    }
+}
+
+impl<I: Iterator<Item = u7>> core::iter::FusedIterator for MmcResponses<I> {}
+
+/// The MMC standard speed: a signed fixed point number with between
+/// three and ten integer bits, used by the shuttle command.
+///
+/// ```rust
+/// use midi2::sysex7::universal::Speed;
+///
+/// assert_eq!(Speed::new(1.0).value(), 1.0);
+/// assert_eq!(Speed::new(-2.5).value(), -2.5);
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Speed([u7; 3]);
+
+impl Speed {
+    const MANTISSA_BITS: u32 = 17;
+    const FRACTION_BITS: u32 = 14;
+    const MAX_SHIFT: u32 = 7;
+
+    /// Speeds beyond the range of the format are clamped.
+    pub fn new(speed: f32) -> Self {
+        let reverse = speed < 0.0;
+        let magnitude = if reverse { -speed } else { speed };
+        let mut shift = 0;
+        while shift < Self::MAX_SHIFT
+            && magnitude >= (1_u32 << (Self::MANTISSA_BITS - Self::FRACTION_BITS + shift)) as f32
+        {
+            shift += 1;
+        }
+        let scale = (1_u32 << (Self::FRACTION_BITS - shift)) as f32;
+        let mantissa = ((magnitude * scale + 0.5) as u32).min((1 << Self::MANTISSA_BITS) - 1);
+        Speed([
+            u7::new((u8::from(reverse) << 6) | (shift as u8) << 3 | (mantissa >> 14) as u8),
+            u7::new((mantissa >> 7) as u8 & 0x7F),
+            u7::new(mantissa as u8 & 0x7F),
+        ])
+    }
+
+    pub fn value(&self) -> f32 {
+        let [sh, sm, sl] = self.0.map(u32::from);
+        let shift = (sh >> 3) & 0b111;
+        let mantissa = (sh & 0b111) << 14 | sm << 7 | sl;
+        let magnitude = mantissa as f32 / (1_u32 << (Self::FRACTION_BITS - shift)) as f32;
+        if sh & 0x40 != 0 {
+            -magnitude
+        } else {
+            magnitude
+        }
+    }
+
+    pub fn data(&self) -> [u7; 3] {
+        self.0
+    }
+}
+
+impl core::convert::From<[u7; 3]> for Speed {
+    fn from(data: [u7; 3]) -> Self {
+        Speed(data)
+    }
+}
+
+/// The tracks of an MMC track bitmap.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Track {
+    Video,
+    TimeCode,
+    AuxA,
+    AuxB,
+    /// Audio tracks are numbered from 1 to 121.
+    Audio(u8),
+}
+
+impl Track {
+    fn bit(&self) -> Option<u32> {
+        match self {
+            Track::Video => Some(0),
+            Track::TimeCode => Some(2),
+            Track::AuxA => Some(3),
+            Track::AuxB => Some(4),
+            Track::Audio(n @ 1..=121) => Some(u32::from(*n) + 4),
+            Track::Audio(_) => None,
+        }
+    }
+}
+
+/// A set of tracks, e.g. the tracks armed for recording.
+///
+/// Audio tracks which are out of range are never set.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct TrackBitmap(u128);
+
+impl TrackBitmap {
+    pub fn is_set(&self, track: Track) -> bool {
+        track.bit().is_some_and(|bit| self.0 & (1 << bit) != 0)
+    }
+
+    pub fn set(&mut self, track: Track, value: bool) {
+        let Some(bit) = track.bit() else {
+            return;
+        };
+        if value {
+            self.0 |= 1 << bit;
+        } else {
+            self.0 &= !(1 << bit);
+        }
+    }
+
+    fn data(&self) -> Payload<TRACK_BITMAP_SIZE> {
+        let mut data = Payload::default();
+        let len = (128 - self.0.leading_zeros()).div_ceil(7).max(1);
+        for i in 0..len {
+            data.push(u7::new((self.0 >> (7 * i)) as u8 & 0x7F));
+        }
+        data
+    }
+
+    fn read(data: &[u7]) -> Result<Self, InvalidData> {
+        if data.len() > TRACK_BITMAP_SIZE {
+            return Err(InvalidData(ERR_DATA_TOO_LONG));
+        }
+        Ok(TrackBitmap(
+            data.iter().enumerate().fold(0, |bits, (i, byte)| {
+                bits | u128::from(u8::from(*byte)) << (7 * i)
+            }),
+        ))
+    }
+}
+
+// data preceded by a count byte
+struct Data {
+    bytes: [u7; MAX_DATA_SIZE],
+    len: usize,
+}
+
+impl Data {
+    fn read<I: Iterator<Item = u7>>(payload: &mut I) -> Result<Self, InvalidData> {
+        let len = usize::from(u8::from(next(payload)?));
+        if len > MAX_DATA_SIZE {
+            return Err(InvalidData(ERR_DATA_TOO_LONG));
+        }
+        let mut bytes = [u7::new(0x0); MAX_DATA_SIZE];
+        for byte in bytes.iter_mut().take(len) {
+            *byte = next(payload)?;
+        }
+        Ok(Data { bytes, len })
+    }
+
+    fn skip<I: Iterator<Item = u7>>(payload: &mut I) -> Result<(), InvalidData> {
+        for _ in 0..u8::from(next(payload)?) {
+            next(payload)?;
+        }
+        Ok(())
+    }
+
+    fn bytes(&self) -> &[u7] {
+        &self.bytes[..self.len]
+    }
+}
+
+// commands and fields from 0x40 to 0x77 are followed by a count byte
+fn has_count(byte: u8) -> bool {
+    (0x40..=0x77).contains(&byte)
+}
+
+fn header(device_id: u7, sub_id: u8) -> impl Iterator<Item = u7> {
+    [u7::new(REAL_TIME), device_id, u7::new(sub_id)].into_iter()
+}
+
+fn read_header<I: Iterator<Item = u7>>(payload: &mut I, sub_id: u8) -> Result<u7, InvalidData> {
+    if u8::from(next(payload)?) != REAL_TIME {
+        return Err(InvalidData(ERR_NOT_UNIVERSAL));
+    }
+    let device_id = next(payload)?;
+    if u8::from(next(payload)?) != sub_id {
+        return Err(InvalidData(ERR_UNEXPECTED_MESSAGE));
+    }
+    Ok(device_id)
+}
+
+// hours with the rate code, minutes, seconds and frames
+fn time_code(time: &Smpte) -> [u7; 4] {
+    [
+        u7::new(time.frame_rate().rate_code() << 5 | time.hours()),
+        u7::new(time.minutes()),
+        u7::new(time.seconds()),
+        u7::new(time.frames()),
+    ]
+}
+
+fn read_time_code(data: &[u7]) -> Result<Smpte, InvalidData> {
+    let [hr, mn, sc, fr, ..] = data else {
+        return Err(InvalidData(super::ERR_PAYLOAD_TOO_SHORT));
+    };
+    let hr = u8::from(*hr);
+    Smpte::new(
+        hr & 0x1F,
+        u8::from(*mn) & 0x3F,
+        u8::from(*sc) & 0x3F,
+        u8::from(*fr) & 0x1F,
+        FrameRate::from_rate_code(hr >> 5),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::traits::Data as _;
+    use pretty_assertions::assert_eq;
+
+    fn commands<B: crate::buffer::Buffer>(
+        message: &Sysex7<B>,
+    ) -> std::vec::Vec<Result<MmcCommand, InvalidData>> {
+        MmcCommands::try_from(message).unwrap().collect()
+    }
+
+    #[test]
+    fn transport_commands() {
+        let message = Sysex7::try_from(
+            &[
+                0xF0_u8, 0x7F, 0x7F, 0x06, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09,
+                0xF7,
+            ][..],
+        )
+        .unwrap();
+        assert_eq!(
+            commands(&message),
+            std::vec![
+                Ok(MmcCommand::Stop),
+                Ok(MmcCommand::Play),
+                Ok(MmcCommand::DeferredPlay),
+                Ok(MmcCommand::FastForward),
+                Ok(MmcCommand::Rewind),
+                Ok(MmcCommand::RecordStrobe),
+                Ok(MmcCommand::RecordExit),
+                Ok(MmcCommand::RecordPause),
+                Ok(MmcCommand::Pause),
+            ]
+        );
+    }
+
+    #[test]
+    fn device_id() {
+        let message = Sysex7::try_from(&[0xF0_u8, 0x7F, 0x05, 0x06, 0x01, 0xF7][..]).unwrap();
+        assert_eq!(
+            MmcCommands::try_from(&message).unwrap().device_id(),
+            u7::new(0x05)
+        );
+    }
+
+    #[test]
+    fn not_a_command() {
+        let message = Sysex7::try_from(&[0xF0_u8, 0x7F, 0x05, 0x07, 0x01, 0xF7][..]).unwrap();
+        assert_eq!(
+            MmcCommands::try_from(&message).err(),
+            Some(InvalidData(ERR_UNEXPECTED_MESSAGE))
+        );
+    }
+
+    #[test]
+    fn locate_ump() {
+        let target = Smpte::new(10, 20, 30, 12, FrameRate::Fps29_97DropFrame).unwrap();
+        let mut message = Sysex7::<[u32; 8]>::new();
+        message
+            .try_set_payload(MmcCommand::payload(
+                u7::new(0x7F),
+                [MmcCommand::Locate(target)],
+            ))
+            .unwrap();
+        assert_eq!(
+            message.data(),
+            &[0x3016_7F7F, 0x0644_0601, 0x3035_4A14, 0x1E0C_0000]
+        );
+        assert_eq!(
+            commands(&message),
+            std::vec![Ok(MmcCommand::Locate(target))]
+        );
+    }
+
+    #[test]
+    fn locate_invalid_time() {
+        let message = Sysex7::try_from(
+            &[
+                0xF0_u8, 0x7F, 0x7F, 0x06, 0x44, 0x06, 0x01, 0x18, 0x00, 0x00, 0x00, 0x00, 0xF7,
+            ][..],
+        )
+        .unwrap();
+        assert_eq!(
+            commands(&message),
+            std::vec![Err(InvalidData("Hours out of range"))]
+        );
+    }
+
+    #[test]
+    fn locate_field() {
+        let message = Sysex7::try_from(
+            &[
+                0xF0_u8, 0x7F, 0x7F, 0x06, 0x44, 0x02, 0x00, 0x08, 0x02, 0xF7,
+            ][..],
+        )
+        .unwrap();
+        assert_eq!(
+            commands(&message),
+            std::vec![
+                Ok(MmcCommand::LocateField(u7::new(0x08))),
+                Ok(MmcCommand::Play)
+            ]
+        );
+    }
+
+    #[test]
+    fn locate_field_bytes() {
+        let mut message = Sysex7::<std::vec::Vec<u8>>::new();
+        message.set_payload(MmcCommand::payload(
+            u7::new(0x7F),
+            [MmcCommand::LocateField(u7::new(0x08))],
+        ));
+        assert_eq!(
+            message.data(),
+            &[0xF0, 0x7F, 0x7F, 0x06, 0x44, 0x02, 0x00, 0x08, 0xF7]
+        );
+    }
+
+    #[test]
+    fn locate_unknown_sub_command() {
+        let message =
+            Sysex7::try_from(&[0xF0_u8, 0x7F, 0x7F, 0x06, 0x44, 0x02, 0x05, 0x08, 0xF7][..])
+                .unwrap();
+        assert_eq!(
+            commands(&message),
+            std::vec![Err(InvalidData(ERR_INVALID_LOCATE))]
+        );
+    }
+
+    #[test]
+    fn shuttle() {
+        let mut message = Sysex7::<std::vec::Vec<u8>>::new();
+        message.set_payload(MmcCommand::payload(
+            u7::new(0x7F),
+            [MmcCommand::Shuttle(Speed::new(-1.5)), MmcCommand::Stop],
+        ));
+        assert_eq!(
+            message.data(),
+            &[0xF0, 0x7F, 0x7F, 0x06, 0x47, 0x03, 0x41, 0x40, 0x00, 0x01, 0xF7]
+        );
+        assert_eq!(
+            commands(&message),
+            std::vec![
+                Ok(MmcCommand::Shuttle(Speed::new(-1.5))),
+                Ok(MmcCommand::Stop)
+            ]
+        );
+    }
+
+    #[test]
+    fn speed_shift() {
+        assert_eq!(Speed::new(100.25).data(), [0x26, 0x22, 0x00].map(u7::new));
+        assert_eq!(Speed::new(100.25).value(), 100.25);
+    }
+
+    #[test]
+    fn speed_clamped() {
+        assert_eq!(Speed::new(5000.0).data(), [0x3F, 0x7F, 0x7F].map(u7::new));
+    }
+
+    #[test]
+    fn write_track_record_ready() {
+        let mut tracks = TrackBitmap::default();
+        tracks.set(Track::Audio(1), true);
+        tracks.set(Track::Audio(4), true);
+        tracks.set(Track::TimeCode, true);
+        let mut message = Sysex7::<std::vec::Vec<u8>>::new();
+        message.set_payload(MmcCommand::payload(
+            u7::new(0x7F),
+            [MmcCommand::Write {
+                field: u7::new(TRACK_RECORD_READY),
+                tracks,
+            }],
+        ));
+        assert_eq!(
+            message.data(),
+            &[0xF0, 0x7F, 0x7F, 0x06, 0x40, 0x03, 0x4F, 0x24, 0x02, 0xF7]
+        );
+        assert_eq!(
+            commands(&message),
+            std::vec![Ok(MmcCommand::Write {
+                field: u7::new(TRACK_RECORD_READY),
+                tracks,
+            })]
+        );
+    }
+
+    #[test]
+    fn track_bitmap() {
+        let mut tracks = TrackBitmap::default();
+        tracks.set(Track::Audio(121), true);
+        tracks.set(Track::Audio(122), true);
+        tracks.set(Track::Video, true);
+        assert!(tracks.is_set(Track::Audio(121)));
+        assert!(!tracks.is_set(Track::Audio(122)));
+        assert!(tracks.is_set(Track::Video));
+        tracks.set(Track::Video, false);
+        assert!(!tracks.is_set(Track::Video));
+        assert_eq!(tracks.data().count(), TRACK_BITMAP_SIZE);
+    }
+
+    #[test]
+    fn other_commands_are_skipped() {
+        let message = Sysex7::try_from(
+            &[
+                0xF0_u8, 0x7F, 0x7F, 0x06, 0x0D, 0x4C, 0x02, 0x01, 0x02, 0x02, 0xF7,
+            ][..],
+        )
+        .unwrap();
+        assert_eq!(
+            commands(&message),
+            std::vec![
+                Ok(MmcCommand::Other(u7::new(0x0D))),
+                Ok(MmcCommand::Other(u7::new(0x4C))),
+                Ok(MmcCommand::Play),
+            ]
+        );
+    }
+
+    #[test]
+    fn truncated_command() {
+        let message =
+            Sysex7::try_from(&[0xF0_u8, 0x7F, 0x7F, 0x06, 0x47, 0x03, 0x01, 0xF7][..]).unwrap();
+        assert_eq!(
+            commands(&message),
+            std::vec![Err(InvalidData(super::super::ERR_PAYLOAD_TOO_SHORT))]
+        );
+    }
+
+    #[test]
+    fn responses() {
+        let time = Smpte::new(0, 1, 2, 3, FrameRate::Fps30).unwrap();
+        let mut tracks = TrackBitmap::default();
+        tracks.set(Track::Audio(2), true);
+        let mut message = Sysex7::<std::vec::Vec<u8>>::new();
+        message.set_payload(MmcResponse::payload(
+            u7::new(0x7F),
+            [
+                MmcResponse::SelectedTimeCode(time),
+                MmcResponse::RecordReady(tracks),
+            ],
+        ));
+        assert_eq!(
+            message.data(),
+            &[0xF0, 0x7F, 0x7F, 0x07, 0x01, 0x60, 0x01, 0x02, 0x03, 0x00, 0x4F, 0x01, 0x40, 0xF7]
+        );
+        assert_eq!(
+            MmcResponses::try_from(&message)
+                .unwrap()
+                .collect::<std::vec::Vec<_>>(),
+            std::vec![
+                Ok(MmcResponse::SelectedTimeCode(time)),
+                Ok(MmcResponse::RecordReady(tracks)),
+            ]
+        );
+    }
+
+    #[test]
+    fn other_responses_are_skipped() {
+        let message = Sysex7::try_from(
+            &[
+                0xF0_u8, 0x7F, 0x7F, 0x07, 0x02, 0x00, 0x00, 0x00, 0x00, 0x00, 0x21, 0x00, 0x00,
+                0x48, 0x01, 0x00, 0x4F, 0x01, 0x20, 0xF7,
+            ][..],
+        )
+        .unwrap();
+        let mut tracks = TrackBitmap::default();
+        tracks.set(Track::Audio(1), true);
+        assert_eq!(
+            MmcResponses::try_from(&message)
+                .unwrap()
+                .collect::<std::vec::Vec<_>>(),
+            std::vec![
+                Ok(MmcResponse::Other(u7::new(0x02))),
+                Ok(MmcResponse::Other(u7::new(0x21))),
+                Ok(MmcResponse::Other(u7::new(0x48))),
+                Ok(MmcResponse::RecordReady(tracks)),
+            ]
+        );
+    }
+}
@@ -0,0 +1,860 @@
+use crate::{
+    error::{BufferOverflow, InvalidData},
+    sysex7::{
+        universal::{next, Category, Header, Payload, UniversalMessage, ERR_UNEXPECTED_MESSAGE},
+        Sysex7,
+    },
+    ux::{u14, u7},
+};
+
+pub(crate) const MIDI_TUNING_STANDARD: u8 = 0x08;
+pub(crate) const BULK_TUNING_DUMP_REQUEST: u8 = 0x00;
+pub(crate) const BULK_TUNING_DUMP: u8 = 0x01;
+pub(crate) const SINGLE_NOTE_TUNING_CHANGE: u8 = 0x02;
+pub(crate) const BULK_TUNING_DUMP_REQUEST_BANK: u8 = 0x03;
+pub(crate) const BULK_TUNING_DUMP_BANK: u8 = 0x04;
+pub(crate) const SINGLE_NOTE_TUNING_CHANGE_BANK: u8 = 0x07;
+pub(crate) const SCALE_OCTAVE_TUNING_1_BYTE: u8 = 0x08;
+pub(crate) const SCALE_OCTAVE_TUNING_2_BYTE: u8 = 0x09;
+
+/// The number of tuning changes which fit in one single note tuning change.
+pub const MAX_NOTE_TUNINGS: usize = 127;
+
+const NAME_SIZE: usize = 16;
+const NOTES: usize = 128;
+const NO_CHANGE: u8 = 0x7F;
+const SCALE_OCTAVE_1_BYTE_CENTER: i8 = 0x40;
+const SCALE_OCTAVE_2_BYTE_CENTER: i16 = 0x2000;
+
+// header, bank, program, name, frequencies and checksum
+const BULK_TUNING_DUMP_SIZE: usize = 4 + 1 + 1 + NAME_SIZE + 3 * NOTES + 1;
+// header, bank, program, count and changes
+const SINGLE_NOTE_TUNING_CHANGE_SIZE: usize = 4 + 1 + 1 + 1 + 4 * MAX_NOTE_TUNINGS;
+// header, channels and offsets
+const SCALE_OCTAVE_TUNING_SIZE: usize = 4 + 3 + 2 * 12;
+
+const ERR_CHECKSUM: &str = "Bulk tuning dump checksum mismatch";
+
+/// A frequency of the MIDI Tuning Standard: a semitone, in which A4 is 69,
+/// and a fraction of 1/16384 of a semitone above it.
+///
+/// With the `channel-voice2` feature the frequency implements
+/// [Pitch](crate::channel_voice2::Pitch), giving conversions to and from Hz.
+/// It converts losslessly from, and rounding down into,
+/// [Fixed7_9](crate::num::Fixed7_9) pitches of
+/// [Pitch7_9](crate::channel_voice2::NoteAttribute::Pitch7_9) note attributes.
+///
+/// ```rust
+/// use midi2::{num::Fixed7_9, sysex7::universal::TuningFrequency, ux::{u14, u7}};
+///
+/// let frequency = TuningFrequency {
+///     semitone: u7::new(60),
+///     fraction: u14::new(0x2000),
+/// };
+/// assert_eq!(Fixed7_9::from(frequency), Fixed7_9::from_num(60.5));
+/// assert_eq!(TuningFrequency::from(Fixed7_9::from_num(60.5)), frequency);
+/// ```
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct TuningFrequency {
+    pub semitone: u7,
+    pub fraction: u14,
+}
+
+impl TuningFrequency {
+    // bits of the fraction not carried by Fixed7_9
+    const FIXED7_9_SHIFT: u32 = 14 - 9;
+
+    pub fn data(&self) -> [u7; 3] {
+        let fraction = u16::from(self.fraction);
+        [
+            self.semitone,
+            u7::new((fraction >> 7) as u8),
+            u7::new(fraction as u8 & 0x7F),
+        ]
+    }
+
+    fn write(frequency: &Option<TuningFrequency>) -> [u7; 3] {
+        match frequency {
+            Some(frequency) => frequency.data(),
+            None => [u7::new(NO_CHANGE); 3],
+        }
+    }
+
+    // none for the reserved no change value
+    fn read(data: &[u7; 3]) -> Option<TuningFrequency> {
+        if *data == [u7::new(NO_CHANGE); 3] {
+            return None;
+        }
+        let [semitone, msb, lsb] = *data;
+        Some(TuningFrequency {
+            semitone,
+            fraction: u14::new(u16::from(msb) << 7 | u16::from(lsb)),
+        })
+    }
+}
+
+impl core::convert::From<TuningFrequency> for crate::num::Fixed7_9 {
+    fn from(frequency: TuningFrequency) -> Self {
+        crate::num::Fixed7_9::from_bits(
+            u16::from(u8::from(frequency.semitone)) << 9
+                | u16::from(frequency.fraction) >> TuningFrequency::FIXED7_9_SHIFT,
+        )
+    }
+}
+
+impl core::convert::From<crate::num::Fixed7_9> for TuningFrequency {
+    fn from(pitch: crate::num::Fixed7_9) -> Self {
+        let bits = pitch.to_bits();
+        TuningFrequency {
+            semitone: u7::new((bits >> 9) as u8),
+            fraction: u14::new((bits & 0x1FF) << TuningFrequency::FIXED7_9_SHIFT),
+        }
+    }
+}
+
+#[cfg(feature = "channel-voice2")]
+impl crate::channel_voice2::Pitch for TuningFrequency {
+    fn semitones(&self) -> f64 {
+        u8::from(self.semitone) as f64 + u16::from(self.fraction) as f64 / 16384.0
+    }
+
+    /// Saturates below the reserved no change value.
+    fn from_semitones(semitones: f64) -> Self {
+        const MAX: u32 = 0x7F << 14 | 0x3FFE;
+        let value = if semitones <= 0.0 {
+            0
+        } else {
+            ((semitones * 16384.0 + 0.5) as u32).min(MAX)
+        };
+        TuningFrequency {
+            semitone: u7::new((value >> 14) as u8),
+            fraction: u14::new(value as u16 & 0x3FFF),
+        }
+    }
+}
+
+/// The checksum of the bulk tuning dump:
+/// the exclusive or of the payload bytes which precede it.
+pub fn tuning_checksum<I: IntoIterator<Item = u7>>(payload: I) -> u7 {
+    payload
+        .into_iter()
+        .fold(u7::new(0x0), |checksum, byte| checksum ^ byte)
+}
+
+fn header(
+    category: Category,
+    device_id: u7,
+    bank: Option<u7>,
+    sub_id2: u8,
+    bank_sub_id2: u8,
+) -> Header {
+    Header {
+        category,
+        device_id,
+        sub_id1: u7::new(MIDI_TUNING_STANDARD),
+        sub_id2: u7::new(if bank.is_some() {
+            bank_sub_id2
+        } else {
+            sub_id2
+        }),
+    }
+}
+
+// the bank is only present in the bank forms of the messages
+fn read_bank<I: Iterator<Item = u7>>(
+    header: &Header,
+    bank_sub_id2: u8,
+    payload: &mut I,
+) -> Result<Option<u7>, InvalidData> {
+    if u8::from(header.sub_id2) == bank_sub_id2 {
+        Ok(Some(next(payload)?))
+    } else {
+        Ok(None)
+    }
+}
+
+/// Requests the [BulkTuningDump] of a tuning program.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct BulkTuningDumpRequest {
+    pub device_id: u7,
+    pub bank: Option<u7>,
+    pub program: u7,
+}
+
+impl BulkTuningDumpRequest {
+    pub fn header(&self) -> Header {
+        header(
+            Category::NonRealTime,
+            self.device_id,
+            self.bank,
+            BULK_TUNING_DUMP_REQUEST,
+            BULK_TUNING_DUMP_REQUEST_BANK,
+        )
+    }
+
+    pub fn payload(&self) -> Payload<6> {
+        let mut payload = Payload::default();
+        payload.extend(self.header().payload());
+        payload.extend(self.bank);
+        payload.push(self.program);
+        payload
+    }
+
+    pub(crate) fn read<I: Iterator<Item = u7>>(
+        header: &Header,
+        payload: &mut I,
+    ) -> Result<Self, InvalidData> {
+        Ok(BulkTuningDumpRequest {
+            device_id: header.device_id,
+            bank: read_bank(header, BULK_TUNING_DUMP_REQUEST_BANK, payload)?,
+            program: next(payload)?,
+        })
+    }
+}
+
+impl<'a, B: crate::buffer::Buffer> TryFrom<&'a Sysex7<B>> for BulkTuningDumpRequest {
+    type Error = InvalidData;
+    fn try_from(message: &'a Sysex7<B>) -> Result<Self, Self::Error> {
+        match UniversalMessage::try_from(message)? {
+            UniversalMessage::BulkTuningDumpRequest(message) => Ok(message),
+            _ => Err(InvalidData(ERR_UNEXPECTED_MESSAGE)),
+        }
+    }
+}
+
+/// The frequencies of all 128 notes of a tuning program.
+///
+/// The checksum is written with the payload and checked when reading.
+///
+/// ```rust
+/// use midi2::{prelude::*, sysex7::universal::{BulkTuningDump, TuningFrequency}};
+///
+/// let mut dump = BulkTuningDump::new(u7::new(0x7F), None, u7::new(0x2));
+/// dump.name = b"Just intonation ".map(u7::new);
+/// dump.set_frequency(
+///     u7::new(60),
+///     Some(TuningFrequency { semitone: u7::new(60), fraction: u14::new(0x100) }),
+/// );
+///
+/// let mut message = sysex7::Sysex7::<Vec<u32>>::new();
+/// message.set_payload(dump.payload());
+/// assert_eq!(BulkTuningDump::try_from(&message), Ok(dump));
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct BulkTuningDump {
+    pub device_id: u7,
+    pub bank: Option<u7>,
+    pub program: u7,
+    /// The name of the tuning in ASCII.
+    pub name: [u7; NAME_SIZE],
+    frequencies: [[u7; 3]; NOTES],
+}
+
+impl BulkTuningDump {
+    /// A dump with a blank name, leaving every note unchanged.
+    pub fn new(device_id: u7, bank: Option<u7>, program: u7) -> Self {
+        BulkTuningDump {
+            device_id,
+            bank,
+            program,
+            name: [u7::new(b' '); NAME_SIZE],
+            frequencies: [TuningFrequency::write(&None); NOTES],
+        }
+    }
+
+    /// The frequency of the note, with none leaving the note unchanged.
+    pub fn frequency(&self, key: u7) -> Option<TuningFrequency> {
+        TuningFrequency::read(&self.frequencies[usize::from(u8::from(key))])
+    }
+
+    pub fn set_frequency(&mut self, key: u7, frequency: Option<TuningFrequency>) {
+        self.frequencies[usize::from(u8::from(key))] = TuningFrequency::write(&frequency);
+    }
+
+    pub fn header(&self) -> Header {
+        header(
+            Category::NonRealTime,
+            self.device_id,
+            self.bank,
+            BULK_TUNING_DUMP,
+            BULK_TUNING_DUMP_BANK,
+        )
+    }
+
+    pub fn payload(&self) -> Payload<BULK_TUNING_DUMP_SIZE> {
+        let mut payload = Payload::<BULK_TUNING_DUMP_SIZE>::default();
+        payload.extend(self.header().payload());
+        payload.extend(self.bank);
+        payload.push(self.program);
+        payload.extend(self.name);
+        for frequency in self.frequencies {
+            payload.extend(frequency);
+        }
+        payload.push(tuning_checksum(payload.clone()));
+        payload
+    }
+
+    pub(crate) fn read<I: Iterator<Item = u7>>(
+        header: &Header,
+        payload: &mut I,
+    ) -> Result<Self, InvalidData> {
+        let mut dump = BulkTuningDump::new(
+            header.device_id,
+            read_bank(header, BULK_TUNING_DUMP_BANK, payload)?,
+            next(payload)?,
+        );
+        for byte in dump.name.iter_mut() {
+            *byte = next(payload)?;
+        }
+        for frequency in dump.frequencies.iter_mut() {
+            *frequency = [next(payload)?, next(payload)?, next(payload)?];
+        }
+        let checksum = next(payload)?;
+        // every byte is kept, so the written form has the received checksum
+        if dump.payload().last() != Some(checksum) {
+            return Err(InvalidData(ERR_CHECKSUM));
+        }
+        Ok(dump)
+    }
+}
+
+impl<'a, B: crate::buffer::Buffer> TryFrom<&'a Sysex7<B>> for BulkTuningDump {
+    type Error = InvalidData;
+    fn try_from(message: &'a Sysex7<B>) -> Result<Self, Self::Error> {
+        match UniversalMessage::try_from(message)? {
+            UniversalMessage::BulkTuningDump(message) => Ok(message),
+            _ => Err(InvalidData(ERR_UNEXPECTED_MESSAGE)),
+        }
+    }
+}
+
+/// The tuning of one note.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct NoteTuning {
+    pub key: u7,
+    /// None leaves the note unchanged.
+    pub frequency: Option<TuningFrequency>,
+}
+
+/// Retunes individual notes of a tuning program.
+///
+/// The MTS specification defines the change without a bank as real time only,
+/// the change with a bank may be real time or non-real time.
+///
+/// ```rust
+/// use midi2::{
+///     prelude::*,
+///     sysex7::universal::{Category, NoteTuning, SingleNoteTuningChange, TuningFrequency},
+/// };
+///
+/// let mut change = SingleNoteTuningChange::new(Category::RealTime, u7::new(0x7F), None, u7::new(0x0));
+/// change.push(NoteTuning {
+///     key: u7::new(69),
+///     frequency: Some(TuningFrequency { semitone: u7::new(69), fraction: u14::new(0x0) }),
+/// }).unwrap();
+///
+/// let mut message = sysex7::Sysex7::<Vec<u8>>::new();
+/// message.set_payload(change.payload());
+/// assert_eq!(
+///     message.data(),
+///     &[0xF0, 0x7F, 0x7F, 0x08, 0x02, 0x00, 0x01, 0x45, 0x45, 0x00, 0x00, 0xF7],
+/// );
+/// assert_eq!(SingleNoteTuningChange::try_from(&message), Ok(change));
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct SingleNoteTuningChange {
+    pub category: Category,
+    pub device_id: u7,
+    pub bank: Option<u7>,
+    pub program: u7,
+    // key followed by the frequency
+    changes: [[u7; 4]; MAX_NOTE_TUNINGS],
+    len: usize,
+}
+
+impl SingleNoteTuningChange {
+    pub fn new(category: Category, device_id: u7, bank: Option<u7>, program: u7) -> Self {
+        SingleNoteTuningChange {
+            category,
+            device_id,
+            bank,
+            program,
+            changes: [[u7::new(0x0); 4]; MAX_NOTE_TUNINGS],
+            len: 0,
+        }
+    }
+
+    pub fn changes(&self) -> impl ExactSizeIterator<Item = NoteTuning> + '_ {
+        self.changes[..self.len]
+            .iter()
+            .map(|[key, frequency @ ..]| NoteTuning {
+                key: *key,
+                frequency: TuningFrequency::read(frequency),
+            })
+    }
+
+    /// Fails when the change already holds [MAX_NOTE_TUNINGS] tunings.
+    pub fn push(&mut self, tuning: NoteTuning) -> Result<(), BufferOverflow> {
+        if self.len == MAX_NOTE_TUNINGS {
+            return Err(BufferOverflow);
+        }
+        let [semitone, msb, lsb] = TuningFrequency::write(&tuning.frequency);
+        self.changes[self.len] = [tuning.key, semitone, msb, lsb];
+        self.len += 1;
+        Ok(())
+    }
+
+    pub fn header(&self) -> Header {
+        header(
+            self.category,
+            self.device_id,
+            self.bank,
+            SINGLE_NOTE_TUNING_CHANGE,
+            SINGLE_NOTE_TUNING_CHANGE_BANK,
+        )
+    }
+
+    pub fn payload(&self) -> Payload<SINGLE_NOTE_TUNING_CHANGE_SIZE> {
+        let mut payload = Payload::default();
+        payload.extend(self.header().payload());
+        payload.extend(self.bank);
+        payload.push(self.program);
+        payload.push(u7::new(self.len as u8));
+        for change in &self.changes[..self.len] {
+            payload.extend(*change);
+        }
+        payload
+    }
+
+    pub(crate) fn read<I: Iterator<Item = u7>>(
+        header: &Header,
+        payload: &mut I,
+    ) -> Result<Self, InvalidData> {
+        let mut change = SingleNoteTuningChange::new(
+            header.category,
+            header.device_id,
+            read_bank(header, SINGLE_NOTE_TUNING_CHANGE_BANK, payload)?,
+            next(payload)?,
+        );
+        for _ in 0..u8::from(next(payload)?) {
+            change.changes[change.len] = [
+                next(payload)?,
+                next(payload)?,
+                next(payload)?,
+                next(payload)?,
+            ];
+            change.len += 1;
+        }
+        Ok(change)
+    }
+}
+
+impl<'a, B: crate::buffer::Buffer> TryFrom<&'a Sysex7<B>> for SingleNoteTuningChange {
+    type Error = InvalidData;
+    fn try_from(message: &'a Sysex7<B>) -> Result<Self, Self::Error> {
+        match UniversalMessage::try_from(message)? {
+            UniversalMessage::SingleNoteTuningChange(message) => Ok(message),
+            _ => Err(InvalidData(ERR_UNEXPECTED_MESSAGE)),
+        }
+    }
+}
+
+// ff gg hh, ff holding channels 15 and 16, hh channels 1 to 7
+fn write_channels(channels: u16) -> [u7; 3] {
+    [
+        u7::new((channels >> 14) as u8 & 0b11),
+        u7::new((channels >> 7) as u8 & 0x7F),
+        u7::new(channels as u8 & 0x7F),
+    ]
+}
+
+fn read_channels<I: Iterator<Item = u7>>(payload: &mut I) -> Result<u16, InvalidData> {
+    let [ff, gg, hh] = [next(payload)?, next(payload)?, next(payload)?].map(u16::from);
+    Ok((ff & 0b11) << 14 | gg << 7 | hh)
+}
+
+/// Offsets each pitch class of the scale on the selected channels,
+/// with a resolution of one cent.
+///
+/// ```rust
+/// use midi2::{prelude::*, sysex7::universal::{Category, ScaleOctaveTuning1Byte}};
+///
+/// let tuning = ScaleOctaveTuning1Byte {
+///     category: Category::RealTime,
+///     device_id: u7::new(0x7F),
+///     channels: 0b1,
+///     offsets: [0, -10, 4, 0, -14, -2, -10, 2, -8, -16, 18, -12],
+/// };
+///
+/// let mut message = sysex7::Sysex7::<[u32; 16]>::new();
+/// message.try_set_payload(tuning.payload()).unwrap();
+/// assert_eq!(ScaleOctaveTuning1Byte::try_from(&message), Ok(tuning));
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ScaleOctaveTuning1Byte {
+    pub category: Category,
+    pub device_id: u7,
+    /// Bit n selects channel n.
+    pub channels: u16,
+    /// Cents from -64 to +63, starting at C.
+    pub offsets: [i8; 12],
+}
+
+impl ScaleOctaveTuning1Byte {
+    pub fn header(&self) -> Header {
+        Header {
+            category: self.category,
+            device_id: self.device_id,
+            sub_id1: u7::new(MIDI_TUNING_STANDARD),
+            sub_id2: u7::new(SCALE_OCTAVE_TUNING_1_BYTE),
+        }
+    }
+
+    /// Offsets out of range are clamped.
+    pub fn payload(&self) -> Payload<SCALE_OCTAVE_TUNING_SIZE> {
+        let mut payload = Payload::default();
+        payload.extend(self.header().payload());
+        payload.extend(write_channels(self.channels));
+        for offset in self.offsets {
+            payload.push(u7::new(
+                (offset.clamp(-64, 63) + SCALE_OCTAVE_1_BYTE_CENTER) as u8,
+            ));
+        }
+        payload
+    }
+
+    pub(crate) fn read<I: Iterator<Item = u7>>(
+        header: &Header,
+        payload: &mut I,
+    ) -> Result<Self, InvalidData> {
+        let channels = read_channels(payload)?;
+        let mut offsets = [0; 12];
+        for offset in offsets.iter_mut() {
+            *offset = u8::from(next(payload)?) as i8 - SCALE_OCTAVE_1_BYTE_CENTER;
+        }
+        Ok(ScaleOctaveTuning1Byte {
+            category: header.category,
+            device_id: header.device_id,
+            channels,
+            offsets,
+        })
+    }
+}
+
+impl<'a, B: crate::buffer::Buffer> TryFrom<&'a Sysex7<B>> for ScaleOctaveTuning1Byte {
+    type Error = InvalidData;
+    fn try_from(message: &'a Sysex7<B>) -> Result<Self, Self::Error> {
+        match UniversalMessage::try_from(message)? {
+            UniversalMessage::ScaleOctaveTuning1Byte(message) => Ok(message),
+            _ => Err(InvalidData(ERR_UNEXPECTED_MESSAGE)),
+        }
+    }
+}
+
+/// Offsets each pitch class of the scale on the selected channels,
+/// in steps of 100/8192 cents.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ScaleOctaveTuning2Byte {
+    pub category: Category,
+    pub device_id: u7,
+    /// Bit n selects channel n.
+    pub channels: u16,
+    /// Steps from -8192 (-100 cents) to +8191 (+100 cents), starting at C.
+    pub offsets: [i16; 12],
+}
+
+impl ScaleOctaveTuning2Byte {
+    const STEPS_PER_CENT: f64 = 8192.0 / 100.0;
+
+    /// The offsets in cents.
+    pub fn cents(&self) -> [f64; 12] {
+        self.offsets
+            .map(|offset| offset as f64 / ScaleOctaveTuning2Byte::STEPS_PER_CENT)
+    }
+
+    pub fn header(&self) -> Header {
+        Header {
+            category: self.category,
+            device_id: self.device_id,
+            sub_id1: u7::new(MIDI_TUNING_STANDARD),
+            sub_id2: u7::new(SCALE_OCTAVE_TUNING_2_BYTE),
+        }
+    }
+
+    /// Offsets out of range are clamped.
+    pub fn payload(&self) -> Payload<SCALE_OCTAVE_TUNING_SIZE> {
+        let mut payload = Payload::default();
+        payload.extend(self.header().payload());
+        payload.extend(write_channels(self.channels));
+        for offset in self.offsets {
+            let value = (offset.clamp(-8192, 8191) + SCALE_OCTAVE_2_BYTE_CENTER) as u16;
+            payload.push(u7::new((value >> 7) as u8));
+            payload.push(u7::new(value as u8 & 0x7F));
+        }
+        payload
+    }
+
+    pub(crate) fn read<I: Iterator<Item = u7>>(
+        header: &Header,
+        payload: &mut I,
+    ) -> Result<Self, InvalidData> {
+        let channels = read_channels(payload)?;
+        let mut offsets = [0; 12];
+        for offset in offsets.iter_mut() {
+            let msb = u16::from(next(payload)?);
+            let lsb = u16::from(next(payload)?);
+            *offset = (msb << 7 | lsb) as i16 - SCALE_OCTAVE_2_BYTE_CENTER;
+        }
+        Ok(ScaleOctaveTuning2Byte {
+            category: header.category,
+            device_id: header.device_id,
+            channels,
+            offsets,
+        })
+    }
+}
+
+impl<'a, B: crate::buffer::Buffer> TryFrom<&'a Sysex7<B>> for ScaleOctaveTuning2Byte {
+    type Error = InvalidData;
+    fn try_from(message: &'a Sysex7<B>) -> Result<Self, Self::Error> {
+        match UniversalMessage::try_from(message)? {
+            UniversalMessage::ScaleOctaveTuning2Byte(message) => Ok(message),
+            _ => Err(InvalidData(ERR_UNEXPECTED_MESSAGE)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::traits::{Data, Sysex};
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn bulk_tuning_dump_request() {
+        let message = Sysex7::try_from(&[0xF0_u8, 0x7E, 0x10, 0x08, 0x00, 0x05, 0xF7][..]).unwrap();
+        assert_eq!(
+            BulkTuningDumpRequest::try_from(&message),
+            Ok(BulkTuningDumpRequest {
+                device_id: u7::new(0x10),
+                bank: None,
+                program: u7::new(0x05),
+            })
+        );
+    }
+
+    #[test]
+    fn bulk_tuning_dump_request_with_bank() {
+        let request = BulkTuningDumpRequest {
+            device_id: u7::new(0x10),
+            bank: Some(u7::new(0x01)),
+            program: u7::new(0x05),
+        };
+        let mut message = Sysex7::<std::vec::Vec<u8>>::new();
+        message.set_payload(request.payload());
+        assert_eq!(
+            message.data(),
+            &[0xF0, 0x7E, 0x10, 0x08, 0x03, 0x01, 0x05, 0xF7]
+        );
+        assert_eq!(BulkTuningDumpRequest::try_from(&message), Ok(request));
+    }
+
+    #[test]
+    fn bulk_tuning_dump_checksum() {
+        let dump = BulkTuningDump::new(u7::new(0x7F), None, u7::new(0x0));
+        let mut message = Sysex7::<std::vec::Vec<u8>>::new();
+        message.set_payload(dump.payload());
+        let data = message.data();
+        assert_eq!(data.len(), 408);
+        assert_eq!(&data[..6], &[0xF0, 0x7E, 0x7F, 0x08, 0x01, 0x00]);
+        assert_eq!(&data[data.len() - 2..], &[0x08, 0xF7]);
+        assert_eq!(tuning_checksum(message.payload().take(405)), u7::new(0x08));
+    }
+
+    #[test]
+    fn bulk_tuning_dump_ump() {
+        let mut dump = BulkTuningDump::new(u7::new(0x7F), Some(u7::new(0x2)), u7::new(0x3));
+        dump.set_frequency(
+            u7::new(0),
+            Some(TuningFrequency {
+                semitone: u7::new(0x0),
+                fraction: u14::new(0x1),
+            }),
+        );
+        dump.set_frequency(
+            u7::new(127),
+            Some(TuningFrequency {
+                semitone: u7::new(0x7F),
+                fraction: u14::new(0x3FFE),
+            }),
+        );
+        assert_eq!(dump.frequency(u7::new(1)), None);
+        let mut message = Sysex7::<std::vec::Vec<u32>>::new();
+        message.set_payload(dump.payload());
+        assert_eq!(&message.data()[..2], &[0x3016_7E7F, 0x0804_0203]);
+        assert_eq!(BulkTuningDump::try_from(&message), Ok(dump));
+    }
+
+    #[test]
+    fn bulk_tuning_dump_bad_checksum() {
+        let mut message = Sysex7::<std::vec::Vec<u8>>::new();
+        message.set_payload(BulkTuningDump::new(u7::new(0x7F), None, u7::new(0x0)).payload());
+        let mut data = message.data().to_vec();
+        data[6] = 0x41;
+        assert_eq!(
+            BulkTuningDump::try_from(&Sysex7::try_from(&data[..]).unwrap()),
+            Err(InvalidData(ERR_CHECKSUM))
+        );
+    }
+
+    #[test]
+    fn single_note_tuning_change_with_bank() {
+        let mut change = SingleNoteTuningChange::new(
+            Category::NonRealTime,
+            u7::new(0x7F),
+            Some(u7::new(0x1)),
+            u7::new(0x2),
+        );
+        change
+            .push(NoteTuning {
+                key: u7::new(60),
+                frequency: Some(TuningFrequency {
+                    semitone: u7::new(60),
+                    fraction: u14::new(0x1000),
+                }),
+            })
+            .unwrap();
+        change
+            .push(NoteTuning {
+                key: u7::new(61),
+                frequency: None,
+            })
+            .unwrap();
+        let mut message = Sysex7::<[u32; 8]>::new();
+        message.try_set_payload(change.payload()).unwrap();
+        assert_eq!(
+            message.data(),
+            &[
+                0x3016_7E7F,
+                0x0807_0102,
+                0x3026_023C,
+                0x3C20_003D,
+                0x3033_7F7F,
+                0x7F00_0000,
+            ]
+        );
+        assert_eq!(SingleNoteTuningChange::try_from(&message), Ok(change));
+        assert_eq!(change.changes().len(), 2);
+    }
+
+    #[test]
+    fn single_note_tuning_change_overflow() {
+        let mut change =
+            SingleNoteTuningChange::new(Category::RealTime, u7::new(0x7F), None, u7::new(0x0));
+        for _ in 0..MAX_NOTE_TUNINGS {
+            change.push(NoteTuning::default()).unwrap();
+        }
+        assert_eq!(change.push(NoteTuning::default()), Err(BufferOverflow));
+    }
+
+    #[test]
+    fn non_real_time_single_note_tuning_change_without_bank() {
+        let message =
+            Sysex7::try_from(&[0xF0_u8, 0x7E, 0x7F, 0x08, 0x02, 0x00, 0x00, 0xF7][..]).unwrap();
+        assert_eq!(
+            SingleNoteTuningChange::try_from(&message),
+            Err(InvalidData(ERR_UNEXPECTED_MESSAGE))
+        );
+    }
+
+    #[test]
+    fn scale_octave_tuning_1_byte() {
+        let mut offsets = [0; 12];
+        offsets[0] = -64;
+        offsets[1] = 63;
+        let tuning = ScaleOctaveTuning1Byte {
+            category: Category::NonRealTime,
+            device_id: u7::new(0x7F),
+            channels: 0b1000_0000_1000_0001,
+            offsets,
+        };
+        let mut message = Sysex7::<std::vec::Vec<u8>>::new();
+        message.set_payload(tuning.payload());
+        assert_eq!(
+            message.data(),
+            &[
+                0xF0, 0x7E, 0x7F, 0x08, 0x08, 0x02, 0x01, 0x01, 0x00, 0x7F, 0x40, 0x40, 0x40, 0x40,
+                0x40, 0x40, 0x40, 0x40, 0x40, 0x40, 0xF7,
+            ]
+        );
+        assert_eq!(ScaleOctaveTuning1Byte::try_from(&message), Ok(tuning));
+    }
+
+    #[test]
+    fn scale_octave_tuning_2_byte() {
+        let mut offsets = [0; 12];
+        offsets[0] = 8191;
+        offsets[1] = -8192;
+        let tuning = ScaleOctaveTuning2Byte {
+            category: Category::RealTime,
+            device_id: u7::new(0x7F),
+            channels: 0xFFFF,
+            offsets,
+        };
+        let mut message = Sysex7::<std::vec::Vec<u8>>::new();
+        message.set_payload(tuning.payload());
+        assert_eq!(
+            &message.data()[..12],
+            &[0xF0, 0x7F, 0x7F, 0x08, 0x09, 0x03, 0x7F, 0x7F, 0x7F, 0x7F, 0x00, 0x00]
+        );
+        assert_eq!(&message.data()[12..14], &[0x40, 0x00]);
+        assert_eq!(ScaleOctaveTuning2Byte::try_from(&message), Ok(tuning));
+        assert_eq!(tuning.cents()[0], 99.98779296875);
+        assert_eq!(tuning.cents()[1], -100.0);
+    }
+
+    #[test]
+    fn fixed7_9_rounds_down() {
+        assert_eq!(
+            crate::num::Fixed7_9::from(TuningFrequency {
+                semitone: u7::new(0x7F),
+                fraction: u14::new(0x3FFF),
+            })
+            .to_bits(),
+            0xFFFF
+        );
+    }
+
+    #[cfg(feature = "channel-voice2")]
+    #[test]
+    fn frequency() {
+        use crate::channel_voice2::Pitch;
+        assert_eq!(
+            TuningFrequency::from_frequency(440.0),
+            TuningFrequency {
+                semitone: u7::new(69),
+                fraction: u14::new(0x0),
+            }
+        );
+        assert!(
+            (TuningFrequency {
+                semitone: u7::new(60),
+                fraction: u14::new(0x0),
+            }
+            .frequency()
+                - 261.625_565)
+                .abs()
+                < 1e-6
+        );
+    }
+
+    #[cfg(feature = "channel-voice2")]
+    #[test]
+    fn saturates_below_no_change() {
+        use crate::channel_voice2::Pitch;
+        assert_eq!(
+            TuningFrequency::from_semitones(200.0).data(),
+            [u7::new(0x7F), u7::new(0x7F), u7::new(0x7E)]
+        );
+    }
+}
@@ -2,6 +2,8 @@
 
 pub(crate) const UMP_MESSAGE_TYPE: u8 = 0x1;
 
+mod quarter_frame;
+mod smpte;
 mod song_position_pointer;
 mod song_select;
 mod time_code;
@@ -177,7 +179,9 @@ mod reset {
 
 pub use active_sensing::*;
 pub use cont::*;
+pub use quarter_frame::*;
 pub use reset::*;
+pub use smpte::*;
 pub use song_position_pointer::*;
 pub use song_select::*;
 pub use start::*;
@@ -190,6 +194,7 @@ pub use tune_request::*;
     derive_more::From,
     midi2_proc::Data,
     midi2_proc::Packets,
+    midi2_proc::Remappable,
     midi2_proc::Grouped,
     midi2_proc::FromBytes,
     midi2_proc::FromUmp,
@@ -0,0 +1,410 @@
+use crate::{
+    buffer::Buffer,
+    system_common::{FrameRate, Smpte, TimeCode},
+    traits::Grouped,
+    ux::{u4, u7},
+};
+
+const PIECES: u8 = 8;
+const LAST_PIECE: u8 = PIECES - 1;
+const ALL_PIECES: u8 = 0xFF;
+// a full sequence of quarter frames spans two frames
+const FRAMES_PER_SEQUENCE: i32 = 2;
+
+/// The direction in which MIDI time code is running.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Direction {
+    #[default]
+    Forward,
+    /// The quarter frames are sent in reverse order, last piece first.
+    Reverse,
+}
+
+/// The eight pieces of a time code address carried by quarter frame messages.
+fn pieces(time: &Smpte) -> [u8; 8] {
+    [
+        time.frames() & 0xF,
+        time.frames() >> 4,
+        time.seconds() & 0xF,
+        time.seconds() >> 4,
+        time.minutes() & 0xF,
+        time.minutes() >> 4,
+        time.hours() & 0xF,
+        (time.hours() >> 4) & 0x1 | time.frame_rate().rate_code() << 1,
+    ]
+}
+
+fn from_pieces(nibbles: &[u8; 8]) -> Option<Smpte> {
+    Smpte::new(
+        nibbles[6] | (nibbles[7] & 0x1) << 4,
+        nibbles[4] | (nibbles[5] & 0x3) << 4,
+        nibbles[2] | (nibbles[3] & 0x3) << 4,
+        nibbles[0] | (nibbles[1] & 0x1) << 4,
+        FrameRate::from_rate_code(nibbles[7] >> 1),
+    )
+    .ok()
+}
+
+/// Assembles the time code address from successive quarter frame messages.
+///
+/// A time is decoded each time all eight pieces have been received in order.
+/// The pieces describe the frame at the start of their sequence, and two frames
+/// have passed by the time the sequence is complete, so the decoded time is
+/// two frames later (or earlier, running in reverse) than the one received.
+///
+/// The decoder is locked once a full sequence has been decoded and stays locked
+/// while the pieces keep arriving in order.
+/// A change of direction restarts the sequence without losing lock,
+/// any other piece out of order or an invalid address loses lock.
+///
+/// ```rust
+/// use midi2::system_common::{FrameRate, QuarterFrameDecoder, QuarterFrameGenerator, Smpte};
+///
+/// let start = Smpte::new(1, 2, 3, 4, FrameRate::Fps25).unwrap();
+/// let mut decoder = QuarterFrameDecoder::new();
+///
+/// let decoded = QuarterFrameGenerator::new(start)
+///     .take(8)
+///     .filter_map(|message| decoder.decode(&message))
+///     .last();
+///
+/// assert_eq!(decoded, Some(start + 2));
+/// assert!(decoder.is_locked());
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct QuarterFrameDecoder {
+    nibbles: [u8; 8],
+    received: u8,
+    last_piece: Option<u8>,
+    direction: Direction,
+    locked: bool,
+    time: Option<Smpte>,
+}
+
+impl QuarterFrameDecoder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Forget the received pieces, the lock and the decoded time.
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    pub fn is_locked(&self) -> bool {
+        self.locked
+    }
+
+    /// The direction of the most recently received pieces.
+    pub fn direction(&self) -> Direction {
+        self.direction
+    }
+
+    /// The most recently decoded time.
+    pub fn time(&self) -> Option<Smpte> {
+        self.time
+    }
+
+    /// Receive the next quarter frame,
+    /// returning the decoded time when it completes a sequence.
+    pub fn decode<B: Buffer>(&mut self, message: &TimeCode<B>) -> Option<Smpte> {
+        let time_code = u8::from(message.time_code());
+        let piece = time_code >> 4;
+        let nibble = time_code & 0xF;
+
+        if let Some(last) = self.last_piece {
+            let direction = if piece == (last + 1) % PIECES {
+                Some(Direction::Forward)
+            } else if piece == (last + LAST_PIECE) % PIECES {
+                Some(Direction::Reverse)
+            } else {
+                None
+            };
+            match direction {
+                Some(direction) if direction != self.direction => {
+                    self.direction = direction;
+                    self.received = 1 << last;
+                }
+                Some(_) => {}
+                None => {
+                    self.locked = false;
+                    self.received = 0;
+                }
+            }
+        }
+        self.last_piece = Some(piece);
+
+        let (first, last, frames) = match self.direction {
+            Direction::Forward => (0, LAST_PIECE, FRAMES_PER_SEQUENCE),
+            Direction::Reverse => (LAST_PIECE, 0, -FRAMES_PER_SEQUENCE),
+        };
+        if piece == first {
+            self.received = 0;
+        }
+        self.nibbles[piece as usize] = nibble;
+        self.received |= 1 << piece;
+        if piece != last || self.received != ALL_PIECES {
+            return None;
+        }
+
+        self.received = 0;
+        let Some(time) = from_pieces(&self.nibbles) else {
+            self.locked = false;
+            return None;
+        };
+        self.locked = true;
+        self.time = Some(time + frames);
+        self.time
+    }
+}
+
+/// Generates the quarter frame messages of a running transport.
+///
+/// The generator is an endless iterator, and the messages should be sent
+/// at intervals of the [quarter frame period](QuarterFrameGenerator::quarter_frame_period).
+/// Each sequence of eight messages describes the frame at its start and
+/// advances the time by two frames, or back by two frames running in reverse.
+///
+/// ```rust
+/// use midi2::{prelude::*, system_common::{FrameRate, QuarterFrameGenerator, Smpte}};
+///
+/// let mut generator = QuarterFrameGenerator::new(
+///     Smpte::new(0, 0, 10, 5, FrameRate::Fps30).unwrap(),
+/// );
+///
+/// let pieces = generator.by_ref().take(8).map(|m| m.data()[0]).collect::<Vec<_>>();
+/// assert_eq!(
+///     pieces,
+///     [
+///         0x10F1_0500, 0x10F1_1000, 0x10F1_2A00, 0x10F1_3000,
+///         0x10F1_4000, 0x10F1_5000, 0x10F1_6000, 0x10F1_7600,
+///     ],
+/// );
+/// assert_eq!(generator.time(), Smpte::new(0, 0, 10, 7, FrameRate::Fps30).unwrap());
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct QuarterFrameGenerator {
+    time: Smpte,
+    piece: u8,
+    direction: Direction,
+    group: u4,
+}
+
+impl QuarterFrameGenerator {
+    /// Start running forwards from the given time.
+    pub fn new(time: Smpte) -> Self {
+        Self {
+            time,
+            piece: 0,
+            direction: Direction::Forward,
+            group: u4::new(0x0),
+        }
+    }
+
+    /// The time described by the current sequence.
+    pub fn time(&self) -> Smpte {
+        self.time
+    }
+
+    /// Jump to the given time, starting a new sequence.
+    pub fn locate(&mut self, time: Smpte) {
+        self.time = time;
+        self.piece = match self.direction {
+            Direction::Forward => 0,
+            Direction::Reverse => LAST_PIECE,
+        };
+    }
+
+    pub fn direction(&self) -> Direction {
+        self.direction
+    }
+
+    /// Change direction, continuing from the piece last sent.
+    pub fn set_direction(&mut self, direction: Direction) {
+        if direction == self.direction {
+            return;
+        }
+        self.direction = direction;
+        self.piece = match direction {
+            Direction::Forward => (self.piece + 2) % PIECES,
+            Direction::Reverse => (self.piece + PIECES - 2) % PIECES,
+        };
+    }
+
+    pub fn group(&self) -> u4 {
+        self.group
+    }
+
+    pub fn set_group(&mut self, group: u4) {
+        self.group = group;
+    }
+
+    /// The time in seconds between successive quarter frame messages.
+    pub fn quarter_frame_period(&self) -> f64 {
+        1.0 / (4.0 * self.time.frame_rate().frequency())
+    }
+}
+
+impl core::iter::Iterator for QuarterFrameGenerator {
+    type Item = TimeCode<[u32; 4]>;
+    fn next(&mut self) -> Option<Self::Item> {
+        let piece = self.piece;
+        let mut message = TimeCode::<[u32; 4]>::new();
+        message.set_group(self.group);
+        message.set_time_code(u7::new(piece << 4 | pieces(&self.time)[piece as usize]));
+
+        match self.direction {
+            Direction::Forward if piece == LAST_PIECE => {
+                self.piece = 0;
+                self.time += FRAMES_PER_SEQUENCE;
+            }
+            Direction::Forward => self.piece += 1,
+            Direction::Reverse if piece == 0 => {
+                self.piece = LAST_PIECE;
+                self.time -= FRAMES_PER_SEQUENCE;
+            }
+            Direction::Reverse => self.piece -= 1,
+        }
+        Some(message)
+    }
+}
+
+impl core::iter::FusedIterator for QuarterFrameGenerator {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::vec::Vec;
+
+    fn quarter_frame(piece: u8, nibble: u8) -> TimeCode<[u32; 4]> {
+        let mut message = TimeCode::<[u32; 4]>::new();
+        message.set_time_code(u7::new(piece << 4 | nibble));
+        message
+    }
+
+    fn smpte(h: u8, m: u8, s: u8, f: u8, rate: FrameRate) -> Smpte {
+        Smpte::new(h, m, s, f, rate).unwrap()
+    }
+
+    #[test]
+    fn pieces_round_trip() {
+        let time = smpte(23, 59, 58, 28, FrameRate::Fps29_97DropFrame);
+        assert_eq!(from_pieces(&pieces(&time)), Some(time));
+    }
+
+    #[test]
+    fn decodes_generated_sequences() {
+        let start = smpte(10, 20, 30, 20, FrameRate::Fps24);
+        let mut decoder = QuarterFrameDecoder::new();
+        let decoded = QuarterFrameGenerator::new(start)
+            .take(32)
+            .filter_map(|m| decoder.decode(&m))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            decoded,
+            std::vec![start + 2, start + 4, start + 6, start + 8]
+        );
+    }
+
+    #[test]
+    fn not_locked_until_a_full_sequence() {
+        let mut decoder = QuarterFrameDecoder::new();
+        let mut generator = QuarterFrameGenerator::new(smpte(0, 0, 0, 0, FrameRate::Fps30));
+        generator.nth(2);
+        for message in generator.by_ref().take(5) {
+            assert_eq!(decoder.decode(&message), None);
+        }
+        assert!(!decoder.is_locked());
+        let decoded = generator
+            .take(8)
+            .filter_map(|m| decoder.decode(&m))
+            .collect::<Vec<_>>();
+        assert_eq!(decoded, std::vec![smpte(0, 0, 0, 4, FrameRate::Fps30)]);
+        assert!(decoder.is_locked());
+    }
+
+    #[test]
+    fn reverse() {
+        let start = smpte(0, 0, 1, 0, FrameRate::Fps25);
+        let mut generator = QuarterFrameGenerator::new(start);
+        generator.set_direction(Direction::Reverse);
+        generator.locate(start);
+        let mut decoder = QuarterFrameDecoder::new();
+        let decoded = generator
+            .take(16)
+            .filter_map(|m| decoder.decode(&m))
+            .collect::<Vec<_>>();
+        assert_eq!(decoded, std::vec![start - 2, start - 4]);
+        assert_eq!(decoder.direction(), Direction::Reverse);
+    }
+
+    #[test]
+    fn direction_reversal_keeps_lock() {
+        let start = smpte(0, 0, 1, 0, FrameRate::Fps25);
+        let mut generator = QuarterFrameGenerator::new(start);
+        let mut decoder = QuarterFrameDecoder::new();
+        for message in generator.by_ref().take(12) {
+            decoder.decode(&message);
+        }
+        assert!(decoder.is_locked());
+
+        // the pieces of the interrupted sequence are sent back to its start,
+        // then a full sequence for the frames before it
+        generator.set_direction(Direction::Reverse);
+        let decoded = generator
+            .take(11)
+            .filter_map(|m| decoder.decode(&m))
+            .collect::<Vec<_>>();
+        assert!(decoder.is_locked());
+        assert_eq!(decoder.direction(), Direction::Reverse);
+        assert_eq!(decoded, std::vec![start - 2]);
+    }
+
+    #[test]
+    fn out_of_order_piece_loses_lock() {
+        let mut decoder = QuarterFrameDecoder::new();
+        for message in QuarterFrameGenerator::new(smpte(0, 0, 0, 0, FrameRate::Fps30)).take(8) {
+            decoder.decode(&message);
+        }
+        assert!(decoder.is_locked());
+        assert_eq!(decoder.decode(&quarter_frame(3, 0)), None);
+        assert!(!decoder.is_locked());
+        assert_eq!(decoder.time(), Some(smpte(0, 0, 0, 2, FrameRate::Fps30)));
+    }
+
+    #[test]
+    fn invalid_address_loses_lock() {
+        let mut decoder = QuarterFrameDecoder::new();
+        // 30 frames at 24 fps
+        let nibbles = [0xE, 0x1, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0];
+        let decoded = nibbles
+            .iter()
+            .enumerate()
+            .filter_map(|(piece, nibble)| decoder.decode(&quarter_frame(piece as u8, *nibble)))
+            .count();
+        assert_eq!(decoded, 0);
+        assert!(!decoder.is_locked());
+    }
+
+    #[test]
+    fn generator_wraps_pieces_and_time() {
+        let mut generator = QuarterFrameGenerator::new(smpte(23, 59, 59, 29, FrameRate::Fps30));
+        generator.nth(7);
+        assert_eq!(generator.time(), smpte(0, 0, 0, 1, FrameRate::Fps30));
+        assert_eq!(u8::from(generator.next().unwrap().time_code()) >> 4, 0);
+    }
+
+    #[test]
+    fn generator_group() {
+        let mut generator = QuarterFrameGenerator::new(Smpte::default());
+        generator.set_group(u4::new(0x7));
+        assert_eq!(generator.next().unwrap().group(), u4::new(0x7));
+    }
+
+    #[test]
+    fn quarter_frame_period() {
+        let generator = QuarterFrameGenerator::new(smpte(0, 0, 0, 0, FrameRate::Fps25));
+        assert_eq!(generator.quarter_frame_period(), 0.01);
+    }
+}
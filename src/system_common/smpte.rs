@@ -0,0 +1,389 @@
+use crate::error::InvalidData;
+
+const ERR_HOURS_OUT_OF_RANGE: &str = "Hours out of range";
+const ERR_MINUTES_OUT_OF_RANGE: &str = "Minutes out of range";
+const ERR_SECONDS_OUT_OF_RANGE: &str = "Seconds out of range";
+const ERR_FRAMES_OUT_OF_RANGE: &str = "Frames out of range";
+const ERR_DROPPED_FRAME: &str = "Frame is dropped in drop frame time code";
+
+const HOURS_PER_DAY: u32 = 24;
+const MINUTES_PER_HOUR: u32 = 60;
+const SECONDS_PER_MINUTE: u32 = 60;
+const DROPPED_FRAMES_PER_MINUTE: u32 = 2;
+// frames in ten minutes of drop frame time code, during which nine minutes drop frames
+const DROP_FRAME_FRAMES_PER_TEN_MINUTES: u32 = 10 * 60 * 30 - 9 * DROPPED_FRAMES_PER_MINUTE;
+const DROP_FRAME_FRAMES_PER_MINUTE: u32 = 60 * 30 - DROPPED_FRAMES_PER_MINUTE;
+
+/// The frame rates of SMPTE time code, with the rate codes used by MIDI time code.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum FrameRate {
+    Fps24,
+    Fps25,
+    /// 30000/1001 frames per second, with frames 0 and 1 dropped from the count
+    /// at the start of every minute which isn't a multiple of ten.
+    Fps29_97DropFrame,
+    #[default]
+    Fps30,
+}
+
+impl FrameRate {
+    /// The number of frames counted in each second of time code.
+    pub fn frames_per_second(&self) -> u8 {
+        match self {
+            FrameRate::Fps24 => 24,
+            FrameRate::Fps25 => 25,
+            FrameRate::Fps29_97DropFrame => 30,
+            FrameRate::Fps30 => 30,
+        }
+    }
+
+    /// The number of frames which actually pass in a second.
+    pub fn frequency(&self) -> f64 {
+        match self {
+            FrameRate::Fps29_97DropFrame => 30000.0 / 1001.0,
+            _ => self.frames_per_second() as f64,
+        }
+    }
+
+    /// The two bit rate code of MIDI time code.
+    pub fn rate_code(&self) -> u8 {
+        match self {
+            FrameRate::Fps24 => 0,
+            FrameRate::Fps25 => 1,
+            FrameRate::Fps29_97DropFrame => 2,
+            FrameRate::Fps30 => 3,
+        }
+    }
+
+    /// Only the two least significant bits of the code are read.
+    pub fn from_rate_code(code: u8) -> Self {
+        match code & 0b11 {
+            0 => FrameRate::Fps24,
+            1 => FrameRate::Fps25,
+            2 => FrameRate::Fps29_97DropFrame,
+            _ => FrameRate::Fps30,
+        }
+    }
+
+    /// The number of frames in the 24 hours of time code.
+    pub fn frames_per_day(&self) -> u32 {
+        match self {
+            FrameRate::Fps29_97DropFrame => {
+                HOURS_PER_DAY * MINUTES_PER_HOUR / 10 * DROP_FRAME_FRAMES_PER_TEN_MINUTES
+            }
+            _ => {
+                HOURS_PER_DAY
+                    * MINUTES_PER_HOUR
+                    * SECONDS_PER_MINUTE
+                    * self.frames_per_second() as u32
+            }
+        }
+    }
+}
+
+/// A SMPTE time code address.
+///
+/// Arithmetic is in frames and wraps around at 24 hours.
+///
+/// ```rust
+/// use midi2::system_common::{FrameRate, Smpte};
+///
+/// let time = Smpte::new(0, 0, 59, 29, FrameRate::Fps29_97DropFrame).unwrap();
+/// assert_eq!(
+///     time + 1,
+///     Smpte::new(0, 1, 0, 2, FrameRate::Fps29_97DropFrame).unwrap(),
+/// );
+///
+/// let time = Smpte::from_seconds(90.5, FrameRate::Fps25);
+/// assert_eq!(time, Smpte::new(0, 1, 30, 12, FrameRate::Fps25).unwrap());
+/// assert_eq!(time.to_seconds(), 90.48);
+/// ```
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Smpte {
+    hours: u8,
+    minutes: u8,
+    seconds: u8,
+    frames: u8,
+    frame_rate: FrameRate,
+}
+
+impl Smpte {
+    pub fn new(
+        hours: u8,
+        minutes: u8,
+        seconds: u8,
+        frames: u8,
+        frame_rate: FrameRate,
+    ) -> Result<Self, InvalidData> {
+        if u32::from(hours) >= HOURS_PER_DAY {
+            return Err(InvalidData(ERR_HOURS_OUT_OF_RANGE));
+        }
+        if u32::from(minutes) >= MINUTES_PER_HOUR {
+            return Err(InvalidData(ERR_MINUTES_OUT_OF_RANGE));
+        }
+        if u32::from(seconds) >= SECONDS_PER_MINUTE {
+            return Err(InvalidData(ERR_SECONDS_OUT_OF_RANGE));
+        }
+        if frames >= frame_rate.frames_per_second() {
+            return Err(InvalidData(ERR_FRAMES_OUT_OF_RANGE));
+        }
+        if frame_rate == FrameRate::Fps29_97DropFrame
+            && seconds == 0
+            && !minutes.is_multiple_of(10)
+            && u32::from(frames) < DROPPED_FRAMES_PER_MINUTE
+        {
+            return Err(InvalidData(ERR_DROPPED_FRAME));
+        }
+        Ok(Self {
+            hours,
+            minutes,
+            seconds,
+            frames,
+            frame_rate,
+        })
+    }
+
+    pub fn hours(&self) -> u8 {
+        self.hours
+    }
+
+    pub fn minutes(&self) -> u8 {
+        self.minutes
+    }
+
+    pub fn seconds(&self) -> u8 {
+        self.seconds
+    }
+
+    pub fn frames(&self) -> u8 {
+        self.frames
+    }
+
+    pub fn frame_rate(&self) -> FrameRate {
+        self.frame_rate
+    }
+
+    /// The number of frames since midnight.
+    pub fn frame_count(&self) -> u32 {
+        let fps = self.frame_rate.frames_per_second() as u32;
+        let total_minutes = u32::from(self.hours) * MINUTES_PER_HOUR + u32::from(self.minutes);
+        let count = (total_minutes * SECONDS_PER_MINUTE + u32::from(self.seconds)) * fps
+            + u32::from(self.frames);
+        match self.frame_rate {
+            FrameRate::Fps29_97DropFrame => {
+                count - DROPPED_FRAMES_PER_MINUTE * (total_minutes - total_minutes / 10)
+            }
+            _ => count,
+        }
+    }
+
+    /// The time the given number of frames after midnight,
+    /// wrapping around at 24 hours.
+    pub fn from_frame_count(count: u32, frame_rate: FrameRate) -> Self {
+        let mut count = count % frame_rate.frames_per_day();
+        if frame_rate == FrameRate::Fps29_97DropFrame {
+            let tens = count / DROP_FRAME_FRAMES_PER_TEN_MINUTES;
+            let remainder = count % DROP_FRAME_FRAMES_PER_TEN_MINUTES;
+            let dropped_minutes =
+                remainder.saturating_sub(DROPPED_FRAMES_PER_MINUTE) / DROP_FRAME_FRAMES_PER_MINUTE;
+            count += DROPPED_FRAMES_PER_MINUTE * (9 * tens + dropped_minutes);
+        }
+        let fps = frame_rate.frames_per_second() as u32;
+        let frames = count % fps;
+        let total_seconds = count / fps;
+        Self {
+            hours: (total_seconds / (MINUTES_PER_HOUR * SECONDS_PER_MINUTE)) as u8,
+            minutes: (total_seconds / SECONDS_PER_MINUTE % MINUTES_PER_HOUR) as u8,
+            seconds: (total_seconds % SECONDS_PER_MINUTE) as u8,
+            frames: frames as u8,
+            frame_rate,
+        }
+    }
+
+    /// The real time since midnight in seconds.
+    pub fn to_seconds(&self) -> f64 {
+        self.frame_count() as f64 / self.frame_rate.frequency()
+    }
+
+    /// The time of the frame running at the given real time since midnight,
+    /// wrapping around at 24 hours. Negative times are clamped to midnight.
+    pub fn from_seconds(seconds: f64, frame_rate: FrameRate) -> Self {
+        // guard against the frame boundaries landing just below a whole number
+        let count = seconds * frame_rate.frequency() + 1e-6;
+        let count = if count > 0.0 { count as u64 } else { 0 };
+        Self::from_frame_count(
+            (count % frame_rate.frames_per_day() as u64) as u32,
+            frame_rate,
+        )
+    }
+
+    /// The same instant of time expressed at another frame rate,
+    /// truncated to the frame running at that instant.
+    pub fn with_frame_rate(&self, frame_rate: FrameRate) -> Self {
+        if frame_rate == self.frame_rate {
+            return *self;
+        }
+        Self::from_seconds(self.to_seconds(), frame_rate)
+    }
+}
+
+impl core::ops::Add<i32> for Smpte {
+    type Output = Smpte;
+    fn add(self, frames: i32) -> Self::Output {
+        let per_day = self.frame_rate.frames_per_day() as i64;
+        let count = (self.frame_count() as i64 + frames as i64).rem_euclid(per_day);
+        Smpte::from_frame_count(count as u32, self.frame_rate)
+    }
+}
+
+impl core::ops::Sub<i32> for Smpte {
+    type Output = Smpte;
+    fn sub(self, frames: i32) -> Self::Output {
+        self + -frames
+    }
+}
+
+impl core::ops::AddAssign<i32> for Smpte {
+    fn add_assign(&mut self, frames: i32) {
+        *self = *self + frames;
+    }
+}
+
+impl core::ops::SubAssign<i32> for Smpte {
+    fn sub_assign(&mut self, frames: i32) {
+        *self = *self - frames;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn smpte(h: u8, m: u8, s: u8, f: u8, rate: FrameRate) -> Smpte {
+        Smpte::new(h, m, s, f, rate).unwrap()
+    }
+
+    #[test]
+    fn invalid_fields() {
+        assert_eq!(
+            Smpte::new(24, 0, 0, 0, FrameRate::Fps30),
+            Err(InvalidData(ERR_HOURS_OUT_OF_RANGE))
+        );
+        assert_eq!(
+            Smpte::new(0, 0, 0, 24, FrameRate::Fps24),
+            Err(InvalidData(ERR_FRAMES_OUT_OF_RANGE))
+        );
+        assert_eq!(
+            Smpte::new(0, 1, 0, 1, FrameRate::Fps29_97DropFrame),
+            Err(InvalidData(ERR_DROPPED_FRAME))
+        );
+        assert!(Smpte::new(0, 10, 0, 0, FrameRate::Fps29_97DropFrame).is_ok());
+    }
+
+    #[test]
+    fn frame_count() {
+        assert_eq!(smpte(1, 0, 0, 0, FrameRate::Fps25).frame_count(), 90000);
+        assert_eq!(
+            smpte(0, 1, 0, 2, FrameRate::Fps29_97DropFrame).frame_count(),
+            1800
+        );
+        assert_eq!(
+            smpte(0, 10, 0, 0, FrameRate::Fps29_97DropFrame).frame_count(),
+            17982
+        );
+        assert_eq!(
+            smpte(1, 0, 0, 0, FrameRate::Fps29_97DropFrame).frame_count(),
+            107892
+        );
+    }
+
+    #[test]
+    fn drop_frame_round_trip() {
+        for count in (0..FrameRate::Fps29_97DropFrame.frames_per_day()).step_by(7) {
+            let time = Smpte::from_frame_count(count, FrameRate::Fps29_97DropFrame);
+            assert_eq!(
+                Smpte::new(
+                    time.hours(),
+                    time.minutes(),
+                    time.seconds(),
+                    time.frames(),
+                    time.frame_rate()
+                ),
+                Ok(time)
+            );
+            assert_eq!(time.frame_count(), count);
+        }
+    }
+
+    #[test]
+    fn drop_frame_skips_frames() {
+        assert_eq!(
+            smpte(0, 9, 59, 29, FrameRate::Fps29_97DropFrame) + 1,
+            smpte(0, 10, 0, 0, FrameRate::Fps29_97DropFrame)
+        );
+        assert_eq!(
+            smpte(0, 11, 0, 2, FrameRate::Fps29_97DropFrame) - 1,
+            smpte(0, 10, 59, 29, FrameRate::Fps29_97DropFrame)
+        );
+    }
+
+    #[test]
+    fn wraps_at_midnight() {
+        assert_eq!(
+            smpte(23, 59, 59, 23, FrameRate::Fps24) + 1,
+            smpte(0, 0, 0, 0, FrameRate::Fps24)
+        );
+        assert_eq!(
+            smpte(0, 0, 0, 0, FrameRate::Fps30) - 1,
+            smpte(23, 59, 59, 29, FrameRate::Fps30)
+        );
+    }
+
+    #[test]
+    fn assign_operators() {
+        let mut time = smpte(0, 0, 0, 0, FrameRate::Fps25);
+        time += 50;
+        assert_eq!(time, smpte(0, 0, 2, 0, FrameRate::Fps25));
+        time -= 26;
+        assert_eq!(time, smpte(0, 0, 0, 24, FrameRate::Fps25));
+    }
+
+    #[test]
+    fn seconds() {
+        assert_eq!(smpte(0, 0, 1, 12, FrameRate::Fps24).to_seconds(), 1.5);
+        assert_eq!(
+            Smpte::from_seconds(3600.0, FrameRate::Fps30),
+            smpte(1, 0, 0, 0, FrameRate::Fps30)
+        );
+        // an hour of drop frame time code is an hour of real time, to within a frame
+        assert_eq!(
+            Smpte::from_seconds(3600.0, FrameRate::Fps29_97DropFrame),
+            smpte(1, 0, 0, 0, FrameRate::Fps29_97DropFrame)
+        );
+        assert_eq!(
+            Smpte::from_seconds(-1.0, FrameRate::Fps30),
+            smpte(0, 0, 0, 0, FrameRate::Fps30)
+        );
+    }
+
+    #[test]
+    fn with_frame_rate() {
+        assert_eq!(
+            smpte(0, 0, 1, 12, FrameRate::Fps24).with_frame_rate(FrameRate::Fps30),
+            smpte(0, 0, 1, 15, FrameRate::Fps30)
+        );
+    }
+
+    #[test]
+    fn rate_codes() {
+        for rate in [
+            FrameRate::Fps24,
+            FrameRate::Fps25,
+            FrameRate::Fps29_97DropFrame,
+            FrameRate::Fps30,
+        ] {
+            assert_eq!(FrameRate::from_rate_code(rate.rate_code()), rate);
+        }
+    }
+}
@@ -63,6 +63,40 @@ pub trait Channeled<B: Buffer> {
         B: BufferMut;
 }
 
+/// Move a message to other groups and channels according to a [Remap](crate::router::Remap).
+///
+/// Every packet of a multi-packet message is rewritten.
+/// Messages without a group or channel are left untouched.
+///
+/// ```rust
+/// use midi2::{prelude::*, router::Remap};
+///
+/// let mut remap = Remap::new();
+/// remap.set_group(u4::new(0x0), u4::new(0x3));
+/// remap.set_channel(u4::new(0x1), u4::new(0x9));
+///
+/// let mut message = channel_voice2::NoteOn::<[u32; 4]>::new();
+/// message.set_channel(u4::new(0x1));
+/// message.remap(&remap);
+///
+/// assert_eq!(message.data(), &[0x4399_0000, 0x0000_0000]);
+/// ```
+#[cfg(any(
+    feature = "channel-voice1",
+    feature = "channel-voice2",
+    feature = "flex-data",
+    feature = "sysex7",
+    feature = "sysex8",
+    feature = "system-common",
+    feature = "ump-stream",
+    feature = "utility"
+))]
+pub trait Remappable<B: Ump> {
+    fn remap(&mut self, remap: &crate::router::Remap)
+    where
+        B: BufferMut;
+}
+
 /// Convert a generic message from one [buffer](crate::buffer) specialisation to another.
 ///
 /// ```rust
@@ -0,0 +1,394 @@
+//! Translation of channel voice messages between the MIDI 1.0 and MIDI 2.0 protocols.
+//!
+//! Values are scaled up with the min-center-max algorithm recommended by the
+//! UMP specification, and scaled down by discarding the least significant bits.
+//!
+//! ```rust
+//! use midi2::{prelude::*, translation};
+//!
+//! let mut message = channel_voice1::NoteOn::<[u32; 4]>::new();
+//! message.set_note_number(u7::new(0x3C));
+//! message.set_velocity(u7::new(0x7F));
+//!
+//! let channel_voice2::ChannelVoice2::NoteOn(translated) = translation::to_midi2(&message.into()) else {
+//!     panic!("Expected a note on");
+//! };
+//! assert_eq!(translated.velocity(), 0xFFFF);
+//! ```
+
+use crate::{
+    channel_voice1,
+    channel_voice1::ChannelVoice1,
+    channel_voice2,
+    channel_voice2::ChannelVoice2,
+    traits::{Channeled, Grouped},
+    ux::{u14, u4, u7},
+};
+
+const BANK_SELECT_MSB: u8 = 0;
+const DATA_ENTRY_MSB: u8 = 6;
+const BANK_SELECT_LSB: u8 = 32;
+const DATA_ENTRY_LSB: u8 = 38;
+const NRPN_LSB: u8 = 98;
+const NRPN_MSB: u8 = 99;
+const RPN_LSB: u8 = 100;
+const RPN_MSB: u8 = 101;
+
+/// The velocity given to the note off produced from a MIDI 1.0 note on with zero velocity,
+/// matching the MIDI 1.0 default release velocity of 64.
+const DEFAULT_NOTE_OFF_VELOCITY: u16 = 0x8000;
+
+/// Scale a value to a larger bit width using the min-center-max algorithm.
+///
+/// The minimum, center and maximum values of the source range
+/// map exactly onto those of the destination range.
+pub fn scale_up(value: u32, source_bits: u32, destination_bits: u32) -> u32 {
+    debug_assert!(0 < source_bits && source_bits <= destination_bits && destination_bits <= 32);
+    let scale_bits = destination_bits - source_bits;
+    if scale_bits == 0 {
+        return value;
+    }
+    let mut shifted = value << scale_bits;
+    let center = 1 << (source_bits - 1);
+    if value <= center {
+        return shifted;
+    }
+    let repeat_bits = source_bits - 1;
+    if repeat_bits == 0 {
+        return shifted | ((1 << scale_bits) - 1);
+    }
+    let repeat_mask = (1 << repeat_bits) - 1;
+    let mut repeat = value & repeat_mask;
+    if scale_bits > repeat_bits {
+        repeat <<= scale_bits - repeat_bits;
+    } else {
+        repeat >>= repeat_bits - scale_bits;
+    }
+    while repeat != 0 {
+        shifted |= repeat;
+        repeat >>= repeat_bits;
+    }
+    shifted
+}
+
+/// Scale a value to a smaller bit width by discarding the least significant bits.
+pub fn scale_down(value: u32, source_bits: u32, destination_bits: u32) -> u32 {
+    debug_assert!(destination_bits <= source_bits && source_bits <= 32);
+    value >> (source_bits - destination_bits)
+}
+
+/// Translate a MIDI 1.0 channel voice message into its MIDI 2.0 equivalent.
+///
+/// Note on messages with zero velocity become note off messages.
+/// Control changes are translated one to one; bank select and (N)RPN
+/// sequences are not assembled into their MIDI 2.0 counterparts.
+pub fn to_midi2<B: crate::buffer::Ump>(message: &ChannelVoice1<B>) -> ChannelVoice2<[u32; 4]> {
+    let group = message.group();
+    let channel = message.channel();
+    let mut translated: ChannelVoice2<[u32; 4]> = match message {
+        ChannelVoice1::NoteOn(m) if m.velocity() == u7::new(0) => {
+            let mut t = channel_voice2::NoteOff::<[u32; 4]>::new();
+            t.set_note_number(m.note_number());
+            t.set_velocity(DEFAULT_NOTE_OFF_VELOCITY);
+            t.into()
+        }
+        ChannelVoice1::NoteOn(m) => {
+            let mut t = channel_voice2::NoteOn::<[u32; 4]>::new();
+            t.set_note_number(m.note_number());
+            t.set_velocity(scale_up(u8::from(m.velocity()).into(), 7, 16) as u16);
+            t.into()
+        }
+        ChannelVoice1::NoteOff(m) => {
+            let mut t = channel_voice2::NoteOff::<[u32; 4]>::new();
+            t.set_note_number(m.note_number());
+            t.set_velocity(scale_up(u8::from(m.velocity()).into(), 7, 16) as u16);
+            t.into()
+        }
+        ChannelVoice1::KeyPressure(m) => {
+            let mut t = channel_voice2::KeyPressure::<[u32; 4]>::new();
+            t.set_note_number(m.note_number());
+            t.set_key_pressure_data(scale_up(u8::from(m.pressure()).into(), 7, 32));
+            t.into()
+        }
+        ChannelVoice1::ControlChange(m) => {
+            let mut t = channel_voice2::ControlChange::<[u32; 4]>::new();
+            t.set_control(m.control());
+            t.set_control_change_data(scale_up(u8::from(m.control_data()).into(), 7, 32));
+            t.into()
+        }
+        ChannelVoice1::ProgramChange(m) => {
+            let mut t = channel_voice2::ProgramChange::<[u32; 4]>::new();
+            t.set_program(m.program());
+            t.into()
+        }
+        ChannelVoice1::ChannelPressure(m) => {
+            let mut t = channel_voice2::ChannelPressure::<[u32; 4]>::new();
+            t.set_channel_pressure_data(scale_up(u8::from(m.pressure()).into(), 7, 32));
+            t.into()
+        }
+        ChannelVoice1::PitchBend(m) => {
+            let mut t = channel_voice2::ChannelPitchBend::<[u32; 4]>::new();
+            t.set_pitch_bend_data(scale_up(u16::from(m.bend()).into(), 14, 32));
+            t.into()
+        }
+    };
+    translated.set_group(group);
+    translated.set_channel(channel);
+    translated
+}
+
+/// Translate a MIDI 2.0 channel voice message into the MIDI 1.0 messages
+/// which best represent it.
+///
+/// * Program changes carrying a bank are preceded by bank select control changes.
+/// * Registered and assignable controllers become (N)RPN control change sequences.
+/// * Note on velocities which would scale to zero are sent with a velocity of one.
+/// * Per-note and relative messages have no MIDI 1.0 equivalent and yield nothing.
+pub fn to_midi1<B: crate::buffer::Ump>(message: &ChannelVoice2<B>) -> Midi1Messages {
+    let group = message.group();
+    let channel = message.channel();
+    let mut messages = Midi1Messages::default();
+    match message {
+        ChannelVoice2::NoteOn(m) => {
+            let mut t = channel_voice1::NoteOn::<[u32; 4]>::new();
+            t.set_note_number(m.note_number());
+            let velocity = scale_down(m.velocity().into(), 16, 7).max(1);
+            t.set_velocity(u7::new(velocity as u8));
+            messages.push(t.into());
+        }
+        ChannelVoice2::NoteOff(m) => {
+            let mut t = channel_voice1::NoteOff::<[u32; 4]>::new();
+            t.set_note_number(m.note_number());
+            t.set_velocity(u7::new(scale_down(m.velocity().into(), 16, 7) as u8));
+            messages.push(t.into());
+        }
+        ChannelVoice2::KeyPressure(m) => {
+            let mut t = channel_voice1::KeyPressure::<[u32; 4]>::new();
+            t.set_note_number(m.note_number());
+            t.set_pressure(u7::new(scale_down(m.key_pressure_data(), 32, 7) as u8));
+            messages.push(t.into());
+        }
+        ChannelVoice2::ControlChange(m) => {
+            messages.push_control_change(
+                u8::from(m.control()),
+                u7::new(scale_down(m.control_change_data(), 32, 7) as u8),
+            );
+        }
+        ChannelVoice2::ProgramChange(m) => {
+            if let Some(bank) = m.bank() {
+                let bank = u16::from(bank);
+                messages.push_control_change(BANK_SELECT_MSB, u7::new((bank >> 7) as u8));
+                messages.push_control_change(BANK_SELECT_LSB, u7::new((bank & 0x7F) as u8));
+            }
+            let mut t = channel_voice1::ProgramChange::<[u32; 4]>::new();
+            t.set_program(m.program());
+            messages.push(t.into());
+        }
+        ChannelVoice2::ChannelPressure(m) => {
+            let mut t = channel_voice1::ChannelPressure::<[u32; 4]>::new();
+            t.set_pressure(u7::new(scale_down(m.channel_pressure_data(), 32, 7) as u8));
+            messages.push(t.into());
+        }
+        ChannelVoice2::ChannelPitchBend(m) => {
+            let mut t = channel_voice1::PitchBend::<[u32; 4]>::new();
+            t.set_bend(u14::new(scale_down(m.pitch_bend_data(), 32, 14) as u16));
+            messages.push(t.into());
+        }
+        ChannelVoice2::RegisteredController(m) => {
+            messages.push_parameter(RPN_MSB, RPN_LSB, m.bank(), m.index(), m.controller_data());
+        }
+        ChannelVoice2::AssignableController(m) => {
+            messages.push_parameter(NRPN_MSB, NRPN_LSB, m.bank(), m.index(), m.controller_data());
+        }
+        _ => {}
+    }
+    messages.set_address(group, channel);
+    messages
+}
+
+/// The MIDI 1.0 messages produced by [to_midi1].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Midi1Messages {
+    messages: [Option<ChannelVoice1<[u32; 4]>>; 4],
+    len: usize,
+    index: usize,
+}
+
+impl Midi1Messages {
+    fn push(&mut self, message: ChannelVoice1<[u32; 4]>) {
+        self.messages[self.len] = Some(message);
+        self.len += 1;
+    }
+
+    fn push_control_change(&mut self, control: u8, data: u7) {
+        let mut message = channel_voice1::ControlChange::<[u32; 4]>::new();
+        message.set_control(u7::new(control));
+        message.set_control_data(data);
+        self.push(message.into());
+    }
+
+    fn push_parameter(&mut self, msb: u8, lsb: u8, bank: u7, index: u7, data: u32) {
+        let data = scale_down(data, 32, 14);
+        self.push_control_change(msb, bank);
+        self.push_control_change(lsb, index);
+        self.push_control_change(DATA_ENTRY_MSB, u7::new((data >> 7) as u8));
+        self.push_control_change(DATA_ENTRY_LSB, u7::new((data & 0x7F) as u8));
+    }
+
+    fn set_address(&mut self, group: u4, channel: u4) {
+        for message in self.messages.iter_mut().flatten() {
+            message.set_group(group);
+            message.set_channel(channel);
+        }
+    }
+}
+
+impl core::iter::Iterator for Midi1Messages {
+    type Item = ChannelVoice1<[u32; 4]>;
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.index == self.len {
+            return None;
+        }
+        let message = self.messages[self.index].take();
+        self.index += 1;
+        message
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::traits::Data;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn scale_up_7_to_16() {
+        assert_eq!(scale_up(0x00, 7, 16), 0x0000);
+        assert_eq!(scale_up(0x40, 7, 16), 0x8000);
+        assert_eq!(scale_up(0x7F, 7, 16), 0xFFFF);
+    }
+
+    #[test]
+    fn scale_up_7_to_32() {
+        assert_eq!(scale_up(0x00, 7, 32), 0x0000_0000);
+        assert_eq!(scale_up(0x40, 7, 32), 0x8000_0000);
+        assert_eq!(scale_up(0x7F, 7, 32), 0xFFFF_FFFF);
+    }
+
+    #[test]
+    fn scale_up_14_to_32() {
+        assert_eq!(scale_up(0x0000, 14, 32), 0x0000_0000);
+        assert_eq!(scale_up(0x2000, 14, 32), 0x8000_0000);
+        assert_eq!(scale_up(0x3FFF, 14, 32), 0xFFFF_FFFF);
+    }
+
+    #[test]
+    fn scale_up_1_to_7() {
+        assert_eq!(scale_up(0x0, 1, 7), 0x00);
+        assert_eq!(scale_up(0x1, 1, 7), 0x40);
+    }
+
+    #[test]
+    fn scale_round_trip() {
+        for v in 0..0x80 {
+            assert_eq!(scale_down(scale_up(v, 7, 32), 32, 7), v);
+        }
+    }
+
+    #[test]
+    fn note_on_to_midi2() {
+        let message: ChannelVoice1<&[u32]> = ChannelVoice1::try_from(&[0x2395_3C40][..]).unwrap();
+        assert_eq!(to_midi2(&message).data(), &[0x4395_3C00, 0x8000_0000]);
+    }
+
+    #[test]
+    fn note_on_with_zero_velocity_to_midi2() {
+        let message: ChannelVoice1<&[u32]> = ChannelVoice1::try_from(&[0x2395_3C00][..]).unwrap();
+        assert_eq!(to_midi2(&message).data(), &[0x4385_3C00, 0x8000_0000]);
+    }
+
+    #[test]
+    fn control_change_to_midi2() {
+        let message: ChannelVoice1<&[u32]> = ChannelVoice1::try_from(&[0x20B1_077F][..]).unwrap();
+        assert_eq!(to_midi2(&message).data(), &[0x40B1_0700, 0xFFFF_FFFF]);
+    }
+
+    #[test]
+    fn pitch_bend_to_midi2() {
+        let message: ChannelVoice1<&[u32]> = ChannelVoice1::try_from(&[0x20E0_0040][..]).unwrap();
+        assert_eq!(to_midi2(&message).data(), &[0x40E0_0000, 0x8000_0000]);
+    }
+
+    #[test]
+    fn program_change_to_midi2() {
+        let message: ChannelVoice1<&[u32]> = ChannelVoice1::try_from(&[0x2AC2_0500][..]).unwrap();
+        assert_eq!(to_midi2(&message).data(), &[0x4AC2_0000, 0x0500_0000]);
+    }
+
+    #[test]
+    fn note_on_to_midi1() {
+        let message: ChannelVoice2<&[u32]> =
+            ChannelVoice2::try_from(&[0x4395_3C00, 0xFFFF_0000][..]).unwrap();
+        assert_eq!(
+            to_midi1(&message)
+                .map(|m| m.data()[0])
+                .collect::<std::vec::Vec<_>>(),
+            std::vec![0x2395_3C7F],
+        );
+    }
+
+    #[test]
+    fn quiet_note_on_to_midi1() {
+        let message: ChannelVoice2<&[u32]> =
+            ChannelVoice2::try_from(&[0x4395_3C00, 0x0100_0000][..]).unwrap();
+        assert_eq!(
+            to_midi1(&message)
+                .map(|m| m.data()[0])
+                .collect::<std::vec::Vec<_>>(),
+            std::vec![0x2395_3C01],
+        );
+    }
+
+    #[test]
+    fn program_change_with_bank_to_midi1() {
+        let message: ChannelVoice2<&[u32]> =
+            ChannelVoice2::try_from(&[0x41C2_0001, 0x0500_0302][..]).unwrap();
+        assert_eq!(
+            to_midi1(&message)
+                .map(|m| m.data()[0])
+                .collect::<std::vec::Vec<_>>(),
+            std::vec![0x21B2_0002, 0x21B2_2003, 0x21C2_0500],
+        );
+    }
+
+    #[test]
+    fn registered_controller_to_midi1() {
+        let message: ChannelVoice2<&[u32]> =
+            ChannelVoice2::try_from(&[0x4020_0001, 0x8000_0000][..]).unwrap();
+        assert_eq!(
+            to_midi1(&message)
+                .map(|m| m.data()[0])
+                .collect::<std::vec::Vec<_>>(),
+            std::vec![0x20B0_6500, 0x20B0_6401, 0x20B0_0640, 0x20B0_2600],
+        );
+    }
+
+    #[test]
+    fn assignable_controller_to_midi1() {
+        let message: ChannelVoice2<&[u32]> =
+            ChannelVoice2::try_from(&[0x4031_0203, 0xFFFF_FFFF][..]).unwrap();
+        assert_eq!(
+            to_midi1(&message)
+                .map(|m| m.data()[0])
+                .collect::<std::vec::Vec<_>>(),
+            std::vec![0x20B1_6302, 0x20B1_6203, 0x20B1_067F, 0x20B1_267F],
+        );
+    }
+
+    #[test]
+    fn per_note_messages_have_no_midi1_equivalent() {
+        let message: ChannelVoice2<&[u32]> =
+            ChannelVoice2::try_from(&[0x4060_3C00, 0x8000_0000][..]).unwrap();
+        assert_eq!(to_midi1(&message).count(), 0);
+    }
+}
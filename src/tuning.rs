@@ -0,0 +1,375 @@
+//! Tuning tables, imported from Scala scale and keyboard mapping files.
+//!
+//! A [Tuning] holds the frequency of each of the 128 MIDI notes.
+//! It is sent to MIDI 1.0 devices as MIDI Tuning Standard sysex,
+//! or applied to MIDI 2.0 notes as pitch note attributes and
+//! per note pitch controllers.
+//!
+//! ```rust
+//! use midi2::{
+//!     prelude::*,
+//!     channel_voice2::{NoteAttribute, NoteOn},
+//!     num::Fixed7_9,
+//!     tuning::{KeyboardMapping, Scale, Tuning},
+//! };
+//!
+//! let scale = Scale::parse("\
+//! ! quarter tone
+//! 24-tone equal temperament
+//! 24
+//! 50.0
+//! 100.0
+//! 150.0
+//! 200.0
+//! 250.0
+//! 300.0
+//! 350.0
+//! 400.0
+//! 450.0
+//! 500.0
+//! 550.0
+//! 600.0
+//! 650.0
+//! 700.0
+//! 750.0
+//! 800.0
+//! 850.0
+//! 900.0
+//! 950.0
+//! 1000.0
+//! 1050.0
+//! 1100.0
+//! 1150.0
+//! 2/1
+//! ").unwrap();
+//! let tuning = Tuning::from_scala(&scale, &KeyboardMapping::default()).unwrap();
+//!
+//! let mut message = sysex7::Sysex7::<Vec<u8>>::new();
+//! message.set_payload(tuning.bulk_tuning_dump(u7::new(0x7F), None, u7::new(0x0)).payload());
+//!
+//! let mut note_on = NoteOn::<[u32; 4]>::new();
+//! note_on.set_note_number(u7::new(70));
+//! tuning.retune_note_on(&mut note_on);
+//! assert_eq!(note_on.attribute(), Some(NoteAttribute::Pitch7_9(Fixed7_9::from_num(69.5))));
+//! ```
+
+use crate::{
+    channel_voice2::{
+        pitch::exp2, Controller, NoteAttribute, NoteOn, Pitch, RegisteredPerNoteController,
+    },
+    error::InvalidData,
+    num::{Fixed7_25, Fixed7_9},
+    sysex7::universal::{
+        BulkTuningDump, Category, NoteTuning, SingleNoteTuningChange, TuningFrequency,
+        MAX_NOTE_TUNINGS,
+    },
+    traits::{Channeled, Grouped},
+    ux::u7,
+};
+
+mod scala;
+
+pub use scala::*;
+
+const NOTES: usize = 128;
+const A4_NOTE_NUMBER: f64 = 69.0;
+const A4_FREQUENCY: f64 = 440.0;
+const CENTS_PER_OCTAVE: f64 = 1200.0;
+
+const ERR_REFERENCE_NOT_MAPPED: &str = "Keyboard mapping reference note is not mapped";
+
+/// The frequencies of the 128 MIDI notes in Hz.
+///
+/// Notes without a frequency are left untuned.
+/// The default tuning is twelve tone equal temperament with A4 at 440 Hz.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Tuning {
+    frequencies: [Option<f64>; NOTES],
+}
+
+impl core::default::Default for Tuning {
+    fn default() -> Self {
+        let mut frequencies = [None; NOTES];
+        for (note, frequency) in frequencies.iter_mut().enumerate() {
+            *frequency = Some(A4_FREQUENCY * exp2((note as f64 - A4_NOTE_NUMBER) / 12.0));
+        }
+        Tuning { frequencies }
+    }
+}
+
+impl Tuning {
+    /// Tunes the notes in the range of the keyboard mapping
+    /// to their mapped degree of the scale.
+    ///
+    /// Fails when the reference note of the mapping isn't mapped to a degree.
+    pub fn from_scala(scale: &Scale, mapping: &KeyboardMapping) -> Result<Self, InvalidData> {
+        let reference = mapping
+            .cents(scale, mapping.reference_note)
+            .ok_or(InvalidData(ERR_REFERENCE_NOT_MAPPED))?;
+        let mut frequencies = [None; NOTES];
+        for (note, frequency) in frequencies.iter_mut().enumerate() {
+            let note = u7::new(note as u8);
+            if note < mapping.first_note || note > mapping.last_note {
+                continue;
+            }
+            *frequency = mapping.cents(scale, note).map(|cents| {
+                mapping.reference_frequency * exp2((cents - reference) / CENTS_PER_OCTAVE)
+            });
+        }
+        Ok(Tuning { frequencies })
+    }
+
+    pub fn frequency(&self, note: u7) -> Option<f64> {
+        self.frequencies[usize::from(u8::from(note))]
+    }
+
+    pub fn set_frequency(&mut self, note: u7, frequency: Option<f64>) {
+        self.frequencies[usize::from(u8::from(note))] = frequency;
+    }
+
+    /// The frequency of the note in the form of the MIDI Tuning Standard.
+    pub fn tuning_frequency(&self, note: u7) -> Option<TuningFrequency> {
+        self.frequency(note).map(TuningFrequency::from_frequency)
+    }
+
+    pub fn pitch7_9(&self, note: u7) -> Option<Fixed7_9> {
+        self.frequency(note).map(Fixed7_9::from_frequency)
+    }
+
+    pub fn pitch7_25(&self, note: u7) -> Option<Fixed7_25> {
+        self.frequency(note).map(Fixed7_25::from_frequency)
+    }
+
+    /// A bulk tuning dump of every note, with a blank name.
+    pub fn bulk_tuning_dump(&self, device_id: u7, bank: Option<u7>, program: u7) -> BulkTuningDump {
+        let mut dump = BulkTuningDump::new(device_id, bank, program);
+        for note in 0..NOTES as u8 {
+            let note = u7::new(note);
+            dump.set_frequency(note, self.tuning_frequency(note));
+        }
+        dump
+    }
+
+    /// Single note tuning changes of the tuned notes,
+    /// split into as many messages as are needed.
+    pub fn single_note_tuning_changes(
+        &self,
+        category: Category,
+        device_id: u7,
+        bank: Option<u7>,
+        program: u7,
+    ) -> impl Iterator<Item = SingleNoteTuningChange> + '_ {
+        let mut notes = (0..NOTES as u8)
+            .map(u7::new)
+            .filter(|note| self.frequency(*note).is_some())
+            .peekable();
+        core::iter::from_fn(move || {
+            notes.peek()?;
+            let mut change = SingleNoteTuningChange::new(category, device_id, bank, program);
+            notes
+                .by_ref()
+                .take(MAX_NOTE_TUNINGS)
+                .try_for_each(|key| {
+                    change.push(NoteTuning {
+                        key,
+                        frequency: self.tuning_frequency(key),
+                    })
+                })
+                .ok()?;
+            Some(change)
+        })
+    }
+
+    /// Sets the [Pitch7_9](NoteAttribute::Pitch7_9) attribute of the note on
+    /// to the tuning of its note number.
+    ///
+    /// Notes without a frequency are left unchanged.
+    pub fn retune_note_on<B>(&self, message: &mut NoteOn<B>)
+    where
+        B: crate::buffer::Ump + crate::buffer::BufferMut,
+    {
+        if let Some(pitch) = self.pitch7_9(message.note_number()) {
+            message.set_attribute(Some(NoteAttribute::Pitch7_9(pitch)));
+        }
+    }
+
+    /// A [Pitch7_25](Controller::Pitch7_25) per note controller tuning the
+    /// note of the note on, on its group and channel.
+    pub fn pitch_controller<B: crate::buffer::Ump>(
+        &self,
+        message: &NoteOn<B>,
+    ) -> Option<RegisteredPerNoteController<[u32; 4]>> {
+        let pitch = self.pitch7_25(message.note_number())?;
+        let mut controller = RegisteredPerNoteController::<[u32; 4]>::new();
+        controller.set_group(message.group());
+        controller.set_channel(message.channel());
+        controller.set_note_number(message.note_number());
+        controller.set_controller(Controller::Pitch7_25(pitch));
+        Some(controller)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ux::{u14, u4};
+    use pretty_assertions::assert_eq;
+
+    const JUST: &str = "\
+! just.scl
+!
+5-limit just intonation
+ 12
+!
+ 16/15
+ 9/8
+ 6/5
+ 5/4
+ 4/3
+ 45/32
+ 3/2
+ 8/5
+ 5/3
+ 9/5
+ 15/8
+ 2/1
+";
+
+    fn assert_close(a: f64, b: f64) {
+        assert!((a - b).abs() < 1e-6, "{a} != {b}");
+    }
+
+    #[test]
+    fn default_is_equal_temperament() {
+        let tuning = Tuning::default();
+        assert_close(tuning.frequency(u7::new(69)).unwrap(), 440.0);
+        assert_close(tuning.frequency(u7::new(57)).unwrap(), 220.0);
+        assert_eq!(tuning.pitch7_9(u7::new(60)), Some(Fixed7_9::from_num(60)));
+    }
+
+    #[test]
+    fn just_intonation() {
+        let scale = Scale::parse(JUST).unwrap();
+        let mapping = KeyboardMapping::parse("0\n0\n127\n60\n60\n261.6255653\n12\n").unwrap();
+        let tuning = Tuning::from_scala(&scale, &mapping).unwrap();
+        assert_close(tuning.frequency(u7::new(60)).unwrap(), 261.6255653);
+        assert_close(tuning.frequency(u7::new(67)).unwrap(), 261.6255653 * 1.5);
+        assert_close(tuning.frequency(u7::new(72)).unwrap(), 261.6255653 * 2.0);
+        assert_close(
+            tuning.frequency(u7::new(59)).unwrap(),
+            261.6255653 * 15.0 / 16.0,
+        );
+        assert_eq!(
+            tuning.tuning_frequency(u7::new(64)),
+            Some(TuningFrequency {
+                semitone: u7::new(63),
+                fraction: u14::new(0x373E),
+            })
+        );
+    }
+
+    #[test]
+    fn reference_note() {
+        let scale = Scale::parse(JUST).unwrap();
+        let tuning = Tuning::from_scala(&scale, &KeyboardMapping::default()).unwrap();
+        assert_close(tuning.frequency(u7::new(69)).unwrap(), 440.0);
+        assert_close(tuning.frequency(u7::new(60)).unwrap(), 440.0 * 3.0 / 5.0);
+    }
+
+    #[test]
+    fn mapped_range() {
+        let scale = Scale::parse(JUST).unwrap();
+        let mapping = KeyboardMapping::parse("0\n21\n108\n60\n69\n440.0\n12\n").unwrap();
+        let tuning = Tuning::from_scala(&scale, &mapping).unwrap();
+        assert_eq!(tuning.frequency(u7::new(20)), None);
+        assert!(tuning.frequency(u7::new(21)).is_some());
+        assert!(tuning.frequency(u7::new(108)).is_some());
+        assert_eq!(tuning.frequency(u7::new(109)), None);
+    }
+
+    #[test]
+    fn reference_not_mapped() {
+        let scale = Scale::parse(JUST).unwrap();
+        let mapping = KeyboardMapping::parse("2\n0\n127\n60\n61\n440.0\n12\n0\nx\n").unwrap();
+        assert_eq!(
+            Tuning::from_scala(&scale, &mapping),
+            Err(InvalidData(ERR_REFERENCE_NOT_MAPPED))
+        );
+    }
+
+    #[test]
+    fn bulk_tuning_dump() {
+        let mut tuning = Tuning::default();
+        tuning.set_frequency(u7::new(0), None);
+        let dump = tuning.bulk_tuning_dump(u7::new(0x7F), None, u7::new(0x1));
+        assert_eq!(dump.frequency(u7::new(0)), None);
+        assert_eq!(
+            dump.frequency(u7::new(69)),
+            Some(TuningFrequency {
+                semitone: u7::new(69),
+                fraction: u14::new(0x0),
+            })
+        );
+    }
+
+    #[test]
+    fn single_note_tuning_changes() {
+        let tuning = Tuning::default();
+        let changes: std::vec::Vec<_> = tuning
+            .single_note_tuning_changes(Category::RealTime, u7::new(0x7F), None, u7::new(0x0))
+            .collect();
+        assert_eq!(changes.len(), 2);
+        assert_eq!(changes[0].changes().len(), MAX_NOTE_TUNINGS);
+        assert_eq!(
+            changes[1].changes().collect::<std::vec::Vec<_>>(),
+            std::vec![NoteTuning {
+                key: u7::new(127),
+                frequency: Some(TuningFrequency {
+                    semitone: u7::new(127),
+                    fraction: u14::new(0x0),
+                }),
+            }]
+        );
+    }
+
+    #[test]
+    fn untuned_notes_are_skipped() {
+        let mut tuning = Tuning::default();
+        for note in 1..128 {
+            tuning.set_frequency(u7::new(note), None);
+        }
+        let changes: std::vec::Vec<_> = tuning
+            .single_note_tuning_changes(Category::RealTime, u7::new(0x7F), None, u7::new(0x0))
+            .collect();
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].changes().len(), 1);
+    }
+
+    #[test]
+    fn retune_untuned_note_on() {
+        let mut tuning = Tuning::default();
+        tuning.set_frequency(u7::new(60), None);
+        let mut message = NoteOn::<[u32; 4]>::new();
+        message.set_note_number(u7::new(60));
+        tuning.retune_note_on(&mut message);
+        assert_eq!(message.attribute(), None);
+    }
+
+    #[test]
+    fn pitch_controller() {
+        let scale = Scale::parse(JUST).unwrap();
+        let tuning = Tuning::from_scala(&scale, &KeyboardMapping::default()).unwrap();
+        let mut message = NoteOn::<[u32; 4]>::new();
+        message.set_group(u4::new(0x2));
+        message.set_channel(u4::new(0x3));
+        message.set_note_number(u7::new(69));
+        let controller = tuning.pitch_controller(&message).unwrap();
+        assert_eq!(controller.group(), u4::new(0x2));
+        assert_eq!(controller.channel(), u4::new(0x3));
+        assert_eq!(controller.note_number(), u7::new(69));
+        assert_eq!(
+            controller.controller(),
+            Controller::Pitch7_25(Fixed7_25::from_num(69))
+        );
+    }
+}
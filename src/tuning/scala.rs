@@ -0,0 +1,566 @@
+use crate::{channel_voice2::pitch::log2, ux::u7};
+
+/// The largest number of notes of a [Scale].
+pub const MAX_SCALE_SIZE: usize = 256;
+/// The largest number of keys of a [KeyboardMapping] pattern.
+pub const MAX_MAPPING_SIZE: usize = 128;
+
+const CENTS_PER_OCTAVE: f64 = 1200.0;
+const COMMENT: char = '!';
+const UNMAPPED: &str = "x";
+
+const ERR_MISSING_DESCRIPTION: &str = "Expected a description line";
+const ERR_MISSING_NOTE_COUNT: &str = "Expected the number of notes";
+const ERR_INVALID_NOTE_COUNT: &str = "Expected the number of notes to be a whole number";
+const ERR_NO_NOTES: &str = "Expected at least one note";
+const ERR_TOO_MANY_NOTES: &str = "Scale has more notes than are supported";
+const ERR_MISSING_PITCH: &str = "Expected a pitch for every note";
+const ERR_INVALID_CENTS: &str = "Expected a cents value, e.g. 701.955";
+const ERR_INVALID_RATIO: &str = "Expected a ratio, e.g. 3/2";
+const ERR_NON_POSITIVE_RATIO: &str = "Expected a ratio greater than zero";
+const ERR_MISSING_MAP_SIZE: &str = "Expected the size of the mapping pattern";
+const ERR_INVALID_MAP_SIZE: &str = "Expected the size of the mapping pattern to be a whole number";
+const ERR_TOO_MANY_KEYS: &str = "Mapping pattern has more keys than are supported";
+const ERR_MISSING_NOTE: &str = "Expected a note number";
+const ERR_INVALID_NOTE: &str = "Expected a note number from 0 to 127";
+const ERR_NOTE_RANGE: &str = "Expected the first note to be no greater than the last note";
+const ERR_MISSING_FREQUENCY: &str = "Expected the reference frequency";
+const ERR_INVALID_FREQUENCY: &str = "Expected the reference frequency in Hz, greater than zero";
+const ERR_MISSING_OCTAVE_DEGREE: &str = "Expected the scale degree of the formal octave";
+const ERR_INVALID_DEGREE: &str = "Expected a scale degree or x for an unmapped key";
+
+/// An error in a Scala file, with the one based number of the line it was found on.
+///
+/// When the file ends early, the line is the one following the last line.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseError {
+    pub line: usize,
+    pub message: &'static str,
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ParseError {}
+
+#[cfg(feature = "std")]
+impl std::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+// the non comment lines of a file, with their line numbers
+struct Lines<'a> {
+    lines: core::iter::Enumerate<core::str::Lines<'a>>,
+    line: usize,
+}
+
+impl<'a> Lines<'a> {
+    fn new(text: &'a str) -> Self {
+        Lines {
+            lines: text.lines().enumerate(),
+            line: 0,
+        }
+    }
+
+    fn next_line(&mut self, missing: &'static str) -> Result<&'a str, ParseError> {
+        for (index, line) in self.lines.by_ref() {
+            self.line = index + 1;
+            if !line.starts_with(COMMENT) {
+                return Ok(line);
+            }
+        }
+        self.line += 1;
+        Err(self.error(missing))
+    }
+
+    // the first word of the next line, the rest of the line is ignored
+    fn next_value(&mut self, missing: &'static str) -> Result<&'a str, ParseError> {
+        let line = self.next_line(missing)?;
+        line.split_whitespace()
+            .next()
+            .ok_or_else(|| self.error(missing))
+    }
+
+    fn next_parsed<T: core::str::FromStr>(
+        &mut self,
+        missing: &'static str,
+        invalid: &'static str,
+    ) -> Result<T, ParseError> {
+        self.next_value(missing)?
+            .parse()
+            .map_err(|_| self.error(invalid))
+    }
+
+    fn next_note(&mut self) -> Result<u7, ParseError> {
+        let note: u8 = self.next_parsed(ERR_MISSING_NOTE, ERR_INVALID_NOTE)?;
+        if note > 0x7F {
+            return Err(self.error(ERR_INVALID_NOTE));
+        }
+        Ok(u7::new(note))
+    }
+
+    fn error(&self, message: &'static str) -> ParseError {
+        ParseError {
+            line: self.line,
+            message,
+        }
+    }
+}
+
+/// A scale read from a Scala `.scl` file.
+///
+/// The pitches of the degrees are held in cents above the implicit first
+/// degree of 1/1. The last degree is the period at which the scale repeats,
+/// usually the octave.
+///
+/// ```rust
+/// use midi2::tuning::Scale;
+///
+/// let scale = Scale::parse("\
+/// ! pythagorean.scl
+/// Pythagorean pentatonic
+///  5
+/// !
+///  9/8
+///  81/64
+///  3/2
+///  27/16
+///  2/1
+/// ").unwrap();
+///
+/// assert_eq!(scale.description(), "Pythagorean pentatonic");
+/// assert_eq!(scale.len(), 5);
+/// assert!((scale.cents(3) - 701.955).abs() < 0.001);
+/// assert_eq!(scale.cents(5), 1200.0);
+/// assert_eq!(scale.cents(-5), -1200.0);
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct Scale<'a> {
+    description: &'a str,
+    degrees: [f64; MAX_SCALE_SIZE],
+    len: usize,
+}
+
+impl<'a> Scale<'a> {
+    pub fn parse(text: &'a str) -> Result<Self, ParseError> {
+        let mut lines = Lines::new(text);
+        let description = lines.next_line(ERR_MISSING_DESCRIPTION)?.trim();
+        let len: usize = lines.next_parsed(ERR_MISSING_NOTE_COUNT, ERR_INVALID_NOTE_COUNT)?;
+        if len == 0 {
+            return Err(lines.error(ERR_NO_NOTES));
+        }
+        if len > MAX_SCALE_SIZE {
+            return Err(lines.error(ERR_TOO_MANY_NOTES));
+        }
+        let mut degrees = [0.0; MAX_SCALE_SIZE];
+        for degree in degrees.iter_mut().take(len) {
+            let value = lines.next_value(ERR_MISSING_PITCH)?;
+            *degree = parse_pitch(value).map_err(|message| lines.error(message))?;
+        }
+        Ok(Scale {
+            description,
+            degrees,
+            len,
+        })
+    }
+
+    pub fn description(&self) -> &'a str {
+        self.description
+    }
+
+    /// The number of notes in each period of the scale.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Always false, scales having at least one note.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// The pitch of the degree in cents above the first degree,
+    /// repeating the scale at its period above and below.
+    pub fn cents(&self, degree: i32) -> f64 {
+        let len = self.len as i32;
+        let period = degree.div_euclid(len);
+        let index = degree.rem_euclid(len);
+        let cents = if index == 0 {
+            0.0
+        } else {
+            self.degrees[index as usize - 1]
+        };
+        period as f64 * self.degrees[self.len - 1] + cents
+    }
+}
+
+// cents contain a period, ratios don't
+fn parse_pitch(value: &str) -> Result<f64, &'static str> {
+    if value.contains('.') {
+        return value
+            .parse()
+            .ok()
+            .filter(|cents: &f64| cents.is_finite())
+            .ok_or(ERR_INVALID_CENTS);
+    }
+    let (numerator, denominator) = value.split_once('/').unwrap_or((value, "1"));
+    let numerator: i64 = numerator.parse().map_err(|_| ERR_INVALID_RATIO)?;
+    let denominator: i64 = denominator.parse().map_err(|_| ERR_INVALID_RATIO)?;
+    if numerator <= 0 || denominator <= 0 {
+        return Err(ERR_NON_POSITIVE_RATIO);
+    }
+    Ok(CENTS_PER_OCTAVE * log2(numerator as f64 / denominator as f64))
+}
+
+/// A keyboard mapping read from a Scala `.kbm` file,
+/// assigning scale degrees to MIDI note numbers.
+///
+/// The mapping pattern repeats every pattern size keys, transposed by the
+/// formal octave degree. Without a pattern, successive keys map to
+/// successive degrees.
+///
+/// The default mapping is linear, with middle C as the first degree
+/// and A4 at 440 Hz.
+///
+/// ```rust
+/// use midi2::{tuning::KeyboardMapping, ux::u7};
+///
+/// let mapping = KeyboardMapping::parse("\
+/// ! white keys only
+/// 12
+/// 0
+/// 127
+/// 60
+/// 69
+/// 440.0
+/// 7
+/// ! mapping
+/// 0
+/// x
+/// 1
+/// x
+/// 2
+/// 3
+/// x
+/// 4
+/// x
+/// 5
+/// x
+/// 6
+/// ").unwrap();
+///
+/// assert_eq!(mapping.reference_note, u7::new(69));
+/// assert_eq!(mapping.mapping().len(), 12);
+/// assert_eq!(mapping.degree(u7::new(60)), Some(0));
+/// assert_eq!(mapping.degree(u7::new(61)), None);
+/// assert_eq!(mapping.degree(u7::new(72)), Some(7));
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct KeyboardMapping {
+    pub first_note: u7,
+    pub last_note: u7,
+    /// The note mapped to the first degree of the scale.
+    pub middle_note: u7,
+    /// The note tuned to the reference frequency.
+    pub reference_note: u7,
+    /// In Hz.
+    pub reference_frequency: f64,
+    /// The degree which the mapping pattern is transposed by at each repetition.
+    pub octave_degree: i32,
+    mapping: [Option<i32>; MAX_MAPPING_SIZE],
+    len: usize,
+}
+
+impl core::default::Default for KeyboardMapping {
+    fn default() -> Self {
+        KeyboardMapping {
+            first_note: u7::new(0),
+            last_note: u7::new(127),
+            middle_note: u7::new(60),
+            reference_note: u7::new(69),
+            reference_frequency: 440.0,
+            octave_degree: 0,
+            mapping: [None; MAX_MAPPING_SIZE],
+            len: 0,
+        }
+    }
+}
+
+impl KeyboardMapping {
+    pub fn parse(text: &str) -> Result<Self, ParseError> {
+        let mut lines = Lines::new(text);
+        let len: usize = lines.next_parsed(ERR_MISSING_MAP_SIZE, ERR_INVALID_MAP_SIZE)?;
+        if len > MAX_MAPPING_SIZE {
+            return Err(lines.error(ERR_TOO_MANY_KEYS));
+        }
+        let first_note = lines.next_note()?;
+        let last_note = lines.next_note()?;
+        if first_note > last_note {
+            return Err(lines.error(ERR_NOTE_RANGE));
+        }
+        let middle_note = lines.next_note()?;
+        let reference_note = lines.next_note()?;
+        let reference_frequency: f64 =
+            lines.next_parsed(ERR_MISSING_FREQUENCY, ERR_INVALID_FREQUENCY)?;
+        if !(reference_frequency > 0.0 && reference_frequency.is_finite()) {
+            return Err(lines.error(ERR_INVALID_FREQUENCY));
+        }
+        let octave_degree = lines.next_parsed(ERR_MISSING_OCTAVE_DEGREE, ERR_INVALID_DEGREE)?;
+        // keys missing from the end of the pattern are unmapped
+        let mut mapping = [None; MAX_MAPPING_SIZE];
+        for degree in mapping.iter_mut().take(len) {
+            let Ok(value) = lines.next_value(ERR_INVALID_DEGREE) else {
+                break;
+            };
+            if value != UNMAPPED {
+                *degree = Some(value.parse().map_err(|_| lines.error(ERR_INVALID_DEGREE))?);
+            }
+        }
+        Ok(KeyboardMapping {
+            first_note,
+            last_note,
+            middle_note,
+            reference_note,
+            reference_frequency,
+            octave_degree,
+            mapping,
+            len,
+        })
+    }
+
+    /// The degrees of the mapping pattern, with none for unmapped keys.
+    ///
+    /// An empty pattern maps keys linearly.
+    pub fn mapping(&self) -> &[Option<i32>] {
+        &self.mapping[..self.len]
+    }
+
+    /// The scale degree the note is mapped to, counting from the middle note.
+    ///
+    /// Notes outside of the mapped range aren't mapped.
+    pub fn degree(&self, note: u7) -> Option<i32> {
+        if note < self.first_note || note > self.last_note {
+            return None;
+        }
+        let (repetition, degree) = self.pattern_degree(note)?;
+        Some(repetition * self.octave_degree + degree)
+    }
+
+    // the repetitions of the pattern and degree within it of any note
+    fn pattern_degree(&self, note: u7) -> Option<(i32, i32)> {
+        let offset = i32::from(u8::from(note)) - i32::from(u8::from(self.middle_note));
+        if self.len == 0 {
+            return Some((0, offset));
+        }
+        let len = self.len as i32;
+        let degree = self.mapping[offset.rem_euclid(len) as usize]?;
+        Some((offset.div_euclid(len), degree))
+    }
+
+    // the pitch of any note in cents above the middle note
+    pub(crate) fn cents(&self, scale: &Scale, note: u7) -> Option<f64> {
+        let (repetition, degree) = self.pattern_degree(note)?;
+        Some(repetition as f64 * scale.cents(self.octave_degree) + scale.cents(degree))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn assert_close(a: f64, b: f64) {
+        assert!((a - b).abs() < 1e-6, "{a} != {b}");
+    }
+
+    #[test]
+    fn pitches() {
+        let scale = Scale::parse("test\n4\n100.0\n 3/2 fifth\n5\n2/1\n").unwrap();
+        assert_close(scale.cents(1), 100.0);
+        assert_close(scale.cents(2), 701.955_000_865_387_4);
+        assert_close(scale.cents(3), 2_786.313_713_864_835);
+        assert_close(scale.cents(4), 1200.0);
+    }
+
+    #[test]
+    fn negative_cents() {
+        let scale = Scale::parse("test\n2\n-50.0\n1200.\n").unwrap();
+        assert_close(scale.cents(1), -50.0);
+        assert_close(scale.cents(-1), -1250.0);
+    }
+
+    #[test]
+    fn empty_description() {
+        let scale = Scale::parse("!\n\n1\n2/1\n").unwrap();
+        assert_eq!(scale.description(), "");
+    }
+
+    #[test]
+    fn missing_pitch() {
+        assert_eq!(
+            Scale::parse("! comment\ntest\n3\n9/8\n5/4\n"),
+            Err(ParseError {
+                line: 6,
+                message: ERR_MISSING_PITCH
+            })
+        );
+    }
+
+    #[test]
+    fn invalid_note_count() {
+        assert_eq!(
+            Scale::parse("test\nfive\n"),
+            Err(ParseError {
+                line: 2,
+                message: ERR_INVALID_NOTE_COUNT
+            })
+        );
+    }
+
+    #[test]
+    fn no_notes() {
+        assert_eq!(
+            Scale::parse("test\n0\n"),
+            Err(ParseError {
+                line: 2,
+                message: ERR_NO_NOTES
+            })
+        );
+    }
+
+    #[test]
+    fn invalid_ratio() {
+        assert_eq!(
+            Scale::parse("test\n2\n3/x\n2/1\n"),
+            Err(ParseError {
+                line: 3,
+                message: ERR_INVALID_RATIO
+            })
+        );
+    }
+
+    #[test]
+    fn non_positive_ratio() {
+        assert_eq!(
+            Scale::parse("test\n2\n-3/2\n2/1\n"),
+            Err(ParseError {
+                line: 3,
+                message: ERR_NON_POSITIVE_RATIO
+            })
+        );
+    }
+
+    #[test]
+    fn invalid_cents() {
+        assert_eq!(
+            Scale::parse("test\n1\n12.0.0\n"),
+            Err(ParseError {
+                line: 3,
+                message: ERR_INVALID_CENTS
+            })
+        );
+    }
+
+    #[test]
+    fn infinite_cents() {
+        assert_eq!(
+            Scale::parse("test\n2\n1.0e400\n2/1\n"),
+            Err(ParseError {
+                line: 3,
+                message: ERR_INVALID_CENTS
+            })
+        );
+    }
+
+    #[test]
+    fn too_many_notes() {
+        assert_eq!(
+            Scale::parse("test\n257\n"),
+            Err(ParseError {
+                line: 2,
+                message: ERR_TOO_MANY_NOTES
+            })
+        );
+    }
+
+    #[test]
+    fn linear_mapping() {
+        let mapping = KeyboardMapping::parse("0\n10\n100\n60\n69\n440.0\n0\n").unwrap();
+        assert!(mapping.mapping().is_empty());
+        assert_eq!(mapping.degree(u7::new(9)), None);
+        assert_eq!(mapping.degree(u7::new(10)), Some(-50));
+        assert_eq!(mapping.degree(u7::new(100)), Some(40));
+        assert_eq!(mapping.degree(u7::new(101)), None);
+    }
+
+    #[test]
+    fn short_mapping_is_unmapped() {
+        let mapping = KeyboardMapping::parse("3\n0\n127\n60\n60\n261.6\n2\n0\n1\n").unwrap();
+        assert_eq!(mapping.mapping(), &[Some(0), Some(1), None]);
+        assert_eq!(mapping.degree(u7::new(62)), None);
+        assert_eq!(mapping.degree(u7::new(63)), Some(2));
+    }
+
+    #[test]
+    fn mapped_cents() {
+        let scale = Scale::parse("test\n3\n200.0\n400.0\n1200.0\n").unwrap();
+        let mapping = KeyboardMapping::parse("2\n0\n127\n60\n60\n261.6\n3\n0\n2\n").unwrap();
+        assert_eq!(mapping.cents(&scale, u7::new(61)), Some(400.0));
+        assert_eq!(mapping.cents(&scale, u7::new(62)), Some(1200.0));
+        assert_eq!(mapping.cents(&scale, u7::new(59)), Some(-800.0));
+    }
+
+    #[test]
+    fn invalid_note() {
+        assert_eq!(
+            KeyboardMapping::parse("0\n0\n128\n"),
+            Err(ParseError {
+                line: 3,
+                message: ERR_INVALID_NOTE
+            })
+        );
+    }
+
+    #[test]
+    fn note_range() {
+        assert_eq!(
+            KeyboardMapping::parse("0\n100\n10\n"),
+            Err(ParseError {
+                line: 3,
+                message: ERR_NOTE_RANGE
+            })
+        );
+    }
+
+    #[test]
+    fn invalid_frequency() {
+        assert_eq!(
+            KeyboardMapping::parse("0\n0\n127\n60\n69\n-440.0\n"),
+            Err(ParseError {
+                line: 6,
+                message: ERR_INVALID_FREQUENCY
+            })
+        );
+    }
+
+    #[test]
+    fn invalid_degree() {
+        assert_eq!(
+            KeyboardMapping::parse("2\n0\n127\n60\n69\n440.0\n12\n0\ny\n"),
+            Err(ParseError {
+                line: 9,
+                message: ERR_INVALID_DEGREE
+            })
+        );
+    }
+
+    #[test]
+    fn missing_octave_degree() {
+        assert_eq!(
+            KeyboardMapping::parse("0\n0\n127\n60\n69\n440.0\n"),
+            Err(ParseError {
+                line: 7,
+                message: ERR_MISSING_OCTAVE_DEGREE
+            })
+        );
+    }
+}
@@ -10,8 +10,12 @@ mod endpoint_info;
 mod endpoint_name;
 mod function_block_discovery;
 mod function_block_info;
+mod function_block_map;
 mod function_block_name;
+mod negotiator;
 mod product_instance_id;
+mod remote_endpoint;
+mod responder;
 mod start_of_clip;
 mod stream_configuration_notification;
 mod stream_configuration_request;
@@ -23,8 +27,12 @@ pub use endpoint_info::*;
 pub use endpoint_name::*;
 pub use function_block_discovery::*;
 pub use function_block_info::*;
+pub use function_block_map::*;
 pub use function_block_name::FunctionBlockName;
+pub use negotiator::*;
 pub use product_instance_id::*;
+pub use remote_endpoint::*;
+pub use responder::*;
 pub use start_of_clip::*;
 pub use stream_configuration_notification::*;
 pub use stream_configuration_request::*;
@@ -39,6 +47,7 @@ const END_FORMAT: u8 = 0x3;
     derive_more::From,
     midi2_proc::Data,
     midi2_proc::Packets,
+    midi2_proc::Remappable,
     midi2_proc::RebufferFrom,
     midi2_proc::TryRebufferFrom,
     Clone,
@@ -1,8 +1,9 @@
 use crate::{
-    detail::{common_properties, schema},
+    detail::{common_properties, property, schema},
     ump_stream,
     ump_stream::UMP_MESSAGE_TYPE,
     ux::{u14, u7},
+    ManufacturerId,
 };
 
 pub(crate) const STATUS: u16 = 0x2;
@@ -15,8 +16,8 @@ struct DeviceIdentity {
     status: (),
     #[property(ump_stream::ConsistentFormatsProperty)]
     consistent_formats: (),
-    #[property(common_properties::UmpSchemaProperty<[u7; 3], schema::Ump<0x0, 0x007F_7F7F, 0x0, 0x0>>)]
-    device_manufacturer: [u7; 3],
+    #[property(DeviceManufacturerProperty)]
+    device_manufacturer: ManufacturerId,
     #[property(common_properties::UmpSchemaProperty<u14, schema::Ump<0x0, 0x0, 0x7F7F_0000, 0x0>>)]
     device_family: u14,
     #[property(common_properties::UmpSchemaProperty<u14, schema::Ump<0x0, 0x0, 0x0000_7F7F, 0x0>>)]
@@ -25,23 +26,46 @@ struct DeviceIdentity {
     software_version: [u7; 4],
 }
 
-impl schema::UmpSchemaRepr<schema::Ump<0x0, 0x007F_7F7F, 0x0, 0x0>> for [crate::ux::u7; 3] {
-    fn write(buffer: &mut [u32], value: Self) {
-        use crate::detail::BitOps;
-        buffer[1].set_septet(1, value[0]);
-        buffer[1].set_septet(2, value[1]);
-        buffer[1].set_septet(3, value[2]);
+struct DeviceManufacturerProperty;
+
+impl<B: crate::buffer::Ump> property::Property<B> for DeviceManufacturerProperty {
+    type Type = ManufacturerId;
+}
+
+impl<'a, B: crate::buffer::Ump> property::ReadProperty<'a, B> for DeviceManufacturerProperty {
+    fn read(buffer: &'a B) -> Self::Type {
+        ManufacturerId::try_from(device_manufacturer_bytes(buffer)).unwrap()
     }
-    fn read(buffer: &[u32]) -> Self {
+    fn validate(buffer: &B) -> Result<(), crate::error::InvalidData> {
+        ManufacturerId::try_from(device_manufacturer_bytes(buffer)).map(|_| ())
+    }
+}
+
+impl<B: crate::buffer::Ump + crate::buffer::BufferMut> property::WriteProperty<B>
+    for DeviceManufacturerProperty
+{
+    fn validate(_v: &Self::Type) -> Result<(), crate::error::InvalidData> {
+        Ok(())
+    }
+    fn write(buffer: &mut B, v: Self::Type) {
         use crate::detail::BitOps;
-        [
-            buffer[1].septet(1),
-            buffer[1].septet(2),
-            buffer[1].septet(3),
-        ]
+        let v = <[u7; 3]>::from(v);
+        let buffer = buffer.buffer_mut();
+        buffer[1].set_septet(1, v[0]);
+        buffer[1].set_septet(2, v[1]);
+        buffer[1].set_septet(3, v[2]);
+    }
+    fn default() -> Self::Type {
+        Default::default()
     }
 }
 
+fn device_manufacturer_bytes<B: crate::buffer::Ump>(buffer: &B) -> [u7; 3] {
+    use crate::detail::BitOps;
+    let word = buffer.buffer()[1];
+    [word.septet(1), word.septet(2), word.septet(3)]
+}
+
 impl schema::UmpSchemaRepr<schema::Ump<0x0, 0x0, 0x0, 0x7F7F_7F7F>> for [crate::ux::u7; 4] {
     fn write(buffer: &mut [u32], value: Self) {
         use crate::detail::BitOps;
@@ -69,30 +93,38 @@ mod tests {
     #[test]
     fn builder() {
         let mut message = DeviceIdentity::<[u32; 4]>::new();
-        message.set_device_manufacturer([u7::new(0x0F), u7::new(0x33), u7::new(0x28)]);
+        message.set_device_manufacturer(ManufacturerId::Standard(u7::new(0x0F)));
         message.set_device_family(u14::new(0xF4A));
         message.set_device_family_model_number(u14::new(0x3818));
         message.set_software_version([u7::new(0x43), u7::new(0x54), u7::new(0x32), u7::new(0x1)]);
         assert_eq!(
             message,
-            DeviceIdentity([0xF002_0000, 0x000f_3328, 0x4A1E_1870, 0x4354_3201,]),
+            DeviceIdentity([0xF002_0000, 0x000F_0000, 0x4A1E_1870, 0x4354_3201,]),
         );
     }
 
     #[test]
     fn device_manufacturer() {
         assert_eq!(
-            DeviceIdentity::try_from(&[0xF002_0000, 0x000F_3328, 0x4A1E_1870, 0x4354_3201][..])
+            DeviceIdentity::try_from(&[0xF002_0000, 0x0000_3328, 0x4A1E_1870, 0x4354_3201][..])
                 .unwrap()
                 .device_manufacturer(),
-            [u7::new(0x0F), u7::new(0x33), u7::new(0x28)],
+            ManufacturerId::Extended(u7::new(0x33), u7::new(0x28)),
         );
     }
 
+    #[test]
+    fn device_manufacturer_non_zero_padding() {
+        assert!(DeviceIdentity::try_from(
+            &[0xF002_0000, 0x000F_3328, 0x4A1E_1870, 0x4354_3201][..]
+        )
+        .is_err());
+    }
+
     #[test]
     fn device_family() {
         assert_eq!(
-            DeviceIdentity::try_from(&[0xF002_0000, 0x000F_3328, 0x4A1E_1870, 0x4354_3201][..])
+            DeviceIdentity::try_from(&[0xF002_0000, 0x0000_3328, 0x4A1E_1870, 0x4354_3201][..])
                 .unwrap()
                 .device_family(),
             u14::new(0xF4A),
@@ -102,7 +134,7 @@ mod tests {
     #[test]
     fn device_family_model_number() {
         assert_eq!(
-            DeviceIdentity::try_from(&[0xF002_0000, 0x000F_3328, 0x4A1E_1870, 0x4354_3201][..])
+            DeviceIdentity::try_from(&[0xF002_0000, 0x0000_3328, 0x4A1E_1870, 0x4354_3201][..])
                 .unwrap()
                 .device_family_model_number(),
             u14::new(0x3818),
@@ -112,7 +144,7 @@ mod tests {
     #[test]
     fn software_version() {
         assert_eq!(
-            DeviceIdentity::try_from(&[0xF002_0000, 0x000F_3328, 0x4A1E_1870, 0x4354_3201][..])
+            DeviceIdentity::try_from(&[0xF002_0000, 0x0000_3328, 0x4A1E_1870, 0x4354_3201][..])
                 .unwrap()
                 .software_version(),
             [u7::new(0x43), u7::new(0x54), u7::new(0x32), u7::new(0x1)],
@@ -124,13 +156,13 @@ mod tests {
         use crate::Packets;
 
         let message =
-            DeviceIdentity::try_from(&[0xF002_0000, 0x000F_3328, 0x4A1E_1870, 0x4354_3201][..])
+            DeviceIdentity::try_from(&[0xF002_0000, 0x0000_3328, 0x4A1E_1870, 0x4354_3201][..])
                 .unwrap();
         let mut packets = message.packets();
 
         assert_eq!(
             packets.next(),
-            Some(&[0xF002_0000, 0x000F_3328, 0x4A1E_1870, 0x4354_3201][..])
+            Some(&[0xF002_0000, 0x0000_3328, 0x4A1E_1870, 0x4354_3201][..])
         );
         assert_eq!(packets.next(), None);
     }
@@ -0,0 +1,363 @@
+use crate::{
+    error::InvalidData,
+    ump_stream::{Direction, FunctionBlockDescriptor, Midi1Port},
+    ux::{u4, u7},
+};
+
+const MAX_FUNCTION_BLOCKS: usize = 32;
+const NUMBER_OF_GROUPS: u8 = 16;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Entry {
+    active: bool,
+    first_group: u8,
+    number_of_groups_spanned: u8,
+    direction: Direction,
+    midi1_port: Option<Midi1Port>,
+}
+
+impl Entry {
+    fn owns(&self, group: u4) -> bool {
+        let group = u8::from(group);
+        let first = self.first_group;
+        group >= first && (group - first) < self.number_of_groups_spanned
+    }
+}
+
+/// Interprets the group ranges declared by a set of function blocks.
+///
+/// Inactive blocks keep their declared groups, but are never
+/// considered when deciding whether a group may be used.
+///
+/// ```rust
+/// use midi2::{prelude::*, ump_stream::*};
+///
+/// let map = FunctionBlockMap::from_descriptors(&[
+///     FunctionBlockDescriptor {
+///         first_group: u4::new(0),
+///         number_of_groups_spanned: 2,
+///         direction: Direction::Input,
+///         ..Default::default()
+///     },
+///     FunctionBlockDescriptor {
+///         first_group: u4::new(2),
+///         number_of_groups_spanned: 1,
+///         direction: Direction::Output,
+///         midi1_port: Some(Midi1Port::RestrictBandwidth),
+///         ..Default::default()
+///     },
+/// ]).unwrap();
+///
+/// assert!(map.validate().is_ok());
+/// assert_eq!(map.blocks_for_group(u4::new(1)).collect::<Vec<_>>(), vec![u7::new(0)]);
+/// assert!(map.can_send(u4::new(1)));
+/// assert!(!map.can_send(u4::new(2)));
+/// assert!(map.is_bandwidth_restricted(u4::new(2)));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FunctionBlockMap {
+    blocks: [Option<Entry>; MAX_FUNCTION_BLOCKS],
+}
+
+impl FunctionBlockMap {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A map of the given blocks, numbered by their position in the slice.
+    pub fn from_descriptors(blocks: &[FunctionBlockDescriptor]) -> Result<Self, InvalidData> {
+        let mut map = Self::new();
+        for (number, block) in blocks.iter().enumerate() {
+            if number >= MAX_FUNCTION_BLOCKS {
+                return Err(InvalidData(ERR_TOO_MANY_BLOCKS));
+            }
+            map.insert(u7::new(number as u8), block)?;
+        }
+        Ok(map)
+    }
+
+    /// Insert or replace the function block with the given number.
+    pub fn insert(
+        &mut self,
+        number: u7,
+        block: &FunctionBlockDescriptor,
+    ) -> Result<(), InvalidData> {
+        let Some(slot) = self.blocks.get_mut(u8::from(number) as usize) else {
+            return Err(InvalidData(ERR_TOO_MANY_BLOCKS));
+        };
+        *slot = Some(Entry {
+            active: block.active,
+            first_group: u8::from(block.first_group),
+            number_of_groups_spanned: block.number_of_groups_spanned,
+            direction: block.direction,
+            midi1_port: block.midi1_port,
+        });
+        Ok(())
+    }
+
+    pub fn remove(&mut self, number: u7) {
+        if let Some(slot) = self.blocks.get_mut(u8::from(number) as usize) {
+            *slot = None;
+        }
+    }
+
+    /// The numbers of every block, active or not, whose range includes the group.
+    pub fn blocks_for_group(&self, group: u4) -> impl Iterator<Item = u7> + '_ {
+        self.entries()
+            .filter(move |(_, entry)| entry.owns(group))
+            .map(|(number, _)| number)
+    }
+
+    /// Whether messages may be sent to the endpoint on the group,
+    /// i.e. an active block owning the group receives input.
+    pub fn can_send(&self, group: u4) -> bool {
+        self.active_entries_for_group(group)
+            .any(|entry| matches!(entry.direction, Direction::Input | Direction::Bidirectional))
+    }
+
+    /// Whether messages may be received from the endpoint on the group,
+    /// i.e. an active block owning the group produces output.
+    pub fn can_receive(&self, group: u4) -> bool {
+        self.active_entries_for_group(group).any(|entry| {
+            matches!(
+                entry.direction,
+                Direction::Output | Direction::Bidirectional
+            )
+        })
+    }
+
+    /// Whether an active block owning the group represents a MIDI 1.0 port.
+    pub fn is_midi1(&self, group: u4) -> bool {
+        self.active_entries_for_group(group)
+            .any(|entry| entry.midi1_port.is_some())
+    }
+
+    /// Whether an active block owning the group represents a MIDI 1.0 port
+    /// restricted to 31.25 kb/s.
+    pub fn is_bandwidth_restricted(&self, group: u4) -> bool {
+        self.active_entries_for_group(group)
+            .any(|entry| entry.midi1_port == Some(Midi1Port::RestrictBandwidth))
+    }
+
+    /// Check the blocks against the rules of the UMP specification.
+    ///
+    /// Every block must span between 1 and 16 groups without going past group 15,
+    /// blocks must be numbered contiguously from zero,
+    /// and at least one of them must be active.
+    pub fn validate(&self) -> Result<(), InvalidData> {
+        let mut seen_gap = false;
+        let mut seen_active = false;
+        for slot in self.blocks.iter() {
+            let Some(entry) = slot else {
+                seen_gap = true;
+                continue;
+            };
+            if seen_gap {
+                return Err(InvalidData(ERR_NON_CONTIGUOUS_BLOCKS));
+            }
+            if entry.number_of_groups_spanned == 0 {
+                return Err(InvalidData(ERR_NO_GROUPS));
+            }
+            if entry.number_of_groups_spanned > NUMBER_OF_GROUPS {
+                return Err(InvalidData(ERR_TOO_MANY_GROUPS));
+            }
+            if entry.first_group + entry.number_of_groups_spanned > NUMBER_OF_GROUPS {
+                return Err(InvalidData(ERR_SPANS_PAST_LAST_GROUP));
+            }
+            seen_active |= entry.active;
+        }
+        if self.entries().next().is_some() && !seen_active {
+            return Err(InvalidData(ERR_NO_ACTIVE_BLOCKS));
+        }
+        Ok(())
+    }
+
+    fn entries(&self) -> impl Iterator<Item = (u7, &Entry)> {
+        self.blocks
+            .iter()
+            .enumerate()
+            .filter_map(|(i, slot)| slot.as_ref().map(|entry| (u7::new(i as u8), entry)))
+    }
+
+    fn active_entries_for_group(&self, group: u4) -> impl Iterator<Item = &Entry> {
+        self.entries()
+            .map(|(_, entry)| entry)
+            .filter(move |entry| entry.active && entry.owns(group))
+    }
+}
+
+const ERR_TOO_MANY_BLOCKS: &str = "An endpoint may declare at most 32 function blocks";
+const ERR_NON_CONTIGUOUS_BLOCKS: &str = "Function blocks must be numbered contiguously from 0";
+const ERR_NO_GROUPS: &str = "Function block must span at least one group";
+const ERR_TOO_MANY_GROUPS: &str = "Function block spans more than 16 groups";
+const ERR_SPANS_PAST_LAST_GROUP: &str = "Function block spans past group 15";
+const ERR_NO_ACTIVE_BLOCKS: &str = "At least one function block must be active";
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn block(first_group: u8, number_of_groups_spanned: u8) -> FunctionBlockDescriptor<'static> {
+        FunctionBlockDescriptor {
+            first_group: u4::new(first_group),
+            number_of_groups_spanned,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn blocks_for_group() {
+        let map =
+            FunctionBlockMap::from_descriptors(&[block(0, 4), block(2, 2), block(8, 1)]).unwrap();
+        assert_eq!(
+            map.blocks_for_group(u4::new(3))
+                .collect::<std::vec::Vec<_>>(),
+            std::vec![u7::new(0), u7::new(1)]
+        );
+        assert_eq!(map.blocks_for_group(u4::new(5)).count(), 0);
+        assert_eq!(
+            map.blocks_for_group(u4::new(8))
+                .collect::<std::vec::Vec<_>>(),
+            std::vec![u7::new(2)]
+        );
+    }
+
+    #[test]
+    fn inactive_blocks_are_not_routable() {
+        let map = FunctionBlockMap::from_descriptors(&[FunctionBlockDescriptor {
+            active: false,
+            ..block(0, 1)
+        }])
+        .unwrap();
+        assert_eq!(map.blocks_for_group(u4::new(0)).count(), 1);
+        assert!(!map.can_send(u4::new(0)));
+        assert!(!map.can_receive(u4::new(0)));
+    }
+
+    #[test]
+    fn directions() {
+        let map = FunctionBlockMap::from_descriptors(&[
+            FunctionBlockDescriptor {
+                direction: Direction::Input,
+                ..block(0, 1)
+            },
+            FunctionBlockDescriptor {
+                direction: Direction::Output,
+                ..block(1, 1)
+            },
+            FunctionBlockDescriptor {
+                direction: Direction::Bidirectional,
+                ..block(2, 1)
+            },
+        ])
+        .unwrap();
+        assert!(map.can_send(u4::new(0)));
+        assert!(!map.can_receive(u4::new(0)));
+        assert!(!map.can_send(u4::new(1)));
+        assert!(map.can_receive(u4::new(1)));
+        assert!(map.can_send(u4::new(2)));
+        assert!(map.can_receive(u4::new(2)));
+        assert!(!map.can_send(u4::new(3)));
+    }
+
+    #[test]
+    fn midi1_ports() {
+        let map = FunctionBlockMap::from_descriptors(&[
+            FunctionBlockDescriptor {
+                midi1_port: Some(Midi1Port::DontRestrictBandwidth),
+                ..block(0, 1)
+            },
+            FunctionBlockDescriptor {
+                midi1_port: Some(Midi1Port::RestrictBandwidth),
+                ..block(1, 1)
+            },
+        ])
+        .unwrap();
+        assert!(map.is_midi1(u4::new(0)));
+        assert!(!map.is_bandwidth_restricted(u4::new(0)));
+        assert!(map.is_midi1(u4::new(1)));
+        assert!(map.is_bandwidth_restricted(u4::new(1)));
+        assert!(!map.is_midi1(u4::new(2)));
+    }
+
+    #[test]
+    fn validate() {
+        assert_eq!(
+            FunctionBlockMap::from_descriptors(&[block(0, 16)])
+                .unwrap()
+                .validate(),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn validate_no_groups() {
+        assert_eq!(
+            FunctionBlockMap::from_descriptors(&[block(0, 0)])
+                .unwrap()
+                .validate(),
+            Err(InvalidData(ERR_NO_GROUPS))
+        );
+    }
+
+    #[test]
+    fn validate_too_many_groups() {
+        assert_eq!(
+            FunctionBlockMap::from_descriptors(&[block(0, 17)])
+                .unwrap()
+                .validate(),
+            Err(InvalidData(ERR_TOO_MANY_GROUPS))
+        );
+    }
+
+    #[test]
+    fn validate_spans_past_last_group() {
+        assert_eq!(
+            FunctionBlockMap::from_descriptors(&[block(15, 2)])
+                .unwrap()
+                .validate(),
+            Err(InvalidData(ERR_SPANS_PAST_LAST_GROUP))
+        );
+    }
+
+    #[test]
+    fn validate_no_active_blocks() {
+        let inactive = FunctionBlockDescriptor {
+            active: false,
+            ..block(0, 1)
+        };
+        let mut map = FunctionBlockMap::from_descriptors(&[inactive, inactive]).unwrap();
+        assert_eq!(map.validate(), Err(InvalidData(ERR_NO_ACTIVE_BLOCKS)));
+        map.insert(u7::new(1), &block(1, 1)).unwrap();
+        assert_eq!(map.validate(), Ok(()));
+    }
+
+    #[test]
+    fn validate_empty() {
+        assert_eq!(FunctionBlockMap::new().validate(), Ok(()));
+    }
+
+    #[test]
+    fn validate_non_contiguous_blocks() {
+        let mut map = FunctionBlockMap::new();
+        map.insert(u7::new(0), &block(0, 1)).unwrap();
+        map.insert(u7::new(2), &block(1, 1)).unwrap();
+        assert_eq!(map.validate(), Err(InvalidData(ERR_NON_CONTIGUOUS_BLOCKS)));
+        map.insert(u7::new(1), &block(2, 1)).unwrap();
+        assert_eq!(map.validate(), Ok(()));
+    }
+
+    #[test]
+    fn too_many_blocks() {
+        let blocks = [block(0, 1); 33];
+        assert_eq!(
+            FunctionBlockMap::from_descriptors(&blocks),
+            Err(InvalidData(ERR_TOO_MANY_BLOCKS))
+        );
+        assert_eq!(
+            FunctionBlockMap::new().insert(u7::new(32), &block(0, 1)),
+            Err(InvalidData(ERR_TOO_MANY_BLOCKS))
+        );
+    }
+}
@@ -0,0 +1,412 @@
+use crate::ump_stream::{
+    EndpointDescriptor, Protocol, StreamConfiguration, StreamConfigurationNotification,
+    StreamConfigurationRequest, UmpStream,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Capabilities {
+    midi1: bool,
+    midi2: bool,
+    receive_jr_timestamps: bool,
+    send_jr_timestamps: bool,
+}
+
+/// Negotiates the protocol and jitter reduction timestamps used
+/// between a local endpoint and a remote endpoint.
+///
+/// Incoming [UmpStream] messages are passed to [StreamProtocolNegotiator::receive].
+/// When the remote endpoint reports its capabilities with an
+/// [EndpointInfo](crate::ump_stream::EndpointInfo) message the negotiator requests the best
+/// configuration both endpoints support: MIDI 2.0 where possible, and jitter reduction
+/// timestamps in each direction where the sender and receiver both support them.
+/// Requests from the remote endpoint are applied as far as the local endpoint supports them
+/// and answered with a notification.
+///
+/// The [configuration](StreamProtocolNegotiator::configuration) is always from the point of view
+/// of the local endpoint, and only changes once the remote endpoint has confirmed it.
+///
+/// ```rust
+/// use midi2::{prelude::*, ump_stream::*};
+///
+/// let mut negotiator = StreamProtocolNegotiator::new(&EndpointDescriptor {
+///     supports_midi1_protocol: true,
+///     ..Default::default()
+/// });
+///
+/// let mut info = EndpointInfo::<[u32; 4]>::new();
+/// info.set_supports_midi1_protocol(true);
+///
+/// let Some(UmpStream::StreamConfigurationRequest(request)) = negotiator.receive(&info.into()) else {
+///     panic!("Expected a request");
+/// };
+/// assert_eq!(request.protocol(), 0x1);
+///
+/// let mut notification = StreamConfigurationNotification::<[u32; 4]>::new();
+/// notification.set_protocol(0x1);
+/// assert_eq!(negotiator.receive(&notification.into()), None);
+/// assert_eq!(negotiator.configuration().protocol, Protocol::Midi1);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StreamProtocolNegotiator {
+    local: Capabilities,
+    remote: Option<Capabilities>,
+    configuration: StreamConfiguration,
+}
+
+impl StreamProtocolNegotiator {
+    /// The initial configuration uses MIDI 2.0 if the local endpoint supports it
+    /// and has jitter reduction timestamps disabled.
+    pub fn new(local: &EndpointDescriptor) -> Self {
+        let protocol = if local.supports_midi2_protocol || !local.supports_midi1_protocol {
+            Protocol::Midi2
+        } else {
+            Protocol::Midi1
+        };
+        StreamProtocolNegotiator {
+            local: Capabilities {
+                midi1: local.supports_midi1_protocol,
+                midi2: local.supports_midi2_protocol,
+                receive_jr_timestamps: local.supports_receiving_jr_timestamps,
+                send_jr_timestamps: local.supports_sending_jr_timestamps,
+            },
+            remote: None,
+            configuration: StreamConfiguration {
+                protocol,
+                receive_jr_timestamps: false,
+                send_jr_timestamps: false,
+            },
+        }
+    }
+
+    /// The agreed configuration, from the point of view of the local endpoint.
+    pub fn configuration(&self) -> StreamConfiguration {
+        self.configuration
+    }
+
+    /// The best configuration supported by both endpoints, from the point of view
+    /// of the local endpoint.
+    ///
+    /// None until the remote endpoint's capabilities are known,
+    /// or if the endpoints have no protocol in common.
+    pub fn preferred_configuration(&self) -> Option<StreamConfiguration> {
+        let remote = self.remote?;
+        let local = self.local;
+        let protocol = if local.midi2 && remote.midi2 {
+            Protocol::Midi2
+        } else if local.midi1 && remote.midi1 {
+            Protocol::Midi1
+        } else {
+            return None;
+        };
+        Some(StreamConfiguration {
+            protocol,
+            receive_jr_timestamps: local.receive_jr_timestamps && remote.send_jr_timestamps,
+            send_jr_timestamps: local.send_jr_timestamps && remote.receive_jr_timestamps,
+        })
+    }
+
+    /// Process an incoming message, returning the reply to send back to the remote endpoint.
+    pub fn receive<B: crate::buffer::Ump>(
+        &mut self,
+        message: &UmpStream<B>,
+    ) -> Option<UmpStream<[u32; 4]>> {
+        match message {
+            UmpStream::EndpointInfo(info) => {
+                self.remote = Some(Capabilities {
+                    midi1: info.supports_midi1_protocol(),
+                    midi2: info.supports_midi2_protocol(),
+                    receive_jr_timestamps: info.supports_receiving_jr_timestamps(),
+                    send_jr_timestamps: info.supports_sending_jr_timestamps(),
+                });
+                let preferred = self.preferred_configuration()?;
+                if preferred == self.configuration {
+                    return None;
+                }
+                // the request is addressed to the remote endpoint,
+                // so the timestamp directions are swapped
+                let mut request = StreamConfigurationRequest::<[u32; 4]>::new();
+                request.set_protocol(preferred.protocol.to_u8());
+                request.set_receive_jr_timestamps(preferred.send_jr_timestamps);
+                request.set_send_jr_timestamps(preferred.receive_jr_timestamps);
+                Some(request.into())
+            }
+            UmpStream::StreamConfigurationNotification(notification) => {
+                if let Some(protocol) = Protocol::from_u8(notification.protocol()) {
+                    self.configuration.protocol = protocol;
+                }
+                self.configuration.receive_jr_timestamps = notification.send_jr_timestamps();
+                self.configuration.send_jr_timestamps = notification.receive_jr_timestamps();
+                None
+            }
+            UmpStream::StreamConfigurationRequest(request) => {
+                match Protocol::from_u8(request.protocol()) {
+                    Some(Protocol::Midi1) if self.local.midi1 => {
+                        self.configuration.protocol = Protocol::Midi1;
+                    }
+                    Some(Protocol::Midi2) if self.local.midi2 => {
+                        self.configuration.protocol = Protocol::Midi2;
+                    }
+                    _ => {}
+                }
+                self.configuration.receive_jr_timestamps =
+                    request.receive_jr_timestamps() && self.local.receive_jr_timestamps;
+                self.configuration.send_jr_timestamps =
+                    request.send_jr_timestamps() && self.local.send_jr_timestamps;
+                let mut notification = StreamConfigurationNotification::<[u32; 4]>::new();
+                notification.set_protocol(self.configuration.protocol.to_u8());
+                notification.set_receive_jr_timestamps(self.configuration.receive_jr_timestamps);
+                notification.set_send_jr_timestamps(self.configuration.send_jr_timestamps);
+                Some(notification.into())
+            }
+            _ => None,
+        }
+    }
+
+    /// Convert an outgoing MIDI 1.0 channel voice message to the negotiated protocol.
+    #[cfg(all(feature = "channel-voice1", feature = "channel-voice2"))]
+    pub fn convert_channel_voice1<B: crate::buffer::Ump>(
+        &self,
+        message: &crate::channel_voice1::ChannelVoice1<B>,
+    ) -> Converted {
+        use crate::{traits::Data, RebufferInto};
+
+        let mut converted = Converted::default();
+        match self.configuration.protocol {
+            Protocol::Midi1 => {
+                let message: crate::channel_voice1::ChannelVoice1<[u32; 4]> =
+                    crate::channel_voice1::ChannelVoice1::try_from(message.data())
+                        .expect("Valid message data")
+                        .rebuffer_into();
+                converted.message = Some(message.into());
+            }
+            Protocol::Midi2 => {
+                converted.message = Some(crate::translation::to_midi2(message).into());
+            }
+        }
+        converted
+    }
+
+    /// Convert an outgoing MIDI 2.0 channel voice message to the negotiated protocol.
+    ///
+    /// Messages with no MIDI 1.0 equivalent are dropped,
+    /// while others may become several MIDI 1.0 messages.
+    #[cfg(all(feature = "channel-voice1", feature = "channel-voice2"))]
+    pub fn convert_channel_voice2<B: crate::buffer::Ump>(
+        &self,
+        message: &crate::channel_voice2::ChannelVoice2<B>,
+    ) -> Converted {
+        use crate::{traits::Data, RebufferInto};
+
+        let mut converted = Converted::default();
+        match self.configuration.protocol {
+            Protocol::Midi1 => {
+                converted.midi1 = crate::translation::to_midi1(message);
+            }
+            Protocol::Midi2 => {
+                let message: crate::channel_voice2::ChannelVoice2<[u32; 4]> =
+                    crate::channel_voice2::ChannelVoice2::try_from(message.data())
+                        .expect("Valid message data")
+                        .rebuffer_into();
+                converted.message = Some(message.into());
+            }
+        }
+        converted
+    }
+}
+
+/// The messages produced by converting a channel voice message to the negotiated protocol.
+#[cfg(all(feature = "channel-voice1", feature = "channel-voice2"))]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Converted {
+    message: Option<crate::UmpMessage<[u32; 4]>>,
+    midi1: crate::translation::Midi1Messages,
+}
+
+#[cfg(all(feature = "channel-voice1", feature = "channel-voice2"))]
+impl core::iter::Iterator for Converted {
+    type Item = crate::UmpMessage<[u32; 4]>;
+    fn next(&mut self) -> Option<Self::Item> {
+        self.message
+            .take()
+            .or_else(|| self.midi1.next().map(Into::into))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{traits::Data, ump_stream::EndpointInfo};
+    use pretty_assertions::assert_eq;
+
+    fn endpoint_info(
+        midi1: bool,
+        midi2: bool,
+        receive_jr: bool,
+        send_jr: bool,
+    ) -> UmpStream<[u32; 4]> {
+        let mut info = EndpointInfo::<[u32; 4]>::new();
+        info.set_supports_midi1_protocol(midi1);
+        info.set_supports_midi2_protocol(midi2);
+        info.set_supports_receiving_jr_timestamps(receive_jr);
+        info.set_supports_sending_jr_timestamps(send_jr);
+        info.into()
+    }
+
+    fn notification(protocol: u8, receive_jr: bool, send_jr: bool) -> UmpStream<[u32; 4]> {
+        let mut notification = StreamConfigurationNotification::<[u32; 4]>::new();
+        notification.set_protocol(protocol);
+        notification.set_receive_jr_timestamps(receive_jr);
+        notification.set_send_jr_timestamps(send_jr);
+        notification.into()
+    }
+
+    fn both_protocols() -> EndpointDescriptor<'static> {
+        EndpointDescriptor {
+            supports_midi1_protocol: true,
+            supports_receiving_jr_timestamps: true,
+            supports_sending_jr_timestamps: true,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn initial_configuration() {
+        let negotiator = StreamProtocolNegotiator::new(&both_protocols());
+        assert_eq!(
+            negotiator.configuration(),
+            StreamConfiguration {
+                protocol: Protocol::Midi2,
+                receive_jr_timestamps: false,
+                send_jr_timestamps: false,
+            }
+        );
+        assert_eq!(negotiator.preferred_configuration(), None);
+    }
+
+    #[test]
+    fn requests_midi1_from_midi1_endpoint() {
+        let mut negotiator = StreamProtocolNegotiator::new(&both_protocols());
+        let reply = negotiator.receive(&endpoint_info(true, false, false, false));
+        assert_eq!(reply.unwrap().data(), &[0xF005_0100]);
+    }
+
+    #[test]
+    fn requests_jr_timestamps_supported_by_both_directions() {
+        let mut negotiator = StreamProtocolNegotiator::new(&both_protocols());
+        // the remote endpoint can only receive timestamps
+        let reply = negotiator.receive(&endpoint_info(true, true, true, false));
+        assert_eq!(reply.unwrap().data(), &[0xF005_0202]);
+    }
+
+    #[test]
+    fn no_request_when_already_agreed() {
+        let mut negotiator = StreamProtocolNegotiator::new(&both_protocols());
+        assert_eq!(
+            negotiator.receive(&endpoint_info(true, true, false, false)),
+            None
+        );
+    }
+
+    #[test]
+    fn no_request_without_common_protocol() {
+        let mut negotiator = StreamProtocolNegotiator::new(&EndpointDescriptor::default());
+        assert_eq!(
+            negotiator.receive(&endpoint_info(true, false, false, false)),
+            None
+        );
+    }
+
+    #[test]
+    fn notification_updates_configuration() {
+        let mut negotiator = StreamProtocolNegotiator::new(&both_protocols());
+        assert_eq!(negotiator.receive(&notification(0x1, true, false)), None);
+        assert_eq!(
+            negotiator.configuration(),
+            StreamConfiguration {
+                protocol: Protocol::Midi1,
+                receive_jr_timestamps: false,
+                send_jr_timestamps: true,
+            }
+        );
+    }
+
+    #[test]
+    fn answers_requests() {
+        let mut negotiator = StreamProtocolNegotiator::new(&EndpointDescriptor {
+            supports_midi1_protocol: true,
+            supports_receiving_jr_timestamps: true,
+            ..Default::default()
+        });
+        let mut request = StreamConfigurationRequest::<[u32; 4]>::new();
+        request.set_protocol(0x1);
+        request.set_receive_jr_timestamps(true);
+        request.set_send_jr_timestamps(true);
+
+        let reply = negotiator.receive(&request.into());
+        assert_eq!(reply.unwrap().data(), &[0xF006_0102]);
+        assert_eq!(
+            negotiator.configuration(),
+            StreamConfiguration {
+                protocol: Protocol::Midi1,
+                receive_jr_timestamps: true,
+                send_jr_timestamps: false,
+            }
+        );
+    }
+
+    #[cfg(all(feature = "channel-voice1", feature = "channel-voice2"))]
+    #[test]
+    fn converts_midi1_to_midi2() {
+        let negotiator = StreamProtocolNegotiator::new(&both_protocols());
+        let message =
+            crate::channel_voice1::ChannelVoice1::try_from(&[0x2395_3C7F_u32][..]).unwrap();
+        let converted = negotiator
+            .convert_channel_voice1(&message)
+            .collect::<std::vec::Vec<_>>();
+        assert_eq!(converted.len(), 1);
+        assert_eq!(converted[0].data(), &[0x4395_3C00, 0xFFFF_0000]);
+    }
+
+    #[cfg(all(feature = "channel-voice1", feature = "channel-voice2"))]
+    #[test]
+    fn passes_through_midi2() {
+        let negotiator = StreamProtocolNegotiator::new(&both_protocols());
+        let message =
+            crate::channel_voice2::ChannelVoice2::try_from(&[0x4395_3C00_u32, 0xFFFF_0000][..])
+                .unwrap();
+        let converted = negotiator
+            .convert_channel_voice2(&message)
+            .collect::<std::vec::Vec<_>>();
+        assert_eq!(converted.len(), 1);
+        assert_eq!(converted[0].data(), &[0x4395_3C00, 0xFFFF_0000]);
+    }
+
+    #[cfg(all(feature = "channel-voice1", feature = "channel-voice2"))]
+    #[test]
+    fn converts_midi2_to_midi1() {
+        let mut negotiator = StreamProtocolNegotiator::new(&both_protocols());
+        negotiator.receive(&notification(0x1, false, false));
+        let message =
+            crate::channel_voice2::ChannelVoice2::try_from(&[0x41C2_0001_u32, 0x0500_0302][..])
+                .unwrap();
+        let converted = negotiator
+            .convert_channel_voice2(&message)
+            .map(|m| m.data()[0])
+            .collect::<std::vec::Vec<_>>();
+        assert_eq!(converted, std::vec![0x21B2_0002, 0x21B2_2003, 0x21C2_0500]);
+    }
+
+    #[cfg(all(feature = "channel-voice1", feature = "channel-voice2"))]
+    #[test]
+    fn passes_through_midi1() {
+        let mut negotiator = StreamProtocolNegotiator::new(&both_protocols());
+        negotiator.receive(&notification(0x1, false, false));
+        let message =
+            crate::channel_voice1::ChannelVoice1::try_from(&[0x2395_3C7F_u32][..]).unwrap();
+        let converted = negotiator
+            .convert_channel_voice1(&message)
+            .collect::<std::vec::Vec<_>>();
+        assert_eq!(converted.len(), 1);
+        assert_eq!(converted[0].data(), &[0x2395_3C7F]);
+    }
+}
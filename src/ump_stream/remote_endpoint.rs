@@ -0,0 +1,528 @@
+use crate::{
+    detail::Queue,
+    ump_stream::{
+        Direction, FunctionBlockDescriptor, Midi1Port, Protocol, StreamConfiguration, UiHint,
+        UmpStream,
+    },
+    ux::{u14, u4, u7},
+};
+
+const MAX_FUNCTION_BLOCKS: usize = 32;
+const MAX_ENDPOINT_NAME_SIZE: usize = 98;
+const MAX_PRODUCT_INSTANCE_ID_SIZE: usize = 42;
+const MAX_FUNCTION_BLOCK_NAME_SIZE: usize = 91;
+const EVENT_QUEUE_SIZE: usize = 8;
+
+/// The contents of a remote [EndpointInfo](crate::ump_stream::EndpointInfo) message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RemoteEndpointInfo {
+    pub ump_version_major: u8,
+    pub ump_version_minor: u8,
+    pub static_function_blocks: bool,
+    pub supports_midi2_protocol: bool,
+    pub supports_midi1_protocol: bool,
+    pub supports_receiving_jr_timestamps: bool,
+    pub supports_sending_jr_timestamps: bool,
+    pub number_of_function_blocks: u7,
+}
+
+/// The contents of a remote [DeviceIdentity](crate::ump_stream::DeviceIdentity) message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RemoteDeviceIdentity {
+    pub device_manufacturer: crate::ManufacturerId,
+    pub device_family: u14,
+    pub device_family_model_number: u14,
+    pub software_version: [u7; 4],
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RemoteEndpointEvent {
+    /// Every function block declared by the endpoint info has been reported.
+    DiscoveryComplete,
+    EndpointInfoChanged,
+    DeviceIdentityChanged,
+    NameChanged,
+    ProductInstanceIdChanged,
+    StreamConfigurationChanged,
+    /// The info or name of the function block with the given number was updated.
+    FunctionBlockChanged(u7),
+}
+
+/// Collects the replies to UMP stream discovery into a model of a remote endpoint.
+///
+/// Complete [UmpStream] messages are fed in with [RemoteEndpoint::receive].
+/// Discovery is complete once the endpoint info has arrived along with
+/// the info of every function block it declares.
+/// From then on, any update to the model emits a [RemoteEndpointEvent].
+///
+/// The model doesn't allocate: names are kept in fixed size buffers
+/// and at most 32 function blocks are tracked.
+///
+/// ```rust
+/// use midi2::{prelude::*, ump_stream::*};
+///
+/// let mut endpoint = RemoteEndpoint::new();
+///
+/// let mut info = EndpointInfo::<[u32; 4]>::new();
+/// info.set_number_of_function_blocks(u7::new(1));
+/// endpoint.receive(&info.into());
+/// assert!(!endpoint.is_complete());
+///
+/// let mut block = FunctionBlockInfo::<[u32; 4]>::new();
+/// block.set_function_block_number(u7::new(0));
+/// endpoint.receive(&block.into());
+/// assert!(endpoint.is_complete());
+/// assert_eq!(endpoint.poll_event(), Some(RemoteEndpointEvent::DiscoveryComplete));
+/// ```
+#[derive(Debug, Clone)]
+pub struct RemoteEndpoint {
+    info: Option<RemoteEndpointInfo>,
+    device_identity: Option<RemoteDeviceIdentity>,
+    name: Option<Text<MAX_ENDPOINT_NAME_SIZE>>,
+    product_instance_id: Option<Text<MAX_PRODUCT_INSTANCE_ID_SIZE>>,
+    stream_configuration: Option<StreamConfiguration>,
+    function_blocks: [FunctionBlock; MAX_FUNCTION_BLOCKS],
+    complete: bool,
+    events: Queue<RemoteEndpointEvent, EVENT_QUEUE_SIZE>,
+}
+
+impl core::default::Default for RemoteEndpoint {
+    fn default() -> Self {
+        RemoteEndpoint {
+            info: None,
+            device_identity: None,
+            name: None,
+            product_instance_id: None,
+            stream_configuration: None,
+            function_blocks: [FunctionBlock::default(); MAX_FUNCTION_BLOCKS],
+            complete: false,
+            events: Default::default(),
+        }
+    }
+}
+
+impl RemoteEndpoint {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn info(&self) -> Option<RemoteEndpointInfo> {
+        self.info
+    }
+
+    pub fn device_identity(&self) -> Option<RemoteDeviceIdentity> {
+        self.device_identity
+    }
+
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_ref().map(Text::as_str)
+    }
+
+    pub fn product_instance_id(&self) -> Option<&str> {
+        self.product_instance_id.as_ref().map(Text::as_str)
+    }
+
+    pub fn stream_configuration(&self) -> Option<StreamConfiguration> {
+        self.stream_configuration
+    }
+
+    /// The function block with the given number, if its info has been received.
+    pub fn function_block(&self, number: u7) -> Option<FunctionBlockDescriptor<'_>> {
+        self.function_blocks
+            .get(u8::from(number) as usize)
+            .and_then(FunctionBlock::descriptor)
+    }
+
+    /// All the function blocks whose info has been received, with their numbers.
+    pub fn function_blocks(&self) -> impl Iterator<Item = (u7, FunctionBlockDescriptor<'_>)> {
+        self.function_blocks
+            .iter()
+            .enumerate()
+            .filter_map(|(i, block)| block.descriptor().map(|d| (u7::new(i as u8), d)))
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.complete
+    }
+
+    pub fn poll_event(&mut self) -> Option<RemoteEndpointEvent> {
+        self.events.pop()
+    }
+
+    /// Update the model from an incoming message.
+    ///
+    /// Messages which aren't discovery replies are ignored.
+    pub fn receive<B: crate::buffer::Ump>(&mut self, message: &UmpStream<B>) {
+        use RemoteEndpointEvent::*;
+
+        match message {
+            UmpStream::EndpointInfo(m) => {
+                let info = RemoteEndpointInfo {
+                    ump_version_major: m.ump_version_major(),
+                    ump_version_minor: m.ump_version_minor(),
+                    static_function_blocks: m.static_function_blocks(),
+                    supports_midi2_protocol: m.supports_midi2_protocol(),
+                    supports_midi1_protocol: m.supports_midi1_protocol(),
+                    supports_receiving_jr_timestamps: m.supports_receiving_jr_timestamps(),
+                    supports_sending_jr_timestamps: m.supports_sending_jr_timestamps(),
+                    number_of_function_blocks: m.number_of_function_blocks(),
+                };
+                if self.info.replace(info) != Some(info) {
+                    self.changed(EndpointInfoChanged);
+                }
+            }
+            UmpStream::DeviceIdentity(m) => {
+                let identity = RemoteDeviceIdentity {
+                    device_manufacturer: m.device_manufacturer(),
+                    device_family: m.device_family(),
+                    device_family_model_number: m.device_family_model_number(),
+                    software_version: m.software_version(),
+                };
+                if self.device_identity.replace(identity) != Some(identity) {
+                    self.changed(DeviceIdentityChanged);
+                }
+            }
+            UmpStream::EndpointName(m) => {
+                let name = Some(Text::from_bytes(m.name_bytes()));
+                if self.name != name {
+                    self.name = name;
+                    self.changed(NameChanged);
+                }
+            }
+            UmpStream::ProductInstanceId(m) => {
+                let id = Some(Text::from_bytes(m.id_bytes()));
+                if self.product_instance_id != id {
+                    self.product_instance_id = id;
+                    self.changed(ProductInstanceIdChanged);
+                }
+            }
+            UmpStream::StreamConfigurationNotification(m) => {
+                let Some(protocol) = Protocol::from_u8(m.protocol()) else {
+                    return;
+                };
+                let configuration = StreamConfiguration {
+                    protocol,
+                    receive_jr_timestamps: m.receive_jr_timestamps(),
+                    send_jr_timestamps: m.send_jr_timestamps(),
+                };
+                if self.stream_configuration.replace(configuration) != Some(configuration) {
+                    self.changed(StreamConfigurationChanged);
+                }
+            }
+            UmpStream::FunctionBlockInfo(m) => {
+                let number = m.function_block_number();
+                let Some(block) = self.function_blocks.get_mut(u8::from(number) as usize) else {
+                    return;
+                };
+                let info = FunctionBlockInfoData {
+                    active: m.active(),
+                    first_group: m.first_group(),
+                    number_of_groups_spanned: m.number_of_groups_spanned(),
+                    midi_ci_version: m.midi_ci_version(),
+                    max_number_of_midi_ci_streams: m.max_number_of_midi_ci_streams(),
+                    ui_hint: m.ui_hint(),
+                    midi1_port: m.midi1_port(),
+                    direction: m.direction(),
+                };
+                if block.info.replace(info) != Some(info) {
+                    self.changed(FunctionBlockChanged(number));
+                }
+            }
+            UmpStream::FunctionBlockName(m) => {
+                let number = m.function_block();
+                let Some(block) = self.function_blocks.get_mut(number as usize) else {
+                    return;
+                };
+                let name = Some(Text::from_bytes(m.name_bytes()));
+                if block.name != name {
+                    block.name = name;
+                    self.changed(FunctionBlockChanged(u7::new(number)));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn changed(&mut self, event: RemoteEndpointEvent) {
+        if self.complete {
+            self.events.push(event);
+        } else if self.declared_function_blocks_received() {
+            self.complete = true;
+            self.events.push(RemoteEndpointEvent::DiscoveryComplete);
+        }
+    }
+
+    fn declared_function_blocks_received(&self) -> bool {
+        let Some(info) = self.info else {
+            return false;
+        };
+        let declared = (u8::from(info.number_of_function_blocks) as usize).min(MAX_FUNCTION_BLOCKS);
+        self.function_blocks[..declared]
+            .iter()
+            .all(|block| block.info.is_some())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct FunctionBlockInfoData {
+    active: bool,
+    first_group: u4,
+    number_of_groups_spanned: u8,
+    midi_ci_version: u8,
+    max_number_of_midi_ci_streams: u8,
+    ui_hint: UiHint,
+    midi1_port: Option<Midi1Port>,
+    direction: Direction,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+struct FunctionBlock {
+    info: Option<FunctionBlockInfoData>,
+    name: Option<Text<MAX_FUNCTION_BLOCK_NAME_SIZE>>,
+}
+
+impl FunctionBlock {
+    fn descriptor(&self) -> Option<FunctionBlockDescriptor<'_>> {
+        let info = self.info?;
+        Some(FunctionBlockDescriptor {
+            active: info.active,
+            first_group: info.first_group,
+            number_of_groups_spanned: info.number_of_groups_spanned,
+            midi_ci_version: info.midi_ci_version,
+            max_number_of_midi_ci_streams: info.max_number_of_midi_ci_streams,
+            ui_hint: info.ui_hint,
+            midi1_port: info.midi1_port,
+            direction: info.direction,
+            name: self.name.as_ref().map(Text::as_str),
+        })
+    }
+}
+
+/// Utf8 text stored in a fixed size buffer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Text<const N: usize> {
+    data: [u8; N],
+    len: usize,
+}
+
+impl<const N: usize> Text<N> {
+    /// Bytes beyond the capacity are dropped,
+    /// as is any trailing invalid utf8.
+    fn from_bytes(bytes: impl Iterator<Item = u8>) -> Self {
+        let mut data = [0x0; N];
+        let mut len = 0;
+        for (slot, b) in data.iter_mut().zip(bytes) {
+            *slot = b;
+            len += 1;
+        }
+        len = match core::str::from_utf8(&data[..len]) {
+            Ok(_) => len,
+            Err(e) => e.valid_up_to(),
+        };
+        Text { data, len }
+    }
+
+    fn as_str(&self) -> &str {
+        core::str::from_utf8(&self.data[..self.len]).unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ump_stream::{
+        DeviceIdentity, EndpointDescriptor, EndpointDiscovery, EndpointInfo, EndpointName,
+        EndpointResponder, FunctionBlockDiscovery, FunctionBlockInfo, FunctionBlockName,
+        ProductInstanceId,
+    };
+    use pretty_assertions::assert_eq;
+
+    fn info(number_of_function_blocks: u8) -> UmpStream<[u32; 4]> {
+        let mut message = EndpointInfo::<[u32; 4]>::new();
+        message.set_ump_version_major(0x1);
+        message.set_ump_version_minor(0x1);
+        message.set_supports_midi2_protocol(true);
+        message.set_number_of_function_blocks(u7::new(number_of_function_blocks));
+        message.into()
+    }
+
+    fn block(number: u8, active: bool) -> UmpStream<[u32; 4]> {
+        let mut message = FunctionBlockInfo::<[u32; 4]>::new();
+        message.set_function_block_number(u7::new(number));
+        message.set_active(active);
+        message.into()
+    }
+
+    #[test]
+    fn endpoint_info() {
+        let mut endpoint = RemoteEndpoint::new();
+        endpoint.receive(&info(0));
+        assert_eq!(
+            endpoint.info(),
+            Some(RemoteEndpointInfo {
+                ump_version_major: 0x1,
+                ump_version_minor: 0x1,
+                static_function_blocks: false,
+                supports_midi2_protocol: true,
+                supports_midi1_protocol: false,
+                supports_receiving_jr_timestamps: false,
+                supports_sending_jr_timestamps: false,
+                number_of_function_blocks: u7::new(0),
+            })
+        );
+    }
+
+    #[test]
+    fn complete_without_function_blocks() {
+        let mut endpoint = RemoteEndpoint::new();
+        assert!(!endpoint.is_complete());
+        endpoint.receive(&info(0));
+        assert!(endpoint.is_complete());
+        assert_eq!(
+            endpoint.poll_event(),
+            Some(RemoteEndpointEvent::DiscoveryComplete)
+        );
+        assert_eq!(endpoint.poll_event(), None);
+    }
+
+    #[test]
+    fn complete_when_all_declared_blocks_received() {
+        let mut endpoint = RemoteEndpoint::new();
+        endpoint.receive(&block(1, true));
+        endpoint.receive(&info(2));
+        assert!(!endpoint.is_complete());
+        endpoint.receive(&block(0, true));
+        assert!(endpoint.is_complete());
+        assert_eq!(
+            endpoint.poll_event(),
+            Some(RemoteEndpointEvent::DiscoveryComplete)
+        );
+        assert_eq!(endpoint.function_blocks().count(), 2);
+    }
+
+    #[test]
+    fn function_block_update_after_discovery() {
+        let mut endpoint = RemoteEndpoint::new();
+        endpoint.receive(&info(1));
+        endpoint.receive(&block(0, true));
+        endpoint.poll_event();
+
+        endpoint.receive(&block(0, true));
+        assert_eq!(endpoint.poll_event(), None);
+
+        endpoint.receive(&block(0, false));
+        assert_eq!(
+            endpoint.poll_event(),
+            Some(RemoteEndpointEvent::FunctionBlockChanged(u7::new(0)))
+        );
+        assert!(!endpoint.function_block(u7::new(0)).unwrap().active);
+    }
+
+    #[test]
+    fn names() {
+        let mut endpoint = RemoteEndpoint::new();
+
+        let mut name = EndpointName::<[u32; 8]>::new();
+        name.try_set_name("Gimme some signal 🔊 🙌").unwrap();
+        endpoint.receive(&name.into());
+
+        let mut id = ProductInstanceId::<[u32; 8]>::new();
+        id.try_set_id("PianoPrime").unwrap();
+        endpoint.receive(&id.into());
+
+        let mut block_name = FunctionBlockName::<[u32; 8]>::new();
+        block_name.try_set_name("SynthWave🌊²").unwrap();
+        block_name.set_function_block(0x1);
+        endpoint.receive(&block_name.into());
+        endpoint.receive(&block(1, true));
+
+        assert_eq!(endpoint.name(), Some("Gimme some signal 🔊 🙌"));
+        assert_eq!(endpoint.product_instance_id(), Some("PianoPrime"));
+        assert_eq!(
+            endpoint.function_block(u7::new(1)).unwrap().name,
+            Some("SynthWave🌊²")
+        );
+    }
+
+    #[test]
+    fn function_block_without_info_is_not_reported() {
+        let mut endpoint = RemoteEndpoint::new();
+        let mut block_name = FunctionBlockName::<[u32; 4]>::new();
+        block_name.try_set_name("Keys").unwrap();
+        endpoint.receive(&block_name.into());
+        assert_eq!(endpoint.function_block(u7::new(0)), None);
+    }
+
+    #[test]
+    fn device_identity() {
+        let mut endpoint = RemoteEndpoint::new();
+        let mut message = DeviceIdentity::<[u32; 4]>::new();
+        message.set_device_family(u14::new(0x1234));
+        endpoint.receive(&message.into());
+        assert_eq!(
+            endpoint.device_identity().unwrap().device_family,
+            u14::new(0x1234)
+        );
+    }
+
+    #[test]
+    fn discovery_with_responder() {
+        let blocks = [
+            FunctionBlockDescriptor {
+                name: Some("Keys"),
+                ..Default::default()
+            },
+            FunctionBlockDescriptor {
+                direction: Direction::Input,
+                ..Default::default()
+            },
+        ];
+        let descriptor = EndpointDescriptor {
+            name: Some("Synth"),
+            product_instance_id: Some("0001"),
+            function_blocks: &blocks,
+            ..Default::default()
+        };
+        let mut responder = EndpointResponder::new(descriptor);
+        let mut endpoint = RemoteEndpoint::new();
+
+        let mut discovery = EndpointDiscovery::<[u32; 4]>::new();
+        discovery.set_request_endpoint_info(true);
+        discovery.set_request_device_identity(true);
+        discovery.set_request_endpoint_name(true);
+        discovery.set_request_product_instance_id(true);
+        discovery.set_request_stream_configuration(true);
+        for reply in responder.respond(&discovery.into()) {
+            endpoint.receive(&reply);
+        }
+
+        let mut discovery = FunctionBlockDiscovery::<[u32; 4]>::new();
+        discovery.set_function_block_number(0xFF);
+        discovery.set_requesting_function_block_info(true);
+        discovery.set_requesting_function_block_name(true);
+        for reply in responder.respond(&discovery.into()) {
+            endpoint.receive(&reply);
+        }
+
+        assert!(endpoint.is_complete());
+        assert_eq!(endpoint.name(), Some("Synth"));
+        assert_eq!(endpoint.product_instance_id(), Some("0001"));
+        assert_eq!(
+            endpoint.stream_configuration(),
+            Some(responder.configuration())
+        );
+        assert_eq!(
+            endpoint
+                .function_blocks()
+                .map(|(_, block)| block)
+                .collect::<std::vec::Vec<_>>(),
+            blocks
+        );
+    }
+
+    #[test]
+    fn text_drops_trailing_invalid_utf8() {
+        let text = Text::<5>::from_bytes("abcd🔊".bytes());
+        assert_eq!(text.as_str(), "abcd");
+    }
+}
@@ -0,0 +1,613 @@
+use crate::{
+    ump_stream::{
+        DeviceIdentity, Direction, EndpointInfo, EndpointName, FunctionBlockInfo,
+        FunctionBlockName, Midi1Port, ProductInstanceId, StreamConfigurationNotification, UiHint,
+        UmpStream,
+    },
+    ux::{u14, u4, u7},
+};
+
+/// The size of the buffers backing the replies produced by an [EndpointResponder].
+///
+/// Large enough for the longest endpoint and function block names.
+pub const REPLY_SIZE: usize = 28;
+
+/// The type of the replies produced by an [EndpointResponder].
+pub type Reply = UmpStream<[u32; REPLY_SIZE]>;
+
+const MAX_ENDPOINT_NAME_SIZE: usize = 98;
+const MAX_PRODUCT_INSTANCE_ID_SIZE: usize = 42;
+const MAX_FUNCTION_BLOCK_NAME_SIZE: usize = 91;
+const ALL_FUNCTION_BLOCKS: u8 = 0xFF;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Protocol {
+    Midi1,
+    Midi2,
+}
+
+impl Protocol {
+    pub fn from_u8(v: u8) -> Option<Self> {
+        match v {
+            0x1 => Some(Protocol::Midi1),
+            0x2 => Some(Protocol::Midi2),
+            _ => None,
+        }
+    }
+    pub fn to_u8(self) -> u8 {
+        match self {
+            Protocol::Midi1 => 0x1,
+            Protocol::Midi2 => 0x2,
+        }
+    }
+}
+
+/// The static description of a function block, used by [EndpointResponder]
+/// to answer function block discovery.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FunctionBlockDescriptor<'a> {
+    pub active: bool,
+    pub first_group: u4,
+    pub number_of_groups_spanned: u8,
+    pub midi_ci_version: u8,
+    pub max_number_of_midi_ci_streams: u8,
+    pub ui_hint: UiHint,
+    pub midi1_port: Option<Midi1Port>,
+    pub direction: Direction,
+    /// Names longer than 91 bytes are truncated.
+    pub name: Option<&'a str>,
+}
+
+impl core::default::Default for FunctionBlockDescriptor<'_> {
+    fn default() -> Self {
+        FunctionBlockDescriptor {
+            active: true,
+            first_group: u4::new(0x0),
+            number_of_groups_spanned: 1,
+            midi_ci_version: 0x0,
+            max_number_of_midi_ci_streams: 0x0,
+            ui_hint: UiHint::Undeclared,
+            midi1_port: None,
+            direction: Direction::default(),
+            name: None,
+        }
+    }
+}
+
+/// The static description of a UMP endpoint, used by [EndpointResponder]
+/// to answer endpoint discovery.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EndpointDescriptor<'a> {
+    pub ump_version_major: u8,
+    pub ump_version_minor: u8,
+    pub static_function_blocks: bool,
+    pub supports_midi2_protocol: bool,
+    pub supports_midi1_protocol: bool,
+    pub supports_receiving_jr_timestamps: bool,
+    pub supports_sending_jr_timestamps: bool,
+    pub device_manufacturer: crate::ManufacturerId,
+    pub device_family: u14,
+    pub device_family_model_number: u14,
+    pub software_version: [u7; 4],
+    /// Names longer than 98 bytes are truncated.
+    pub name: Option<&'a str>,
+    /// Ids longer than 42 bytes are truncated.
+    pub product_instance_id: Option<&'a str>,
+    /// At most 32 function blocks are reported.
+    pub function_blocks: &'a [FunctionBlockDescriptor<'a>],
+}
+
+impl core::default::Default for EndpointDescriptor<'_> {
+    /// Default describes a MIDI 2.0 only endpoint implementing UMP version 1.1.
+    fn default() -> Self {
+        EndpointDescriptor {
+            ump_version_major: 0x1,
+            ump_version_minor: 0x1,
+            static_function_blocks: false,
+            supports_midi2_protocol: true,
+            supports_midi1_protocol: false,
+            supports_receiving_jr_timestamps: false,
+            supports_sending_jr_timestamps: false,
+            device_manufacturer: Default::default(),
+            device_family: Default::default(),
+            device_family_model_number: Default::default(),
+            software_version: Default::default(),
+            name: None,
+            product_instance_id: None,
+            function_blocks: &[],
+        }
+    }
+}
+
+/// The currently negotiated stream configuration of an endpoint.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StreamConfiguration {
+    pub protocol: Protocol,
+    pub receive_jr_timestamps: bool,
+    pub send_jr_timestamps: bool,
+}
+
+/// Answers UMP stream discovery on behalf of an endpoint.
+///
+/// Incoming [UmpStream] messages are passed to [EndpointResponder::respond]
+/// which yields exactly the replies requested by the discovery filters.
+/// Stream configuration requests are applied as far as the endpoint supports them,
+/// and always answered with a notification of the resulting configuration.
+///
+/// ```rust
+/// use midi2::{prelude::*, ump_stream::*};
+///
+/// let descriptor = EndpointDescriptor {
+///     name: Some("Synth"),
+///     ..Default::default()
+/// };
+/// let mut responder = EndpointResponder::new(descriptor);
+///
+/// let mut discovery = EndpointDiscovery::<[u32; 4]>::new();
+/// discovery.set_request_endpoint_info(true);
+/// discovery.set_request_endpoint_name(true);
+///
+/// let mut replies = responder.respond(&discovery.into());
+/// assert!(matches!(replies.next(), Some(UmpStream::EndpointInfo(_))));
+/// assert!(matches!(replies.next(), Some(UmpStream::EndpointName(_))));
+/// assert!(replies.next().is_none());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EndpointResponder<'a> {
+    descriptor: EndpointDescriptor<'a>,
+    configuration: StreamConfiguration,
+}
+
+impl<'a> EndpointResponder<'a> {
+    /// The initial configuration uses MIDI 2.0 if supported
+    /// and has jitter reduction timestamps disabled.
+    pub fn new(descriptor: EndpointDescriptor<'a>) -> Self {
+        let protocol = if descriptor.supports_midi2_protocol || !descriptor.supports_midi1_protocol
+        {
+            Protocol::Midi2
+        } else {
+            Protocol::Midi1
+        };
+        EndpointResponder {
+            descriptor,
+            configuration: StreamConfiguration {
+                protocol,
+                receive_jr_timestamps: false,
+                send_jr_timestamps: false,
+            },
+        }
+    }
+
+    pub fn descriptor(&self) -> &EndpointDescriptor<'a> {
+        &self.descriptor
+    }
+
+    pub fn configuration(&self) -> StreamConfiguration {
+        self.configuration
+    }
+
+    /// Process an incoming message, returning the replies to send back.
+    ///
+    /// Messages which don't call for a reply yield no replies.
+    pub fn respond<B: crate::buffer::Ump>(&mut self, message: &UmpStream<B>) -> Replies<'_, 'a> {
+        let mut pending = 0;
+        let mut function_blocks = 0..0;
+        let mut function_block_info = false;
+        let mut function_block_name = false;
+
+        match message {
+            UmpStream::EndpointDiscovery(discovery) => {
+                let requests = [
+                    discovery.request_endpoint_info(),
+                    discovery.request_device_identity(),
+                    discovery.request_endpoint_name() && self.descriptor.name.is_some(),
+                    discovery.request_product_instance_id()
+                        && self.descriptor.product_instance_id.is_some(),
+                    discovery.request_stream_configuration(),
+                ];
+                for (i, requested) in requests.iter().enumerate() {
+                    if *requested {
+                        pending |= 1 << i;
+                    }
+                }
+            }
+            UmpStream::FunctionBlockDiscovery(discovery) => {
+                let number_of_blocks = self.number_of_function_blocks();
+                let requested = discovery.function_block_number() as usize;
+                function_blocks = if requested == ALL_FUNCTION_BLOCKS as usize {
+                    0..number_of_blocks
+                } else if requested < number_of_blocks {
+                    requested..requested + 1
+                } else {
+                    0..0
+                };
+                function_block_info = discovery.requesting_function_block_info();
+                function_block_name = discovery.requesting_function_block_name();
+            }
+            UmpStream::StreamConfigurationRequest(request) => {
+                self.configure(
+                    Protocol::from_u8(request.protocol()),
+                    request.receive_jr_timestamps(),
+                    request.send_jr_timestamps(),
+                );
+                pending = PENDING_STREAM_CONFIGURATION;
+            }
+            _ => {}
+        }
+
+        Replies {
+            descriptor: &self.descriptor,
+            configuration: self.configuration,
+            pending,
+            function_blocks,
+            function_block_info,
+            function_block_name,
+            next_is_name: false,
+        }
+    }
+
+    fn configure(
+        &mut self,
+        protocol: Option<Protocol>,
+        receive_jr_timestamps: bool,
+        send_jr_timestamps: bool,
+    ) {
+        match protocol {
+            Some(Protocol::Midi1) if self.descriptor.supports_midi1_protocol => {
+                self.configuration.protocol = Protocol::Midi1;
+            }
+            Some(Protocol::Midi2) if self.descriptor.supports_midi2_protocol => {
+                self.configuration.protocol = Protocol::Midi2;
+            }
+            _ => {}
+        }
+        self.configuration.receive_jr_timestamps =
+            receive_jr_timestamps && self.descriptor.supports_receiving_jr_timestamps;
+        self.configuration.send_jr_timestamps =
+            send_jr_timestamps && self.descriptor.supports_sending_jr_timestamps;
+    }
+
+    fn number_of_function_blocks(&self) -> usize {
+        self.descriptor.function_blocks.len().min(32)
+    }
+}
+
+const PENDING_ENDPOINT_INFO: u8 = 0b0000_0001;
+const PENDING_DEVICE_IDENTITY: u8 = 0b0000_0010;
+const PENDING_ENDPOINT_NAME: u8 = 0b0000_0100;
+const PENDING_PRODUCT_INSTANCE_ID: u8 = 0b0000_1000;
+const PENDING_STREAM_CONFIGURATION: u8 = 0b0001_0000;
+
+/// The replies to a single incoming message, produced by [EndpointResponder::respond].
+#[derive(Debug, Clone)]
+pub struct Replies<'r, 'a> {
+    descriptor: &'r EndpointDescriptor<'a>,
+    configuration: StreamConfiguration,
+    pending: u8,
+    function_blocks: core::ops::Range<usize>,
+    function_block_info: bool,
+    function_block_name: bool,
+    next_is_name: bool,
+}
+
+impl<'r, 'a> core::iter::Iterator for Replies<'r, 'a> {
+    type Item = Reply;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.pending != 0 {
+            let next = self.pending & self.pending.wrapping_neg();
+            self.pending &= !next;
+            return Some(match next {
+                PENDING_ENDPOINT_INFO => self.endpoint_info(),
+                PENDING_DEVICE_IDENTITY => self.device_identity(),
+                PENDING_ENDPOINT_NAME => self.endpoint_name(),
+                PENDING_PRODUCT_INSTANCE_ID => self.product_instance_id(),
+                PENDING_STREAM_CONFIGURATION => self.stream_configuration(),
+                _ => unreachable!(),
+            });
+        }
+
+        while !self.function_blocks.is_empty() {
+            let index = self.function_blocks.start;
+            let block = &self.descriptor.function_blocks[index];
+            if self.next_is_name {
+                self.next_is_name = false;
+                self.function_blocks.start += 1;
+                if let (true, Some(name)) = (self.function_block_name, block.name) {
+                    return Some(function_block_name(index, name));
+                }
+            } else {
+                self.next_is_name = true;
+                if self.function_block_info {
+                    return Some(function_block_info(index, block));
+                }
+            }
+        }
+
+        None
+    }
+}
+
+impl<'r, 'a> Replies<'r, 'a> {
+    fn endpoint_info(&self) -> Reply {
+        let descriptor = self.descriptor;
+        let mut message = EndpointInfo::<[u32; REPLY_SIZE]>::new();
+        message.set_ump_version_major(descriptor.ump_version_major);
+        message.set_ump_version_minor(descriptor.ump_version_minor);
+        message.set_static_function_blocks(descriptor.static_function_blocks);
+        message.set_supports_midi2_protocol(descriptor.supports_midi2_protocol);
+        message.set_supports_midi1_protocol(descriptor.supports_midi1_protocol);
+        message.set_supports_receiving_jr_timestamps(descriptor.supports_receiving_jr_timestamps);
+        message.set_supports_sending_jr_timestamps(descriptor.supports_sending_jr_timestamps);
+        message
+            .set_number_of_function_blocks(u7::new(descriptor.function_blocks.len().min(32) as u8));
+        message.into()
+    }
+
+    fn device_identity(&self) -> Reply {
+        let descriptor = self.descriptor;
+        let mut message = DeviceIdentity::<[u32; REPLY_SIZE]>::new();
+        message.set_device_manufacturer(descriptor.device_manufacturer);
+        message.set_device_family(descriptor.device_family);
+        message.set_device_family_model_number(descriptor.device_family_model_number);
+        message.set_software_version(descriptor.software_version);
+        message.into()
+    }
+
+    fn endpoint_name(&self) -> Reply {
+        let name = truncate(self.descriptor.name.unwrap_or(""), MAX_ENDPOINT_NAME_SIZE);
+        let mut message = EndpointName::<[u32; REPLY_SIZE]>::new();
+        message
+            .try_set_name(name)
+            .expect("Buffer is large enough for truncated name");
+        message.into()
+    }
+
+    fn product_instance_id(&self) -> Reply {
+        let id = truncate(
+            self.descriptor.product_instance_id.unwrap_or(""),
+            MAX_PRODUCT_INSTANCE_ID_SIZE,
+        );
+        let mut message = ProductInstanceId::<[u32; REPLY_SIZE]>::new();
+        message
+            .try_set_id(id)
+            .expect("Buffer is large enough for truncated id");
+        message.into()
+    }
+
+    fn stream_configuration(&self) -> Reply {
+        let mut message = StreamConfigurationNotification::<[u32; REPLY_SIZE]>::new();
+        message.set_protocol(self.configuration.protocol.to_u8());
+        message.set_receive_jr_timestamps(self.configuration.receive_jr_timestamps);
+        message.set_send_jr_timestamps(self.configuration.send_jr_timestamps);
+        message.into()
+    }
+}
+
+fn function_block_info(index: usize, block: &FunctionBlockDescriptor) -> Reply {
+    let mut message = FunctionBlockInfo::<[u32; REPLY_SIZE]>::new();
+    message.set_active(block.active);
+    message.set_function_block_number(u7::new(index as u8));
+    message.set_first_group(block.first_group);
+    message.set_number_of_groups_spanned(block.number_of_groups_spanned);
+    message.set_midi_ci_version(block.midi_ci_version);
+    message.set_max_number_of_midi_ci_streams(block.max_number_of_midi_ci_streams);
+    message.set_ui_hint(block.ui_hint);
+    message.set_midi1_port(block.midi1_port);
+    message.set_direction(block.direction);
+    message.into()
+}
+
+fn function_block_name(index: usize, name: &str) -> Reply {
+    let mut message = FunctionBlockName::<[u32; REPLY_SIZE]>::new();
+    message
+        .try_set_name(truncate(name, MAX_FUNCTION_BLOCK_NAME_SIZE))
+        .expect("Buffer is large enough for truncated name");
+    message.set_function_block(index as u8);
+    message.into()
+}
+
+fn truncate(s: &str, max_size: usize) -> &str {
+    if s.len() <= max_size {
+        return s;
+    }
+    let mut end = max_size;
+    while !s.is_char_boundary(end) {
+        end -= 1;
+    }
+    &s[..end]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        traits::Data,
+        ump_stream::{EndpointDiscovery, FunctionBlockDiscovery, StreamConfigurationRequest},
+    };
+    use pretty_assertions::assert_eq;
+
+    const FUNCTION_BLOCKS: [FunctionBlockDescriptor; 2] = [
+        FunctionBlockDescriptor {
+            active: true,
+            first_group: u4::new(0x0),
+            number_of_groups_spanned: 1,
+            midi_ci_version: 0x2,
+            max_number_of_midi_ci_streams: 0x1,
+            ui_hint: UiHint::Sender,
+            midi1_port: None,
+            direction: Direction::Output,
+            name: Some("Keys"),
+        },
+        FunctionBlockDescriptor {
+            active: false,
+            first_group: u4::new(0x1),
+            number_of_groups_spanned: 2,
+            midi_ci_version: 0x0,
+            max_number_of_midi_ci_streams: 0x0,
+            ui_hint: UiHint::Receiver,
+            midi1_port: Some(Midi1Port::DontRestrictBandwidth),
+            direction: Direction::Input,
+            name: None,
+        },
+    ];
+
+    fn descriptor() -> EndpointDescriptor<'static> {
+        EndpointDescriptor {
+            supports_midi1_protocol: true,
+            supports_receiving_jr_timestamps: true,
+            device_manufacturer: crate::ManufacturerId::Extended(u7::new(0x21), u7::new(0x09)),
+            device_family: u14::new(0x1234),
+            device_family_model_number: u14::new(0x0567),
+            software_version: [u7::new(0x1), u7::new(0x2), u7::new(0x3), u7::new(0x4)],
+            name: Some("Synth"),
+            product_instance_id: None,
+            function_blocks: &FUNCTION_BLOCKS,
+            ..Default::default()
+        }
+    }
+
+    fn endpoint_discovery(filter: u8) -> UmpStream<[u32; 4]> {
+        let mut message = EndpointDiscovery::<[u32; 4]>::new();
+        message.set_request_endpoint_info(filter & 0b1 != 0);
+        message.set_request_device_identity(filter & 0b10 != 0);
+        message.set_request_endpoint_name(filter & 0b100 != 0);
+        message.set_request_product_instance_id(filter & 0b1000 != 0);
+        message.set_request_stream_configuration(filter & 0b1_0000 != 0);
+        message.into()
+    }
+
+    fn function_block_discovery(number: u8, info: bool, name: bool) -> UmpStream<[u32; 4]> {
+        let mut message = FunctionBlockDiscovery::<[u32; 4]>::new();
+        message.set_function_block_number(number);
+        message.set_requesting_function_block_info(info);
+        message.set_requesting_function_block_name(name);
+        message.into()
+    }
+
+    #[test]
+    fn endpoint_info() {
+        let mut responder = EndpointResponder::new(descriptor());
+        let mut replies = responder.respond(&endpoint_discovery(0b1));
+        assert_eq!(replies.next().unwrap().data(), &[0xF001_0101, 0x0200_0302]);
+        assert_eq!(replies.next(), None);
+    }
+
+    #[test]
+    fn full_endpoint_discovery_skips_missing_product_instance_id() {
+        let mut responder = EndpointResponder::new(descriptor());
+        let replies = responder.respond(&endpoint_discovery(0b1_1111));
+        let replies: std::vec::Vec<_> = replies.collect();
+        assert_eq!(replies.len(), 4);
+        assert!(matches!(replies[0], UmpStream::EndpointInfo(_)));
+        assert!(matches!(replies[1], UmpStream::DeviceIdentity(_)));
+        assert!(matches!(replies[2], UmpStream::EndpointName(_)));
+        assert!(matches!(
+            replies[3],
+            UmpStream::StreamConfigurationNotification(_)
+        ));
+    }
+
+    #[test]
+    fn device_identity() {
+        let mut responder = EndpointResponder::new(descriptor());
+        let mut replies = responder.respond(&endpoint_discovery(0b10));
+        assert_eq!(
+            replies.next().unwrap().data(),
+            &[0xF002_0000, 0x0000_2109, 0x3424_670A, 0x0102_0304]
+        );
+    }
+
+    #[test]
+    fn endpoint_name() {
+        let mut responder = EndpointResponder::new(descriptor());
+        let mut replies = responder.respond(&endpoint_discovery(0b100));
+        assert_eq!(
+            replies.next().unwrap().data(),
+            &[0xF003_5379, 0x6E74_6800, 0x0, 0x0]
+        );
+    }
+
+    #[test]
+    fn long_endpoint_name_is_truncated() {
+        let name = "0123456789".repeat(10);
+        let mut responder = EndpointResponder::new(EndpointDescriptor {
+            name: Some(&name),
+            ..Default::default()
+        });
+        let mut replies = responder.respond(&endpoint_discovery(0b100));
+        assert_eq!(replies.next().unwrap().data().len(), REPLY_SIZE);
+    }
+
+    #[test]
+    fn all_function_blocks() {
+        let mut responder = EndpointResponder::new(descriptor());
+        let replies: std::vec::Vec<_> = responder
+            .respond(&function_block_discovery(0xFF, true, true))
+            .collect();
+        assert_eq!(replies.len(), 3);
+        assert_eq!(replies[0].data(), &[0xF011_8022, 0x0001_0201]);
+        assert_eq!(replies[1].data(), &[0xF012_004B, 0x6579_7300, 0x0, 0x0]);
+        assert_eq!(replies[2].data(), &[0xF011_0115, 0x0102_0000]);
+    }
+
+    #[test]
+    fn single_function_block_name() {
+        let mut responder = EndpointResponder::new(descriptor());
+        let replies: std::vec::Vec<_> = responder
+            .respond(&function_block_discovery(0x0, false, true))
+            .collect();
+        assert_eq!(replies.len(), 1);
+        assert!(matches!(replies[0], UmpStream::FunctionBlockName(_)));
+    }
+
+    #[test]
+    fn unknown_function_block() {
+        let mut responder = EndpointResponder::new(descriptor());
+        assert_eq!(
+            responder
+                .respond(&function_block_discovery(0x2, true, true))
+                .next(),
+            None
+        );
+    }
+
+    #[test]
+    fn stream_configuration_request() {
+        let mut responder = EndpointResponder::new(descriptor());
+        let mut request = StreamConfigurationRequest::<[u32; 4]>::new();
+        request.set_protocol(0x1);
+        request.set_receive_jr_timestamps(true);
+        request.set_send_jr_timestamps(true);
+
+        let mut replies = responder.respond(&request.into());
+        assert_eq!(replies.next().unwrap().data(), &[0xF006_0102]);
+        assert_eq!(replies.next(), None);
+        assert_eq!(
+            responder.configuration(),
+            StreamConfiguration {
+                protocol: Protocol::Midi1,
+                receive_jr_timestamps: true,
+                send_jr_timestamps: false,
+            }
+        );
+    }
+
+    #[test]
+    fn unsupported_protocol_is_not_applied() {
+        let mut responder = EndpointResponder::new(EndpointDescriptor::default());
+        let mut request = StreamConfigurationRequest::<[u32; 4]>::new();
+        request.set_protocol(0x1);
+
+        let mut replies = responder.respond(&request.into());
+        assert_eq!(replies.next().unwrap().data(), &[0xF006_0200]);
+        assert_eq!(responder.configuration().protocol, Protocol::Midi2);
+    }
+
+    #[test]
+    fn other_messages_have_no_replies() {
+        let mut responder = EndpointResponder::new(descriptor());
+        let message: UmpStream<[u32; 4]> = crate::ump_stream::StartOfClip::<[u32; 4]>::new().into();
+        assert_eq!(responder.respond(&message).next(), None);
+    }
+}
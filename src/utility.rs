@@ -166,6 +166,7 @@ impl<B: crate::buffer::Ump + crate::buffer::BufferMut> crate::detail::property::
     derive_more::From,
     midi2_proc::Data,
     midi2_proc::Packets,
+    midi2_proc::Remappable,
     midi2_proc::RebufferFrom,
     midi2_proc::RebufferFromArray,
     midi2_proc::TryRebufferFrom,